@@ -0,0 +1,113 @@
+use crate::ChessBoard;
+use crate::ChessGamestate;
+use crate::ChessPiece;
+use crate::ChessPieceColor;
+use crate::ChessPieceKind;
+use crate::ChessPoint;
+
+// Chess960 (or Fischer Random Chess) starts from one of 960 shuffled back ranks, with the Bishops on opposite colors
+// and the King somewhere between the Rooks, so that castling is still possible on both sides
+// Black's back rank mirrors White's, and the Pawns start where they usually do
+impl ChessGamestate {
+    // The number of Chess960 starting positions
+    pub const CHESS960_POSITION_COUNT: usize = 960;
+
+    // Creates a gamestate from a Chess960 starting position, or None if the index is not below 960
+    // The positions are numbered as in Scharnagl's scheme, in which index 518 is the standard starting position
+    pub fn new_chess960(index: usize) -> Option<Self> {
+        use ChessPieceColor::*;
+        use ChessPieceKind::*;
+
+        if index >= Self::CHESS960_POSITION_COUNT {
+            return None;
+        }
+
+        let back_rank = chess960_back_rank(index);
+
+        let mut pieces = [[None; 8]; 8];
+        pieces[0] = back_rank.map(|kind| Some(ChessPiece::new(kind, White)));
+        pieces[1] = [Some(ChessPiece::new(Pawn, White)); 8];
+        pieces[6] = [Some(ChessPiece::new(Pawn, Black)); 8];
+        pieces[7] = back_rank.map(|kind| Some(ChessPiece::new(kind, Black)));
+
+        let king_file = back_rank.iter().position(|kind| *kind == King)?;
+
+        // The Rook left of the King castles queenside, and the Rook right of it castles kingside
+        let queenside_rook_file = back_rank.iter().position(|kind| *kind == Rook)?;
+        let kingside_rook_file = back_rank.iter().rposition(|kind| *kind == Rook)?;
+
+        let mut gamestate = ChessGamestate::new();
+
+        gamestate.board = ChessBoard::from_pieces(pieces);
+        gamestate.white_king_position = ChessPoint::new(king_file, 0);
+        gamestate.black_king_position = ChessPoint::new(king_file, 7);
+        gamestate.white_kingside_rook_file = kingside_rook_file;
+        gamestate.white_queenside_rook_file = queenside_rook_file;
+        gamestate.black_kingside_rook_file = kingside_rook_file;
+        gamestate.black_queenside_rook_file = queenside_rook_file;
+        gamestate.chess960 = true;
+
+        gamestate.refresh_hash();
+
+        Some(gamestate)
+    }
+}
+
+// Gets the back rank of a Chess960 starting position from its index, from the a-file to the h-file
+// The index is split into the light-squared Bishop's file, the dark-squared Bishop's file, the Queen's place
+// among the empty tiles and the Knights' places among the empty tiles; the Rooks and King fill the rest in that order
+fn chess960_back_rank(index: usize) -> [ChessPieceKind; 8] {
+    use ChessPieceKind::*;
+
+    // The places of the two Knights among the five tiles left after the Bishops and Queen
+    const KNIGHT_PLACES: [(usize, usize); 10] = [
+        (0, 1),
+        (0, 2),
+        (0, 3),
+        (0, 4),
+        (1, 2),
+        (1, 3),
+        (1, 4),
+        (2, 3),
+        (2, 4),
+        (3, 4),
+    ];
+
+    let mut back_rank: [Option<ChessPieceKind>; 8] = [None; 8];
+    let mut remaining_index = index;
+
+    // Light tiles on the first rank are on the b-, d-, f- and h-files, and dark tiles on the a-, c-, e- and g-files
+    back_rank[remaining_index % 4 * 2 + 1] = Some(Bishop);
+    remaining_index /= 4;
+
+    back_rank[remaining_index % 4 * 2] = Some(Bishop);
+    remaining_index /= 4;
+
+    let place_on_empty_tile = |back_rank: &mut [Option<ChessPieceKind>; 8], place, kind| {
+        let file = (0..8)
+            .filter(|file| back_rank[*file].is_none())
+            .nth(place)
+            .unwrap_or_else(|| {
+                unreachable!("[INTERNAL ERROR] Chess960 back rank ran out of tiles")
+            });
+
+        back_rank[file] = Some(kind);
+    };
+
+    place_on_empty_tile(&mut back_rank, remaining_index % 6, Queen);
+    remaining_index /= 6;
+
+    // The second Knight's place is counted before the first Knight is placed, so it is shifted back by one
+    let (first_knight_place, second_knight_place) = KNIGHT_PLACES[remaining_index];
+    place_on_empty_tile(&mut back_rank, first_knight_place, Knight);
+    place_on_empty_tile(&mut back_rank, second_knight_place - 1, Knight);
+
+    for kind in [Rook, King, Rook] {
+        place_on_empty_tile(&mut back_rank, 0, kind);
+    }
+
+    back_rank.map(|kind| match kind {
+        Some(kind) => kind,
+        None => unreachable!("[INTERNAL ERROR] Chess960 back rank has an empty tile"),
+    })
+}
@@ -0,0 +1,220 @@
+use crate::ChessPieceColor;
+use crate::ChessPoint;
+
+// Attack sets are bitboards: each bit is set if the tile with that index (a1 = 0, h8 = 63) is attacked
+// The leaper tables and the sliding rays are calculated at compile time
+
+const KNIGHT_OFFSETS: [(i32, i32); 8] = [
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+
+const KING_OFFSETS: [(i32, i32); 8] = [
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+];
+
+// The directions that sliding pieces move in
+// The first four increase the tile index and the last four decrease it, which decides
+// whether the nearest blocker on a ray is its lowest or highest set bit
+const RAY_DIRECTIONS: [(i32, i32); 8] = [
+    (0, 1),
+    (1, 0),
+    (1, 1),
+    (-1, 1),
+    (0, -1),
+    (-1, 0),
+    (1, -1),
+    (-1, -1),
+];
+
+static KNIGHT_ATTACKS: [u64; 64] = leaper_attacks(&KNIGHT_OFFSETS);
+static KING_ATTACKS: [u64; 64] = leaper_attacks(&KING_OFFSETS);
+static WHITE_PAWN_ATTACKS: [u64; 64] = leaper_attacks(&[(-1, 1), (1, 1)]);
+static BLACK_PAWN_ATTACKS: [u64; 64] = leaper_attacks(&[(-1, -1), (1, -1)]);
+static RAYS: [[u64; 64]; 8] = rays();
+static BETWEEN: [[u64; 64]; 64] = between_tiles();
+
+// The indices of the ray directions that each sliding piece moves in
+const ROOK_RAYS: [usize; 4] = [0, 1, 4, 5];
+const BISHOP_RAYS: [usize; 4] = [2, 3, 6, 7];
+
+// Gets the tiles attacked by a Knight on the given tile
+pub(crate) fn knight_attacks(point: &ChessPoint) -> u64 {
+    KNIGHT_ATTACKS[point.index()]
+}
+
+// Gets the tiles attacked by a King on the given tile, not including castling
+pub(crate) fn king_attacks(point: &ChessPoint) -> u64 {
+    KING_ATTACKS[point.index()]
+}
+
+// Gets the tiles diagonally in front of a Pawn of the given color on the given tile
+pub(crate) fn pawn_attacks(color: ChessPieceColor, point: &ChessPoint) -> u64 {
+    match color {
+        ChessPieceColor::White => WHITE_PAWN_ATTACKS[point.index()],
+        ChessPieceColor::Black => BLACK_PAWN_ATTACKS[point.index()],
+    }
+}
+
+// Gets the tiles attacked by a Rook on the given tile, given the tiles occupied by any piece
+pub(crate) fn rook_attacks(point: &ChessPoint, occupied: u64) -> u64 {
+    sliding_attacks(point, occupied, &ROOK_RAYS)
+}
+
+// Gets the tiles attacked by a Bishop on the given tile, given the tiles occupied by any piece
+pub(crate) fn bishop_attacks(point: &ChessPoint, occupied: u64) -> u64 {
+    sliding_attacks(point, occupied, &BISHOP_RAYS)
+}
+
+// Gets the tiles attacked by a Queen on the given tile, given the tiles occupied by any piece
+pub(crate) fn queen_attacks(point: &ChessPoint, occupied: u64) -> u64 {
+    rook_attacks(point, occupied) | bishop_attacks(point, occupied)
+}
+
+// Gets the tiles strictly between two tiles on the same rank, file or diagonal
+// Tiles which are not lined up have nothing between them
+pub(crate) fn between(source: &ChessPoint, destination: &ChessPoint) -> u64 {
+    BETWEEN[source.index()][destination.index()]
+}
+
+// Gets the points of every set bit in a bitboard, from a1 to h8
+pub(crate) fn points(mut bitboard: u64) -> impl Iterator<Item = ChessPoint> {
+    std::iter::from_fn(move || {
+        if bitboard == 0 {
+            return None;
+        }
+
+        let index = bitboard.trailing_zeros() as usize;
+        bitboard &= bitboard - 1;
+
+        Some(ChessPoint::from_index(index))
+    })
+}
+
+// Gets the bitboard with only the given point set
+pub(crate) fn bit(point: &ChessPoint) -> u64 {
+    1 << point.index()
+}
+
+// Follows each ray until it reaches the nearest occupied tile, which is included in the attacks
+// This is the classical approach, which needs neither magic numbers nor the PEXT instruction
+fn sliding_attacks(point: &ChessPoint, occupied: u64, ray_indices: &[usize; 4]) -> u64 {
+    let mut attacks = 0;
+
+    for &ray_index in ray_indices {
+        let ray = RAYS[ray_index][point.index()];
+        let blockers = ray & occupied;
+
+        attacks |= ray;
+
+        if blockers == 0 {
+            continue;
+        }
+
+        // The tiles behind the nearest blocker are exactly the blocker's own ray in the same direction
+        let nearest_blocker = match ray_index < 4 {
+            true => blockers.trailing_zeros(),
+            false => 63 - blockers.leading_zeros(),
+        };
+
+        attacks &= !RAYS[ray_index][nearest_blocker as usize];
+    }
+
+    attacks
+}
+
+// Calculates the tiles reachable from each tile with a single jump by any of the given offsets
+const fn leaper_attacks(offsets: &[(i32, i32)]) -> [u64; 64] {
+    let mut attacks = [0; 64];
+    let mut index = 0;
+
+    while index < 64 {
+        let mut offset_index = 0;
+
+        while offset_index < offsets.len() {
+            let x = (index % 8) as i32 + offsets[offset_index].0;
+            let y = (index / 8) as i32 + offsets[offset_index].1;
+
+            if x >= 0 && x < 8 && y >= 0 && y < 8 {
+                attacks[index] |= 1 << (8 * y + x);
+            }
+
+            offset_index += 1;
+        }
+
+        index += 1;
+    }
+
+    attacks
+}
+
+// Calculates the tiles in each direction from each tile, up to the edge of the board
+const fn rays() -> [[u64; 64]; 8] {
+    let mut rays = [[0; 64]; 8];
+    let mut ray_index = 0;
+
+    while ray_index < 8 {
+        let (change_in_x, change_in_y) = RAY_DIRECTIONS[ray_index];
+        let mut index = 0;
+
+        while index < 64 {
+            let mut x = (index % 8) as i32 + change_in_x;
+            let mut y = (index / 8) as i32 + change_in_y;
+
+            while x >= 0 && x < 8 && y >= 0 && y < 8 {
+                rays[ray_index][index] |= 1 << (8 * y + x);
+                x += change_in_x;
+                y += change_in_y;
+            }
+
+            index += 1;
+        }
+
+        ray_index += 1;
+    }
+
+    rays
+}
+
+// Calculates the tiles between each pair of tiles, by walking outwards from each tile in every direction
+const fn between_tiles() -> [[u64; 64]; 64] {
+    let mut between = [[0; 64]; 64];
+    let mut index = 0;
+
+    while index < 64 {
+        let mut ray_index = 0;
+
+        while ray_index < 8 {
+            let (change_in_x, change_in_y) = RAY_DIRECTIONS[ray_index];
+            let mut x = (index % 8) as i32 + change_in_x;
+            let mut y = (index / 8) as i32 + change_in_y;
+            let mut passed_tiles = 0;
+
+            while x >= 0 && x < 8 && y >= 0 && y < 8 {
+                between[index][(8 * y + x) as usize] = passed_tiles;
+                passed_tiles |= 1 << (8 * y + x);
+                x += change_in_x;
+                y += change_in_y;
+            }
+
+            ray_index += 1;
+        }
+
+        index += 1;
+    }
+
+    between
+}
@@ -3,9 +3,19 @@ use crate::ChessPieceColor;
 use crate::ChessPieceKind;
 use crate::ChessPoint;
 
+use std::ops::Deref;
+use std::ops::DerefMut;
+
+// The board keeps both an array of pieces, which allows pieces to be looked up by tile,
+// and bitboards of the tiles occupied by each piece kind and color, which allow fast attack tests
+// Both are updated together by set_piece(), so the pieces can only be changed through it or through piece_at_mut()
 #[derive(Clone, Copy)]
 pub struct ChessBoard {
-    pub pieces: [[Option<ChessPiece>; 8]; 8],
+    pieces: [[Option<ChessPiece>; 8]; 8],
+    // The tiles occupied by each piece kind, in the order of ChessPieceKind
+    kind_bitboards: [u64; 6],
+    // The tiles occupied by each color, White first
+    color_bitboards: [u64; 2],
 }
 
 impl ChessBoard {
@@ -16,52 +26,171 @@ impl ChessBoard {
 
         const EMPTY_ROW: [Option<ChessPiece>; 8] = [None; 8];
 
+        ChessBoard::from_pieces([
+            [
+                ChessPiece::new(Rook, White),
+                ChessPiece::new(Knight, White),
+                ChessPiece::new(Bishop, White),
+                ChessPiece::new(Queen, White),
+                ChessPiece::new(King, White),
+                ChessPiece::new(Bishop, White),
+                ChessPiece::new(Knight, White),
+                ChessPiece::new(Rook, White),
+            ]
+            .map(Some),
+            [ChessPiece::new(Pawn, White); 8].map(Some),
+            EMPTY_ROW,
+            EMPTY_ROW,
+            EMPTY_ROW,
+            EMPTY_ROW,
+            [ChessPiece::new(Pawn, Black); 8].map(Some),
+            [
+                ChessPiece::new(Rook, Black),
+                ChessPiece::new(Knight, Black),
+                ChessPiece::new(Bishop, Black),
+                ChessPiece::new(Queen, Black),
+                ChessPiece::new(King, Black),
+                ChessPiece::new(Bishop, Black),
+                ChessPiece::new(Knight, Black),
+                ChessPiece::new(Rook, Black),
+            ]
+            .map(Some),
+        ])
+    }
+
+    // Creates a board with no pieces on it
+    pub fn empty() -> Self {
         ChessBoard {
-            pieces: [
-                [
-                    ChessPiece::new(Rook, White),
-                    ChessPiece::new(Knight, White),
-                    ChessPiece::new(Bishop, White),
-                    ChessPiece::new(Queen, White),
-                    ChessPiece::new(King, White),
-                    ChessPiece::new(Bishop, White),
-                    ChessPiece::new(Knight, White),
-                    ChessPiece::new(Rook, White),
-                ]
-                .map(Some),
-                [ChessPiece::new(Pawn, White); 8].map(Some),
-                EMPTY_ROW,
-                EMPTY_ROW,
-                EMPTY_ROW,
-                EMPTY_ROW,
-                [ChessPiece::new(Pawn, Black); 8].map(Some),
-                [
-                    ChessPiece::new(Rook, Black),
-                    ChessPiece::new(Knight, Black),
-                    ChessPiece::new(Bishop, Black),
-                    ChessPiece::new(Queen, Black),
-                    ChessPiece::new(King, Black),
-                    ChessPiece::new(Bishop, Black),
-                    ChessPiece::new(Knight, Black),
-                    ChessPiece::new(Rook, Black),
-                ]
-                .map(Some),
-            ],
+            pieces: [[None; 8]; 8],
+            kind_bitboards: [0; 6],
+            color_bitboards: [0; 2],
+        }
+    }
+
+    // Creates a board from an array of pieces, indexed by y and then x
+    pub fn from_pieces(pieces: [[Option<ChessPiece>; 8]; 8]) -> Self {
+        let mut board = ChessBoard::empty();
+
+        for (y, row) in pieces.iter().enumerate() {
+            for (x, piece) in row.iter().enumerate() {
+                board.set_piece(&ChessPoint::new(x, y), *piece);
+            }
         }
+
+        board
     }
 
     // Sets a given tile on the board to a given piece (or empties the tile if given None)
     pub fn set_piece(&mut self, point: &ChessPoint, piece: Option<ChessPiece>) {
+        let point_bit = 1 << point.index();
+
+        if let Some(removed_piece) = self.pieces[point.y()][point.x()] {
+            self.kind_bitboards[kind_index(removed_piece.kind)] &= !point_bit;
+            self.color_bitboards[color_index(removed_piece.color)] &= !point_bit;
+        }
+
+        if let Some(placed_piece) = piece {
+            self.kind_bitboards[kind_index(placed_piece.kind)] |= point_bit;
+            self.color_bitboards[color_index(placed_piece.color)] |= point_bit;
+        }
+
         self.pieces[point.y()][point.x()] = piece;
     }
 
     // Returns the piece at a given point, if there is one
-    // Mutable version is included below
     pub fn piece_at(&self, point: &ChessPoint) -> Option<&ChessPiece> {
         self.pieces[point.y()][point.x()].as_ref()
     }
 
-    pub fn piece_at_mut(&mut self, point: &ChessPoint) -> Option<&mut ChessPiece> {
-        self.pieces[point.y()][point.x()].as_mut()
+    // Returns the piece at a given point, if there is one, which can be changed in place
+    // The bitboards are brought up to date with the changed piece once the returned ChessPieceMut is dropped
+    pub fn piece_at_mut(&mut self, point: &ChessPoint) -> Option<ChessPieceMut<'_>> {
+        let piece = self.pieces[point.y()][point.x()]?;
+
+        Some(ChessPieceMut {
+            board: self,
+            point: *point,
+            piece,
+        })
+    }
+
+    // Gets the array of pieces, indexed by y and then x
+    // This is the read-only replacement for the pieces field, which is private so that it cannot leave the bitboards behind
+    pub fn pieces(&self) -> &[[Option<ChessPiece>; 8]; 8] {
+        &self.pieces
+    }
+
+    // Gets the tiles occupied by pieces of the given kind and color, as a bitboard where a1 is bit 0 and h8 is bit 63
+    pub fn pieces_bitboard(&self, kind: ChessPieceKind, color: ChessPieceColor) -> u64 {
+        self.kind_bitboards[kind_index(kind)] & self.color_bitboards[color_index(color)]
+    }
+
+    // Gets the tiles occupied by pieces of the given kind, of either color
+    pub fn kind_bitboard(&self, kind: ChessPieceKind) -> u64 {
+        self.kind_bitboards[kind_index(kind)]
+    }
+
+    // Gets the tiles occupied by pieces of the given color
+    pub fn color_bitboard(&self, color: ChessPieceColor) -> u64 {
+        self.color_bitboards[color_index(color)]
+    }
+
+    // Gets the tiles occupied by any piece
+    pub fn occupied_bitboard(&self) -> u64 {
+        self.color_bitboards[0] | self.color_bitboards[1]
+    }
+}
+
+// A piece on the board which is being changed in place, from ChessBoard::piece_at_mut()
+// It dereferences to the piece, and writes the piece back to the board through set_piece() when it is dropped
+pub struct ChessPieceMut<'a> {
+    board: &'a mut ChessBoard,
+    point: ChessPoint,
+    piece: ChessPiece,
+}
+
+impl Deref for ChessPieceMut<'_> {
+    type Target = ChessPiece;
+
+    fn deref(&self) -> &ChessPiece {
+        &self.piece
+    }
+}
+
+impl DerefMut for ChessPieceMut<'_> {
+    fn deref_mut(&mut self) -> &mut ChessPiece {
+        &mut self.piece
+    }
+}
+
+impl Drop for ChessPieceMut<'_> {
+    fn drop(&mut self) {
+        self.board.set_piece(&self.point, Some(self.piece));
+    }
+}
+
+fn kind_index(kind: ChessPieceKind) -> usize {
+    use ChessPieceKind::*;
+
+    match kind {
+        Pawn => 0,
+        Rook => 1,
+        Knight => 2,
+        Bishop => 3,
+        Queen => 4,
+        King => 5,
+    }
+}
+
+fn color_index(color: ChessPieceColor) -> usize {
+    match color {
+        ChessPieceColor::White => 0,
+        ChessPieceColor::Black => 1,
+    }
+}
+
+impl Default for ChessBoard {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -0,0 +1,227 @@
+use crate::ChessClockBonus;
+use crate::ChessGameStatus;
+use crate::ChessGamestate;
+use crate::ChessPieceColor;
+use crate::ChessTimeControl;
+use crate::ChessTimeControlStage;
+
+use std::time::Duration;
+use std::time::Instant;
+
+// A chess clock for a game played under a time control
+// Only the clock of the player whose turn it is runs, and it is switched over by pressing it after each move
+// The current time is passed in rather than read by the clock, so that the clock can be driven by any source of time
+pub struct ChessClock {
+    time_control: ChessTimeControl,
+    white_side: ChessClockSide,
+    black_side: ChessClockSide,
+    // The color whose clock runs, or would run once the clock is started
+    turn_color: ChessPieceColor,
+    // When the current turn started, or None if the clock has not been started
+    turn_start: Option<Instant>,
+    // The color which ran out of time, after which the clock stops
+    flagged_color: Option<ChessPieceColor>,
+}
+
+// The state of one player's side of the clock
+#[derive(Clone, Copy)]
+struct ChessClockSide {
+    // The time left as of the start of the player's turn, or the end of their last turn
+    time_left: Duration,
+    // The index of the time control stage the player is in
+    stage_index: usize,
+    // The moves the player must still make in the stage, or None if the stage lasts for the rest of the game
+    moves_left_in_stage: Option<u32>,
+}
+
+impl ChessClock {
+    // Creates a stopped clock, set to the first stage of the time control, on which the given color moves first
+    pub fn new(time_control: ChessTimeControl, turn_color: ChessPieceColor) -> Self {
+        let first_stage = match time_control.stages().first() {
+            Some(stage) => *stage,
+            None => unreachable!("[INTERNAL ERROR] Time control has no stages"),
+        };
+
+        let side = ChessClockSide {
+            time_left: first_stage.base_time,
+            stage_index: 0,
+            moves_left_in_stage: first_stage.moves,
+        };
+
+        ChessClock {
+            time_control,
+            white_side: side,
+            black_side: side,
+            turn_color,
+            turn_start: None,
+            flagged_color: None,
+        }
+    }
+
+    // Starts the clock of the player whose turn it is
+    pub fn start(&mut self, now: Instant) {
+        if self.turn_start.is_none() {
+            self.turn_start = Some(now);
+        }
+    }
+
+    // Checks if the clock has been started and no player has run out of time
+    pub fn is_running(&self) -> bool {
+        self.turn_start.is_some() && self.flagged_color.is_none()
+    }
+
+    // Gets the color whose clock is running, or would run once the clock is started
+    pub fn turn_color(&self) -> ChessPieceColor {
+        self.turn_color
+    }
+
+    // Ends the turn of the player whose turn it is, adding their bonus time and starting their opponent's clock
+    // If the player ran out of time before pressing the clock, they lose on time and the clock stops instead
+    // Returns whether the player still had time left
+    pub fn press(&mut self, now: Instant) -> bool {
+        if !self.is_running() {
+            return self.flagged_color.is_none();
+        }
+
+        if self.flagged(now).is_some() {
+            return false;
+        }
+
+        let time_used = self.turn_time(now);
+        let stage = self.current_stage(self.turn_color);
+
+        let side = self.side_mut(self.turn_color);
+        side.time_left -= time_used;
+
+        // Bronstein delay gives back the time used, up to the delay, so the clock can never gain time on a move
+        side.time_left += match stage.bonus {
+            ChessClockBonus::Increment(increment) => increment,
+            ChessClockBonus::Bronstein(delay) => time_used.min(delay),
+            ChessClockBonus::None | ChessClockBonus::Delay(_) => Duration::ZERO,
+        };
+
+        self.complete_move();
+
+        self.turn_color = match self.turn_color {
+            ChessPieceColor::White => ChessPieceColor::Black,
+            ChessPieceColor::Black => ChessPieceColor::White,
+        };
+
+        self.turn_start = Some(now);
+
+        true
+    }
+
+    // Gets the time a player has left
+    pub fn time_left(&self, color: ChessPieceColor, now: Instant) -> Duration {
+        let side = self.side(color);
+
+        match color == self.turn_color && self.is_running() {
+            true => side.time_left.saturating_sub(self.turn_time(now)),
+            false => side.time_left,
+        }
+    }
+
+    // Gets the color which has run out of time, if either has
+    // Only the player whose turn it is can run out of time, and once they have, their clock stays at zero
+    pub fn flagged(&mut self, now: Instant) -> Option<ChessPieceColor> {
+        if self.is_running() && self.time_left(self.turn_color, now).is_zero() {
+            self.side_mut(self.turn_color).time_left = Duration::ZERO;
+            self.flagged_color = Some(self.turn_color);
+        }
+
+        self.flagged_color
+    }
+
+    // Gets the status of a game played with this clock, which ends once a player runs out of time
+    // A player who runs out of time loses, unless their opponent could not possibly checkmate them, which is a draw
+    pub fn status(&mut self, gamestate: &ChessGamestate, now: Instant) -> ChessGameStatus {
+        let gamestate_status = gamestate.status();
+
+        // A game which has already ended on the board cannot be lost on time afterwards
+        if gamestate_status.is_over() {
+            return gamestate_status;
+        }
+
+        let flagged_color = match self.flagged(now) {
+            Some(flagged_color) => flagged_color,
+            None => return gamestate_status,
+        };
+
+        let opponent_color = match flagged_color {
+            ChessPieceColor::White => ChessPieceColor::Black,
+            ChessPieceColor::Black => ChessPieceColor::White,
+        };
+
+        match gamestate.has_mating_material(opponent_color) {
+            true => ChessGameStatus::Timeout(opponent_color),
+            false => ChessGameStatus::TimeoutVsInsufficientMaterial,
+        }
+    }
+
+    // Gets the bonus time a player gets for each move in their current stage
+    pub fn bonus(&self, color: ChessPieceColor) -> ChessClockBonus {
+        self.current_stage(color).bonus
+    }
+
+    // Gets the number of moves a player must make before the next time control, if there is one
+    pub fn moves_to_go(&self, color: ChessPieceColor) -> Option<u32> {
+        self.side(color).moves_left_in_stage
+    }
+
+    // Gets the time that counts against the clock of the player whose turn it is, which excludes a simple delay
+    fn turn_time(&self, now: Instant) -> Duration {
+        let turn_start = match self.turn_start {
+            Some(turn_start) => turn_start,
+            None => return Duration::ZERO,
+        };
+
+        let turn_time = now.saturating_duration_since(turn_start);
+
+        match self.current_stage(self.turn_color).bonus {
+            ChessClockBonus::Delay(delay) => turn_time.saturating_sub(delay),
+            _ => turn_time,
+        }
+    }
+
+    // Counts a move made by the player whose turn it is, moving them on to the next stage once they finish theirs
+    fn complete_move(&mut self) {
+        let stages = self.time_control.stages().to_vec();
+        let side = self.side_mut(self.turn_color);
+
+        let moves_left_in_stage = match side.moves_left_in_stage {
+            Some(moves_left_in_stage) => moves_left_in_stage - 1,
+            None => return,
+        };
+
+        if moves_left_in_stage > 0 {
+            side.moves_left_in_stage = Some(moves_left_in_stage);
+            return;
+        }
+
+        // The last stage is repeated, as it only lasts for a number of moves if the stages never run out
+        side.stage_index = (side.stage_index + 1).min(stages.len() - 1);
+
+        let next_stage = stages[side.stage_index];
+        side.time_left += next_stage.base_time;
+        side.moves_left_in_stage = next_stage.moves;
+    }
+
+    fn current_stage(&self, color: ChessPieceColor) -> ChessTimeControlStage {
+        self.time_control.stages()[self.side(color).stage_index]
+    }
+
+    fn side(&self, color: ChessPieceColor) -> &ChessClockSide {
+        match color {
+            ChessPieceColor::White => &self.white_side,
+            ChessPieceColor::Black => &self.black_side,
+        }
+    }
+
+    fn side_mut(&mut self, color: ChessPieceColor) -> &mut ChessClockSide {
+        match color {
+            ChessPieceColor::White => &mut self.white_side,
+            ChessPieceColor::Black => &mut self.black_side,
+        }
+    }
+}
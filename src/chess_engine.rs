@@ -0,0 +1,462 @@
+use crate::chess_evaluation;
+use crate::ChessBound;
+use crate::ChessGamestate;
+use crate::ChessMove;
+use crate::ChessPieceKind;
+use crate::ChessSearchLimits;
+use crate::ChessSearchResult;
+use crate::ChessTranspositionTable;
+
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Instant;
+
+// A computer opponent, which picks moves with an iterative-deepening alpha-beta search
+// The engine keeps its move ordering tables between searches, so one engine should be used for a whole game
+pub struct ChessEngine {
+    // Two quiet moves per ply which recently caused a cutoff, which are likely to cause one again in sibling positions
+    killer_moves: Vec<[Option<ChessMove>; 2]>,
+    // How often each quiet move (by source and destination index) has caused a cutoff, weighted by depth
+    history_scores: Vec<[u32; 64]>,
+    // The best line found at each ply of the current iteration
+    principal_variations: Vec<Vec<ChessMove>>,
+    // The best line of the previous iteration, which is searched first
+    previous_principal_variation: Vec<ChessMove>,
+    // Results of earlier searches of each position, which are kept between searches
+    transposition_table: ChessTranspositionTable,
+    nodes: u64,
+    deadline: Option<Instant>,
+    // Can be set from another thread to stop the search early, as if the time had run out
+    stop_signal: Arc<AtomicBool>,
+    // Set once the time runs out, after which every result of the current iteration is discarded
+    aborted: bool,
+}
+
+// Scores used to order moves, so that the moves most likely to be best are searched first
+const TRANSPOSITION_MOVE_ORDER: i32 = 2_000_000;
+const PRINCIPAL_VARIATION_ORDER: i32 = 1_000_000;
+const CAPTURE_ORDER: i32 = 100_000;
+const FIRST_KILLER_ORDER: i32 = 90_000;
+const SECOND_KILLER_ORDER: i32 = 80_000;
+
+// How many positions are visited between checks of the clock
+const NODES_PER_TIME_CHECK: u64 = 1024;
+
+impl ChessEngine {
+    // The score of delivering checkmate immediately; a mate further away scores one less for each ply
+    pub const MATE_SCORE: i32 = 30_000;
+    // The deepest the search will go, including captures searched beyond the depth limit
+    pub const MAX_PLY: usize = 64;
+
+    // Bounds the search window, and is larger than any real score
+    const INFINITY: i32 = 32_000;
+
+    pub fn new() -> Self {
+        Self::with_hash_size(ChessTranspositionTable::DEFAULT_SIZE_MB)
+    }
+
+    // Creates an engine whose transposition table takes up about the given number of megabytes
+    pub fn with_hash_size(size_mb: usize) -> Self {
+        ChessEngine {
+            killer_moves: vec![[None; 2]; Self::MAX_PLY + 1],
+            history_scores: vec![[0; 64]; 64],
+            principal_variations: vec![Vec::new(); Self::MAX_PLY + 1],
+            previous_principal_variation: Vec::new(),
+            transposition_table: ChessTranspositionTable::new(size_mb),
+            nodes: 0,
+            deadline: None,
+            stop_signal: Arc::new(AtomicBool::new(false)),
+            aborted: false,
+        }
+    }
+
+    // Searches for the best move for the player whose turn it is, within the given limits
+    // Each completed depth is searched from scratch with the results of the previous one used to order the moves,
+    // so that a search which runs out of time can fall back on the deepest completed result
+    pub fn search(
+        &mut self,
+        gamestate: &ChessGamestate,
+        limits: &ChessSearchLimits,
+    ) -> ChessSearchResult {
+        self.search_with_progress(gamestate, limits, |_| {})
+    }
+
+    // Searches like search(), but also reports the result of each completed iteration as soon as it is known
+    pub fn search_with_progress(
+        &mut self,
+        gamestate: &ChessGamestate,
+        limits: &ChessSearchLimits,
+        mut report_iteration: impl FnMut(&ChessSearchResult),
+    ) -> ChessSearchResult {
+        let mut gamestate = gamestate.clone();
+        let legal_moves = gamestate.legal_moves();
+
+        self.start_search(limits);
+
+        // If the time runs out during the first iteration, any legal move is better than none
+        let mut result = ChessSearchResult {
+            best_move: legal_moves.first().copied(),
+            score: 0,
+            depth: 0,
+            nodes: 0,
+            hashfull: 0,
+            principal_variation: Vec::new(),
+        };
+
+        // With only one legal move, there is nothing to search for
+        if legal_moves.len() <= 1 {
+            return result;
+        }
+
+        let max_depth = limits.depth.unwrap_or(Self::MAX_PLY as u32).max(1);
+
+        for depth in 1..=max_depth {
+            let score = self.negamax(&mut gamestate, depth, 0, -Self::INFINITY, Self::INFINITY);
+
+            if self.aborted {
+                break;
+            }
+
+            self.previous_principal_variation = self.principal_variations[0].clone();
+
+            result = ChessSearchResult {
+                best_move: self.previous_principal_variation.first().copied(),
+                score,
+                depth,
+                nodes: self.nodes,
+                hashfull: self.transposition_table.hashfull(),
+                principal_variation: self.previous_principal_variation.clone(),
+            };
+
+            report_iteration(&result);
+
+            // A forced mate cannot be improved on by searching deeper
+            if result
+                .mate_in()
+                .is_some_and(|moves_to_mate| moves_to_mate > 0)
+            {
+                break;
+            }
+        }
+
+        result.nodes = self.nodes;
+
+        result
+    }
+
+    // Replaces the transposition table with an empty one of about the given number of megabytes
+    pub fn set_hash_size(&mut self, size_mb: usize) {
+        self.transposition_table = ChessTranspositionTable::new(size_mb);
+    }
+
+    // Forgets the results of earlier searches, such as when a new game starts
+    pub fn clear_hash(&mut self) {
+        self.transposition_table.clear();
+    }
+
+    // Gets the signal which stops the search when it is set, so that a search running on another thread can be stopped
+    // The engine never clears the signal itself, so it must be cleared before the next search
+    pub fn stop_signal(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop_signal)
+    }
+
+    // Resets the per-search state, keeping the move ordering tables from earlier searches
+    fn start_search(&mut self, limits: &ChessSearchLimits) {
+        self.nodes = 0;
+        self.aborted = false;
+        self.deadline = limits.move_time.map(|move_time| Instant::now() + move_time);
+        self.previous_principal_variation.clear();
+        self.killer_moves.fill([None; 2]);
+        self.transposition_table.new_search();
+
+        // Old history scores are halved so that they do not drown out what is learned in the new position
+        for scores in self.history_scores.iter_mut() {
+            for score in scores.iter_mut() {
+                *score /= 2;
+            }
+        }
+    }
+
+    // Finds the score of the position for the player whose turn it is, searching the given number of plies ahead
+    // Scores at or above beta are refuted by the opponent earlier in the line, so the search stops looking as soon as one is found
+    fn negamax(
+        &mut self,
+        gamestate: &mut ChessGamestate,
+        depth: u32,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        self.principal_variations[ply].clear();
+
+        if ply > 0 && Self::is_drawn(gamestate) {
+            return 0;
+        }
+
+        let in_check = gamestate.is_in_check();
+
+        // Lines ending in check are searched one ply deeper, so that checkmates are not missed at the horizon
+        let depth = match in_check {
+            true => depth + 1,
+            false => depth,
+        };
+
+        if depth == 0 || ply >= Self::MAX_PLY {
+            return self.quiescence(gamestate, ply, alpha, beta);
+        }
+
+        self.visit_node();
+
+        let hash = gamestate.hash();
+        let mut transposition_move = None;
+
+        if let Some(entry) = self.transposition_table.probe(hash) {
+            transposition_move = entry.best_move();
+
+            // The root is always searched, so that there is a best line to report
+            if ply > 0 && entry.depth() >= depth {
+                let score = entry.score(ply);
+
+                let score_is_usable = match entry.bound() {
+                    ChessBound::Exact => true,
+                    ChessBound::Lower => score >= beta,
+                    ChessBound::Upper => score <= alpha,
+                };
+
+                if score_is_usable {
+                    return score;
+                }
+            }
+        }
+
+        let mut moves = gamestate.legal_moves();
+
+        if moves.is_empty() {
+            return match in_check {
+                true => -Self::MATE_SCORE + ply as i32,
+                false => 0,
+            };
+        }
+
+        self.order_moves(gamestate, &mut moves, ply, transposition_move);
+
+        let original_alpha = alpha;
+        let mut best_score = -Self::INFINITY;
+        let mut best_move = None;
+
+        for searched_move in moves {
+            let move_is_quiet = Self::is_quiet(gamestate, &searched_move);
+
+            gamestate.perform_validated_move(&searched_move);
+            let score = -self.negamax(gamestate, depth - 1, ply + 1, -beta, -alpha);
+            gamestate.unmake_move();
+
+            if self.aborted {
+                return 0;
+            }
+
+            best_score = best_score.max(score);
+
+            if score > alpha {
+                alpha = score;
+                best_move = Some(searched_move);
+                self.update_principal_variation(ply, searched_move);
+            }
+
+            if alpha >= beta {
+                if move_is_quiet {
+                    self.record_cutoff(ply, depth, &searched_move);
+                }
+
+                break;
+            }
+        }
+
+        let bound = if best_score >= beta {
+            ChessBound::Lower
+        } else if best_score > original_alpha {
+            ChessBound::Exact
+        } else {
+            ChessBound::Upper
+        };
+
+        self.transposition_table
+            .store(hash, depth, bound, best_score, best_move, ply);
+
+        best_score
+    }
+
+    // Searches only captures and promotions until the position is quiet, so that the evaluation
+    // is never taken in the middle of an exchange
+    // The player whose turn it is may also "stand pat" and decline every capture, so the static evaluation is a lower bound
+    fn quiescence(
+        &mut self,
+        gamestate: &mut ChessGamestate,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        self.principal_variations[ply].clear();
+        self.visit_node();
+
+        let stand_pat_score = gamestate.evaluate();
+
+        if stand_pat_score >= beta || ply >= Self::MAX_PLY {
+            return stand_pat_score;
+        }
+
+        alpha = alpha.max(stand_pat_score);
+
+        // Under-promotions are almost never better than promoting to a Queen, and would only slow the search down
+        let mut moves: Vec<ChessMove> = gamestate
+            .legal_captures()
+            .into_iter()
+            .filter(|capture| matches!(capture.promotion(), None | Some(ChessPieceKind::Queen)))
+            .collect();
+
+        self.order_moves(gamestate, &mut moves, ply, None);
+
+        let mut best_score = stand_pat_score;
+
+        for searched_move in moves {
+            gamestate.perform_validated_move(&searched_move);
+            let score = -self.quiescence(gamestate, ply + 1, -beta, -alpha);
+            gamestate.unmake_move();
+
+            if self.aborted {
+                return 0;
+            }
+
+            best_score = best_score.max(score);
+
+            if score > alpha {
+                alpha = score;
+                self.update_principal_variation(ply, searched_move);
+            }
+
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        best_score
+    }
+
+    // Counts a visited position, and checks whether the search has run out of time
+    fn visit_node(&mut self) {
+        self.nodes += 1;
+
+        if self.nodes.is_multiple_of(NODES_PER_TIME_CHECK) {
+            let out_of_time = self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline);
+
+            if out_of_time || self.stop_signal.load(Ordering::Relaxed) {
+                self.aborted = true;
+            }
+        }
+    }
+
+    // Checks for draws by repetition, the fifty-move rule or insufficient material
+    // A single repetition is treated as a draw, as the player who could avoid it would otherwise have done so
+    fn is_drawn(gamestate: &ChessGamestate) -> bool {
+        gamestate.halfmove_clock >= 100
+            || gamestate.repetition_count() >= 2
+            || gamestate.has_insufficient_material()
+    }
+
+    // Sorts moves so that the most promising are searched first, which lets alpha-beta skip more of the rest
+    // The best move stored in the transposition table for the position, if any, is searched first of all
+    fn order_moves(
+        &self,
+        gamestate: &ChessGamestate,
+        moves: &mut [ChessMove],
+        ply: usize,
+        transposition_move: Option<ChessMove>,
+    ) {
+        moves.sort_by_cached_key(|ordered_move| match transposition_move {
+            Some(transposition_move) if transposition_move == *ordered_move => {
+                -TRANSPOSITION_MOVE_ORDER
+            }
+            _ => -self.move_order_score(gamestate, ordered_move, ply),
+        });
+    }
+
+    fn move_order_score(
+        &self,
+        gamestate: &ChessGamestate,
+        scored_move: &ChessMove,
+        ply: usize,
+    ) -> i32 {
+        if self.previous_principal_variation.get(ply) == Some(scored_move) {
+            return PRINCIPAL_VARIATION_ORDER;
+        }
+
+        // Captures are ordered by Most Valuable Victim, then Least Valuable Attacker
+        if !Self::is_quiet(gamestate, scored_move) {
+            let victim_value = gamestate
+                .get_captured_piece(scored_move)
+                .map_or(0, |piece| chess_evaluation::piece_value(piece.kind));
+
+            let attacker_value = gamestate
+                .board
+                .piece_at(scored_move.source())
+                .map_or(0, |piece| chess_evaluation::piece_value(piece.kind));
+
+            let promotion_value = scored_move
+                .promotion()
+                .map_or(0, chess_evaluation::piece_value);
+
+            return CAPTURE_ORDER + 10 * (victim_value + promotion_value) - attacker_value / 10;
+        }
+
+        match self.killer_moves[ply] {
+            [Some(killer_move), _] if killer_move == *scored_move => FIRST_KILLER_ORDER,
+            [_, Some(killer_move)] if killer_move == *scored_move => SECOND_KILLER_ORDER,
+            _ => {
+                let history_score = self.history_scores[scored_move.source().index()]
+                    [scored_move.destination().index()];
+
+                history_score.min(SECOND_KILLER_ORDER as u32 - 1) as i32
+            }
+        }
+    }
+
+    // Checks if a move neither captures nor promotes
+    fn is_quiet(gamestate: &ChessGamestate, queried_move: &ChessMove) -> bool {
+        queried_move.promotion().is_none() && gamestate.get_captured_piece(queried_move).is_none()
+    }
+
+    // Remembers a quiet move which caused a cutoff, so that it is searched early in similar positions
+    fn record_cutoff(&mut self, ply: usize, depth: u32, cutoff_move: &ChessMove) {
+        let killer_moves = &mut self.killer_moves[ply];
+
+        if killer_moves[0] != Some(*cutoff_move) {
+            killer_moves[1] = killer_moves[0];
+            killer_moves[0] = Some(*cutoff_move);
+        }
+
+        let history_score = &mut self.history_scores[cutoff_move.source().index()]
+            [cutoff_move.destination().index()];
+
+        *history_score = history_score.saturating_add(depth * depth);
+    }
+
+    // Makes the best line at a ply the given move followed by the best line found after it
+    fn update_principal_variation(&mut self, ply: usize, best_move: ChessMove) {
+        let (current_variations, deeper_variations) =
+            self.principal_variations.split_at_mut(ply + 1);
+        let principal_variation = &mut current_variations[ply];
+
+        principal_variation.clear();
+        principal_variation.push(best_move);
+        principal_variation.extend_from_slice(&deeper_variations[0]);
+    }
+}
+
+impl Default for ChessEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,8 +1,181 @@
+use crate::ChessFenError;
+use crate::ChessGameStatus;
+use crate::ChessMove;
+use crate::ChessParseError;
+use crate::ChessPieceColor;
+use crate::ChessPieceKind;
+use crate::ChessSanError;
+
+use std::error::Error;
+use std::fmt;
+
+// Everything that can go wrong when entering or performing a move
+// Move errors carry the move that was rejected, along with the kind of piece involved where it is relevant
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ChessError {
-    InvalidMovePattern,
-    MoveCollisionOccurs,
-    CannotCaptureFriendly,
-    CannotSelfCheck,
-    EnemyPieceAtMoveSource,
-    NoPieceAtMoveSource,
+    // The moved piece of the given kind cannot move in the pattern of the move
+    InvalidMovePattern(ChessMove, ChessPieceKind),
+    // The moved piece of the given kind would have to pass through another piece
+    MoveCollisionOccurs(ChessMove, ChessPieceKind),
+    // The destination holds a friendly piece of the given kind
+    CannotCaptureFriendly(ChessMove, ChessPieceKind),
+    CannotSelfCheck(ChessMove),
+    // The source holds an enemy piece of the given kind
+    EnemyPieceAtMoveSource(ChessMove, ChessPieceKind),
+    NoPieceAtMoveSource(ChessMove),
+    NoCastlingRights(ChessMove),
+    CannotCastleOutOfCheck(ChessMove),
+    CannotCastleThroughCheck(ChessMove),
+    PromotionPieceRequired(ChessMove),
+    UnexpectedPromotionPiece(ChessMove),
+    // The Pawn cannot be promoted to the given kind of piece
+    InvalidPromotionPiece(ChessMove, ChessPieceKind),
+    // No moves can be made because the game has ended
+    GameOver(ChessGameStatus),
+    // A draw was claimed, but neither threefold repetition nor the fifty-move rule applies
+    NoDrawToClaim,
+    // A move could not be parsed from a pair of tile positions
+    Parse(ChessParseError),
+    // A move could not be found from Standard Algebraic Notation
+    San(ChessSanError),
+    // A position could not be loaded from a FEN string
+    Fen(ChessFenError),
+}
+
+impl fmt::Display for ChessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ChessError::*;
+
+        match self {
+            InvalidMovePattern(attempted_move, kind) => write!(
+                f,
+                "A {} cannot move from {} to {}.",
+                kind,
+                attempted_move.source(),
+                attempted_move.destination()
+            ),
+            MoveCollisionOccurs(attempted_move, kind) => write!(
+                f,
+                "The {} on {} cannot reach {}, as pieces other than Knights cannot move through other pieces.",
+                kind,
+                attempted_move.source(),
+                attempted_move.destination()
+            ),
+            CannotCaptureFriendly(attempted_move, kind) => write!(
+                f,
+                "You cannot capture your own {} on {}.",
+                kind,
+                attempted_move.destination()
+            ),
+            CannotSelfCheck(attempted_move) => {
+                write!(f, "You cannot move into check ({}).", attempted_move)
+            }
+            EnemyPieceAtMoveSource(attempted_move, kind) => write!(
+                f,
+                "You cannot move the enemy {} on {}.",
+                kind,
+                attempted_move.source()
+            ),
+            NoPieceAtMoveSource(attempted_move) => {
+                write!(f, "There is no piece on {}.", attempted_move.source())
+            }
+            NoCastlingRights(_) => write!(f, "You can no longer castle on that side."),
+            CannotCastleOutOfCheck(_) => write!(f, "You cannot castle while in check."),
+            CannotCastleThroughCheck(attempted_move) => write!(
+                f,
+                "You cannot castle to {}, as the King would pass through an attacked tile.",
+                attempted_move.destination()
+            ),
+            PromotionPieceRequired(attempted_move) => write!(
+                f,
+                "You must choose a piece to promote to, like \"{} {} q\".",
+                attempted_move.source(),
+                attempted_move.destination()
+            ),
+            UnexpectedPromotionPiece(_) => {
+                write!(f, "Only Pawns reaching the last rank can promote.")
+            }
+            InvalidPromotionPiece(_, kind) => write!(
+                f,
+                "Pawns cannot promote to a {}, only to a Queen, Rook, Bishop or Knight.",
+                kind
+            ),
+            GameOver(ChessGameStatus::Checkmate(winner)) => write!(
+                f,
+                "The game is over; {} won by checkmate.",
+                match winner {
+                    ChessPieceColor::White => "White",
+                    ChessPieceColor::Black => "Black",
+                }
+            ),
+            GameOver(ChessGameStatus::Stalemate) => {
+                write!(f, "The game is over; it was drawn by stalemate.")
+            }
+            GameOver(ChessGameStatus::FivefoldRepetition) => write!(
+                f,
+                "The game is over; it was drawn by fivefold repetition."
+            ),
+            GameOver(ChessGameStatus::InsufficientMaterial) => write!(
+                f,
+                "The game is over; it was drawn as neither player can checkmate."
+            ),
+            GameOver(ChessGameStatus::SeventyFiveMoveRule) => {
+                write!(f, "The game is over; it was drawn by the 75-move rule.")
+            }
+            GameOver(ChessGameStatus::DrawClaimed(_)) => {
+                write!(f, "The game is over; a draw was claimed.")
+            }
+            GameOver(ChessGameStatus::Timeout(winner)) => write!(
+                f,
+                "The game is over; {} won on time.",
+                match winner {
+                    ChessPieceColor::White => "White",
+                    ChessPieceColor::Black => "Black",
+                }
+            ),
+            GameOver(ChessGameStatus::TimeoutVsInsufficientMaterial) => write!(
+                f,
+                "The game is over; it was drawn as a player ran out of time but could not be checkmated."
+            ),
+            GameOver(ChessGameStatus::Ongoing) => write!(f, "The game is over."),
+            NoDrawToClaim => write!(
+                f,
+                "A draw can only be claimed after threefold repetition or 50 moves without a capture or Pawn move."
+            ),
+            Parse(err) => err.fmt(f),
+            San(err) => err.fmt(f),
+            Fen(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for ChessError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use ChessError::*;
+
+        match self {
+            Parse(err) => Some(err),
+            San(err) => Some(err),
+            Fen(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ChessParseError> for ChessError {
+    fn from(err: ChessParseError) -> Self {
+        ChessError::Parse(err)
+    }
+}
+
+impl From<ChessSanError> for ChessError {
+    fn from(err: ChessSanError) -> Self {
+        ChessError::San(err)
+    }
+}
+
+impl From<ChessFenError> for ChessError {
+    fn from(err: ChessFenError) -> Self {
+        ChessError::Fen(err)
+    }
 }
@@ -0,0 +1,192 @@
+use crate::chess_attacks;
+use crate::ChessGamestate;
+use crate::ChessPieceColor;
+use crate::ChessPieceKind;
+use crate::ChessPoint;
+
+// The piece-square tables give a bonus or penalty in centipawns for a piece standing on each tile
+// They are written from White's point of view, with the 8th rank at the top, and mirrored for Black
+// These are the tables of Tomasz Michniewski's "Simplified Evaluation Function"
+
+#[rustfmt::skip]
+const PAWN_TABLE: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     50,  50,  50,  50,  50,  50,  50,  50,
+     10,  10,  20,  30,  30,  20,  10,  10,
+      5,   5,  10,  25,  25,  10,   5,   5,
+      0,   0,   0,  20,  20,   0,   0,   0,
+      5,  -5, -10,   0,   0, -10,  -5,   5,
+      5,  10,  10, -20, -20,  10,  10,   5,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const KNIGHT_TABLE: [i32; 64] = [
+    -50, -40, -30, -30, -30, -30, -40, -50,
+    -40, -20,   0,   0,   0,   0, -20, -40,
+    -30,   0,  10,  15,  15,  10,   0, -30,
+    -30,   5,  15,  20,  20,  15,   5, -30,
+    -30,   0,  15,  20,  20,  15,   0, -30,
+    -30,   5,  10,  15,  15,  10,   5, -30,
+    -40, -20,   0,   5,   5,   0, -20, -40,
+    -50, -40, -30, -30, -30, -30, -40, -50,
+];
+
+#[rustfmt::skip]
+const BISHOP_TABLE: [i32; 64] = [
+    -20, -10, -10, -10, -10, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,  10,  10,   5,   0, -10,
+    -10,   5,   5,  10,  10,   5,   5, -10,
+    -10,   0,  10,  10,  10,  10,   0, -10,
+    -10,  10,  10,  10,  10,  10,  10, -10,
+    -10,   5,   0,   0,   0,   0,   5, -10,
+    -20, -10, -10, -10, -10, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const ROOK_TABLE: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+      5,  10,  10,  10,  10,  10,  10,   5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+      0,   0,   0,   5,   5,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const QUEEN_TABLE: [i32; 64] = [
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,   5,   5,   5,   0, -10,
+     -5,   0,   5,   5,   5,   5,   0,  -5,
+      0,   0,   5,   5,   5,   5,   0,  -5,
+    -10,   5,   5,   5,   5,   5,   0, -10,
+    -10,   0,   5,   0,   0,   0,   0, -10,
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+
+// The King should stay sheltered while there are pieces to attack it, and become active once they are traded off
+#[rustfmt::skip]
+const KING_MIDDLEGAME_TABLE: [i32; 64] = [
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -20, -30, -30, -40, -40, -30, -30, -20,
+    -10, -20, -20, -20, -20, -20, -20, -10,
+     20,  20,   0,   0,   0,   0,  20,  20,
+     20,  30,  10,   0,   0,  10,  30,  20,
+];
+
+#[rustfmt::skip]
+const KING_ENDGAME_TABLE: [i32; 64] = [
+    -50, -40, -30, -20, -20, -30, -40, -50,
+    -30, -20, -10,   0,   0, -10, -20, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -30,   0,   0,   0,   0, -30, -30,
+    -50, -30, -30, -30, -30, -30, -30, -50,
+];
+
+// The game phase is the amount of non-Pawn material left, from 24 with every piece on the board down to 0
+const MAX_PHASE: i32 = 24;
+
+// Gets the material value of a piece kind in centipawns
+// The King has no material value, as it can never be traded
+pub(crate) fn piece_value(kind: ChessPieceKind) -> i32 {
+    use ChessPieceKind::*;
+
+    match kind {
+        Pawn => 100,
+        Knight => 320,
+        Bishop => 330,
+        Rook => 500,
+        Queen => 900,
+        King => 0,
+    }
+}
+
+impl ChessGamestate {
+    // Estimates how good the position is for the player whose turn it is, in centipawns
+    // This only counts material and where the pieces stand, so it is only meaningful for quiet positions
+    pub fn evaluate(&self) -> i32 {
+        use ChessPieceKind::*;
+
+        let mut white_score = 0;
+        let mut phase = 0;
+
+        for kind in [Pawn, Knight, Bishop, Rook, Queen] {
+            for color in [ChessPieceColor::White, ChessPieceColor::Black] {
+                let sign = color_sign(color);
+
+                for point in chess_attacks::points(self.board.pieces_bitboard(kind, color)) {
+                    white_score += sign * (piece_value(kind) + square_bonus(kind, color, &point));
+                    phase += phase_weight(kind);
+                }
+            }
+        }
+
+        // The King's table is blended between the middlegame and endgame tables by the material left
+        let phase = phase.min(MAX_PHASE);
+
+        for color in [ChessPieceColor::White, ChessPieceColor::Black] {
+            for point in chess_attacks::points(self.board.pieces_bitboard(King, color)) {
+                let table_index = table_index(color, &point);
+                let king_bonus = (KING_MIDDLEGAME_TABLE[table_index] * phase
+                    + KING_ENDGAME_TABLE[table_index] * (MAX_PHASE - phase))
+                    / MAX_PHASE;
+
+                white_score += color_sign(color) * king_bonus;
+            }
+        }
+
+        color_sign(self.turn_color) * white_score
+    }
+}
+
+fn square_bonus(kind: ChessPieceKind, color: ChessPieceColor, point: &ChessPoint) -> i32 {
+    use ChessPieceKind::*;
+
+    let table = match kind {
+        Pawn => &PAWN_TABLE,
+        Knight => &KNIGHT_TABLE,
+        Bishop => &BISHOP_TABLE,
+        Rook => &ROOK_TABLE,
+        Queen => &QUEEN_TABLE,
+        King => &KING_MIDDLEGAME_TABLE,
+    };
+
+    table[table_index(color, point)]
+}
+
+// Gets the index of a tile in a piece-square table, which lists the 8th rank first from White's point of view
+fn table_index(color: ChessPieceColor, point: &ChessPoint) -> usize {
+    match color {
+        ChessPieceColor::White => 8 * (7 - point.y()) + point.x(),
+        ChessPieceColor::Black => 8 * point.y() + point.x(),
+    }
+}
+
+// Gets how much a piece counts towards the game phase
+fn phase_weight(kind: ChessPieceKind) -> i32 {
+    use ChessPieceKind::*;
+
+    match kind {
+        Knight | Bishop => 1,
+        Rook => 2,
+        Queen => 4,
+        Pawn | King => 0,
+    }
+}
+
+fn color_sign(color: ChessPieceColor) -> i32 {
+    match color {
+        ChessPieceColor::White => 1,
+        ChessPieceColor::Black => -1,
+    }
+}
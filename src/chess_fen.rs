@@ -0,0 +1,544 @@
+use crate::ChessBoard;
+use crate::ChessGamestate;
+use crate::ChessPiece;
+use crate::ChessPieceColor;
+use crate::ChessPieceKind;
+use crate::ChessPoint;
+
+// The FEN string for the default starting position
+pub const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChessFenError {
+    WrongFieldCount,
+    WrongRankCount,
+    WrongFileCount,
+    InvalidPieceChar,
+    InvalidKingCount,
+    // A Pawn is on the first or eighth rank, which it can never stand on
+    InvalidPawnRank,
+    // The player who just moved left their own King in check, so the position cannot have been reached
+    OpponentInCheck,
+    InvalidTurnColor,
+    InvalidCastlingRights,
+    InvalidEnPassantTile,
+    InvalidHalfmoveClock,
+    InvalidFullmoveClock,
+}
+
+impl std::fmt::Display for ChessFenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        use ChessFenError::*;
+
+        write!(
+            f,
+            "{}",
+            match self {
+                WrongFieldCount => "A FEN string must have 4 or 6 space-separated fields.",
+                WrongRankCount => "The FEN piece placement must have 8 ranks.",
+                WrongFileCount => "Each rank of the FEN piece placement must have 8 files.",
+                InvalidPieceChar => "The FEN piece placement contains an unknown piece.",
+                InvalidKingCount => "Each color must have exactly one King.",
+                InvalidPawnRank => "Pawns cannot be on the first or eighth rank.",
+                OpponentInCheck => "The King of the player who is not to move cannot be in check.",
+                InvalidTurnColor => "The FEN turn color must be 'w' or 'b'.",
+                InvalidCastlingRights => {
+                    "The FEN castling rights must be '-', or some of 'KQkq' or the files of the castling Rooks, \
+                     each with its King on the back rank and a Rook on that side of it."
+                }
+                InvalidEnPassantTile => {
+                    "The FEN en passant tile must be an empty tile just behind a Pawn which has moved two tiles."
+                }
+                InvalidHalfmoveClock => "The FEN halfmove clock must be a number.",
+                InvalidFullmoveClock => "The FEN fullmove number must be a positive number.",
+            }
+        )
+    }
+}
+
+impl std::error::Error for ChessFenError {}
+
+impl ChessGamestate {
+    // Creates a gamestate from a FEN string, like the one in STARTING_FEN
+    // The halfmove and fullmove clocks may be omitted, in which case they default to 0 and 1
+    pub fn from_fen(fen: &str) -> Result<Self, ChessFenError> {
+        use ChessFenError::*;
+
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+
+        if fields.len() != 4 && fields.len() != 6 {
+            return Err(WrongFieldCount);
+        }
+
+        let board = parse_board(fields[0])?;
+
+        let turn_color = match fields[1] {
+            "w" => ChessPieceColor::White,
+            "b" => ChessPieceColor::Black,
+            _ => return Err(InvalidTurnColor),
+        };
+
+        let (white_king_position, black_king_position) = find_kings(&board)?;
+
+        // The gamestate starts from the default position so that it has an empty move history
+        let mut gamestate = ChessGamestate::new();
+
+        gamestate.board = board;
+        gamestate.turn_color = turn_color;
+        gamestate.white_king_position = white_king_position;
+        gamestate.black_king_position = black_king_position;
+        gamestate.white_castle_kingside = false;
+        gamestate.white_castle_queenside = false;
+        gamestate.black_castle_kingside = false;
+        gamestate.black_castle_queenside = false;
+
+        if fields[2] != "-" {
+            for castling_char in fields[2].chars() {
+                parse_castling_char(&mut gamestate, castling_char)?;
+            }
+        }
+
+        // Castling with a King or Rook off its usual file is only possible under the Chess960 rules
+        gamestate.chess960 = [
+            (
+                gamestate.white_castle_kingside,
+                gamestate.white_kingside_rook_file,
+                7,
+                white_king_position,
+            ),
+            (
+                gamestate.white_castle_queenside,
+                gamestate.white_queenside_rook_file,
+                0,
+                white_king_position,
+            ),
+            (
+                gamestate.black_castle_kingside,
+                gamestate.black_kingside_rook_file,
+                7,
+                black_king_position,
+            ),
+            (
+                gamestate.black_castle_queenside,
+                gamestate.black_queenside_rook_file,
+                0,
+                black_king_position,
+            ),
+        ]
+        .iter()
+        .any(
+            |(castling_right, rook_file, usual_rook_file, king_position)| {
+                *castling_right && (rook_file != usual_rook_file || king_position.x() != 4)
+            },
+        );
+
+        if fields[3] != "-" {
+            let en_passant_tile: ChessPoint =
+                fields[3].parse().map_err(|_| InvalidEnPassantTile)?;
+
+            if !is_valid_en_passant_tile(&gamestate.board, &en_passant_tile, turn_color) {
+                return Err(InvalidEnPassantTile);
+            }
+
+            gamestate.en_passant_tile = Some(en_passant_tile);
+        }
+
+        if fields.len() == 6 {
+            gamestate.halfmove_clock = fields[4].parse().map_err(|_| InvalidHalfmoveClock)?;
+            gamestate.fullmove_clock = match fields[5].parse() {
+                Ok(0) | Err(_) => return Err(InvalidFullmoveClock),
+                Ok(fullmove_clock) => fullmove_clock,
+            };
+        }
+
+        // The player who is not to move cannot be in check, as they would have had to move out of it
+        if gamestate.is_check() {
+            return Err(OpponentInCheck);
+        }
+
+        gamestate.refresh_hash();
+
+        Ok(gamestate)
+    }
+
+    // Encodes the gamestate as a FEN string
+    // Castling rights are written in X-FEN, which is the same as standard FEN apart from some Chess960 positions
+    pub fn to_fen(&self) -> String {
+        self.encode_fen(false)
+    }
+
+    // Encodes the gamestate as a Shredder-FEN string, which gives each castling right as the file of its Rook, like "HAha"
+    pub fn to_shredder_fen(&self) -> String {
+        self.encode_fen(true)
+    }
+
+    fn encode_fen(&self, shredder: bool) -> String {
+        let mut fen = String::new();
+
+        // Ranks are listed from the 8th to the 1st
+        for (y, row) in self.board.pieces().iter().enumerate().rev() {
+            let mut empty_tiles = 0;
+
+            for piece in row.iter() {
+                match piece {
+                    Some(piece) => {
+                        if empty_tiles > 0 {
+                            fen.push_str(&empty_tiles.to_string());
+                            empty_tiles = 0;
+                        }
+
+                        fen.push(get_fen_char(piece));
+                    }
+                    None => empty_tiles += 1,
+                }
+            }
+
+            if empty_tiles > 0 {
+                fen.push_str(&empty_tiles.to_string());
+            }
+
+            if y > 0 {
+                fen.push('/');
+            }
+        }
+
+        fen.push_str(match self.turn_color {
+            ChessPieceColor::White => " w ",
+            ChessPieceColor::Black => " b ",
+        });
+
+        let castling_rights = [
+            (
+                self.white_castle_kingside,
+                self.white_kingside_rook_file,
+                ChessPieceColor::White,
+                true,
+            ),
+            (
+                self.white_castle_queenside,
+                self.white_queenside_rook_file,
+                ChessPieceColor::White,
+                false,
+            ),
+            (
+                self.black_castle_kingside,
+                self.black_kingside_rook_file,
+                ChessPieceColor::Black,
+                true,
+            ),
+            (
+                self.black_castle_queenside,
+                self.black_queenside_rook_file,
+                ChessPieceColor::Black,
+                false,
+            ),
+        ];
+
+        let mut any_castling_rights = false;
+
+        for (castling_right, rook_file, color, castle_is_kingside) in castling_rights {
+            if !castling_right {
+                continue;
+            }
+
+            // X-FEN only names the Rook's file when another Rook is further out on the same side of the King
+            let uses_file_char = shredder
+                || outermost_rook_file(
+                    &self.board,
+                    color,
+                    self.king_file(color),
+                    castle_is_kingside,
+                ) != Some(rook_file);
+
+            let castling_char = match (uses_file_char, castle_is_kingside) {
+                (true, _) => (b'A' + rook_file as u8) as char,
+                (false, true) => 'K',
+                (false, false) => 'Q',
+            };
+
+            fen.push(match color {
+                ChessPieceColor::White => castling_char,
+                ChessPieceColor::Black => castling_char.to_ascii_lowercase(),
+            });
+
+            any_castling_rights = true;
+        }
+
+        if !any_castling_rights {
+            fen.push('-');
+        }
+
+        match self.en_passant_tile {
+            Some(tile) => fen.push_str(&format!(" {}", tile)),
+            None => fen.push_str(" -"),
+        }
+
+        fen.push_str(&format!(" {} {}", self.halfmove_clock, self.fullmove_clock));
+
+        fen
+    }
+}
+
+impl ChessGamestate {
+    fn king_file(&self, color: ChessPieceColor) -> usize {
+        match color {
+            ChessPieceColor::White => self.white_king_position.x(),
+            ChessPieceColor::Black => self.black_king_position.x(),
+        }
+    }
+}
+
+// Parses one character of the castling rights field, and sets the right and the file of its Rook
+// 'K' and 'Q' stand for the outermost Rook on that side of the King, as in X-FEN, while a file letter names the Rook's
+// file directly, as in Shredder-FEN; uppercase characters are White's rights and lowercase characters are Black's
+fn parse_castling_char(
+    gamestate: &mut ChessGamestate,
+    castling_char: char,
+) -> Result<(), ChessFenError> {
+    let color = match castling_char.is_ascii_uppercase() {
+        true => ChessPieceColor::White,
+        false => ChessPieceColor::Black,
+    };
+
+    let (king_position, back_rank) = match color {
+        ChessPieceColor::White => (gamestate.white_king_position, 0),
+        ChessPieceColor::Black => (gamestate.black_king_position, 7),
+    };
+
+    // A King can only castle from its own back rank
+    if king_position.y() != back_rank {
+        return Err(ChessFenError::InvalidCastlingRights);
+    }
+
+    let king_file = king_position.x();
+
+    let (castle_is_kingside, rook_file) = match castling_char.to_ascii_uppercase() {
+        'K' => (
+            true,
+            outermost_rook_file(&gamestate.board, color, king_file, true)
+                .ok_or(ChessFenError::InvalidCastlingRights)?,
+        ),
+        'Q' => (
+            false,
+            outermost_rook_file(&gamestate.board, color, king_file, false)
+                .ok_or(ChessFenError::InvalidCastlingRights)?,
+        ),
+        file_char @ 'A'..='H' => {
+            let rook_file = (file_char as u8 - b'A') as usize;
+
+            // The Rook must be on one side of the King or the other
+            let rook_is_present = gamestate
+                .board
+                .piece_at(&ChessPoint::new(rook_file, back_rank))
+                .is_some_and(|piece| piece.kind == ChessPieceKind::Rook && piece.color == color);
+
+            if rook_file == king_file || !rook_is_present {
+                return Err(ChessFenError::InvalidCastlingRights);
+            }
+
+            (rook_file > king_file, rook_file)
+        }
+        _ => return Err(ChessFenError::InvalidCastlingRights),
+    };
+
+    let (castling_right, castling_rook_file) = match (color, castle_is_kingside) {
+        (ChessPieceColor::White, true) => (
+            &mut gamestate.white_castle_kingside,
+            &mut gamestate.white_kingside_rook_file,
+        ),
+        (ChessPieceColor::White, false) => (
+            &mut gamestate.white_castle_queenside,
+            &mut gamestate.white_queenside_rook_file,
+        ),
+        (ChessPieceColor::Black, true) => (
+            &mut gamestate.black_castle_kingside,
+            &mut gamestate.black_kingside_rook_file,
+        ),
+        (ChessPieceColor::Black, false) => (
+            &mut gamestate.black_castle_queenside,
+            &mut gamestate.black_queenside_rook_file,
+        ),
+    };
+
+    // Each right may only be listed once
+    if *castling_right {
+        return Err(ChessFenError::InvalidCastlingRights);
+    }
+
+    *castling_right = true;
+    *castling_rook_file = rook_file;
+
+    Ok(())
+}
+
+// Finds the file of the Rook furthest from the King on one side of it, along the given color's back rank
+fn outermost_rook_file(
+    board: &ChessBoard,
+    color: ChessPieceColor,
+    king_file: usize,
+    kingside: bool,
+) -> Option<usize> {
+    let back_rank = match color {
+        ChessPieceColor::White => 0,
+        ChessPieceColor::Black => 7,
+    };
+
+    let is_rook = |file: &usize| {
+        board
+            .piece_at(&ChessPoint::new(*file, back_rank))
+            .is_some_and(|piece| piece.kind == ChessPieceKind::Rook && piece.color == color)
+    };
+
+    match kingside {
+        true => (king_file + 1..8).rev().find(is_rook),
+        false => (0..king_file).find(is_rook),
+    }
+}
+
+// Parses the piece placement field of a FEN string
+fn parse_board(placement: &str) -> Result<ChessBoard, ChessFenError> {
+    use ChessFenError::*;
+
+    let ranks: Vec<&str> = placement.split('/').collect();
+
+    if ranks.len() != 8 {
+        return Err(WrongRankCount);
+    }
+
+    let mut board = ChessBoard::empty();
+
+    // Ranks are listed from the 8th to the 1st
+    for (rank_index, rank) in ranks.iter().enumerate() {
+        let y = 7 - rank_index;
+        let mut x = 0;
+
+        for piece_char in rank.chars() {
+            if let Some(empty_tiles) = piece_char.to_digit(10) {
+                if !(1..=8).contains(&empty_tiles) {
+                    return Err(InvalidPieceChar);
+                }
+
+                x += empty_tiles as usize;
+                continue;
+            }
+
+            if x >= 8 {
+                return Err(WrongFileCount);
+            }
+
+            let point = ChessPoint::new(x, y);
+            let piece = parse_piece(piece_char, &point)?;
+
+            if piece.kind == ChessPieceKind::Pawn && (y == 0 || y == 7) {
+                return Err(InvalidPawnRank);
+            }
+
+            board.set_piece(&point, Some(piece));
+            x += 1;
+        }
+
+        if x != 8 {
+            return Err(WrongFileCount);
+        }
+    }
+
+    Ok(board)
+}
+
+// Parses a FEN piece character, where uppercase letters are White pieces and lowercase letters are Black pieces
+// Pieces on their starting tiles are treated as unmoved, so that Pawn double moves and castling remain possible
+fn parse_piece(piece_char: char, point: &ChessPoint) -> Result<ChessPiece, ChessFenError> {
+    let kind = ChessPieceKind::from_char(piece_char).ok_or(ChessFenError::InvalidPieceChar)?;
+
+    let color = match piece_char.is_ascii_uppercase() {
+        true => ChessPieceColor::White,
+        false => ChessPieceColor::Black,
+    };
+
+    let mut piece = ChessPiece::new(kind, color);
+
+    if !is_starting_point(&piece, point) {
+        piece.increment_move_count();
+    }
+
+    Ok(piece)
+}
+
+// Checks if a point is one of the tiles the given piece can start the game on
+fn is_starting_point(piece: &ChessPiece, point: &ChessPoint) -> bool {
+    use ChessPieceKind::*;
+
+    let (back_rank, pawn_rank) = match piece.color {
+        ChessPieceColor::White => (0, 1),
+        ChessPieceColor::Black => (7, 6),
+    };
+
+    let starting_files: &[usize] = match piece.kind {
+        Pawn => return point.y() == pawn_rank,
+        Rook => &[0, 7],
+        Knight => &[1, 6],
+        Bishop => &[2, 5],
+        Queen => &[3],
+        King => &[4],
+    };
+
+    point.y() == back_rank && starting_files.contains(&point.x())
+}
+
+// Checks that an en passant tile could have been left by the enemy's last move, which must have been a Pawn moving
+// two tiles from the tile behind the en passant tile, past it, to the tile in front of it
+fn is_valid_en_passant_tile(
+    board: &ChessBoard,
+    en_passant_tile: &ChessPoint,
+    turn_color: ChessPieceColor,
+) -> bool {
+    let (expected_rank, pawn_rank, starting_rank, enemy_color) = match turn_color {
+        ChessPieceColor::White => (5, 4, 6, ChessPieceColor::Black),
+        ChessPieceColor::Black => (2, 3, 1, ChessPieceColor::White),
+    };
+
+    if en_passant_tile.y() != expected_rank {
+        return false;
+    }
+
+    let x = en_passant_tile.x();
+
+    let pawn_is_in_front = board
+        .piece_at(&ChessPoint::new(x, pawn_rank))
+        .is_some_and(|piece| piece.kind == ChessPieceKind::Pawn && piece.color == enemy_color);
+
+    pawn_is_in_front
+        && board.piece_at(en_passant_tile).is_none()
+        && board.piece_at(&ChessPoint::new(x, starting_rank)).is_none()
+}
+
+// Finds the positions of both Kings, ensuring that each color has exactly one
+fn find_kings(board: &ChessBoard) -> Result<(ChessPoint, ChessPoint), ChessFenError> {
+    let mut white_kings = Vec::new();
+    let mut black_kings = Vec::new();
+
+    for (y, row) in board.pieces().iter().enumerate() {
+        for (x, piece) in row.iter().enumerate() {
+            match piece {
+                Some(piece) if piece.kind == ChessPieceKind::King => match piece.color {
+                    ChessPieceColor::White => white_kings.push(ChessPoint::new(x, y)),
+                    ChessPieceColor::Black => black_kings.push(ChessPoint::new(x, y)),
+                },
+                _ => {}
+            }
+        }
+    }
+
+    match (white_kings.as_slice(), black_kings.as_slice()) {
+        ([white_king], [black_king]) => Ok((*white_king, *black_king)),
+        _ => Err(ChessFenError::InvalidKingCount),
+    }
+}
+
+// Gets the FEN character for a piece, which is uppercase for White and lowercase for Black
+fn get_fen_char(piece: &ChessPiece) -> char {
+    match piece.color {
+        ChessPieceColor::White => piece.kind.get_kind_char(),
+        ChessPieceColor::Black => piece.kind.get_kind_char().to_ascii_lowercase(),
+    }
+}
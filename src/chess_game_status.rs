@@ -0,0 +1,49 @@
+use crate::ChessPieceColor;
+
+// The state of a game, as determined by the position on the board and the positions before it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChessGameStatus {
+    // The player whose turn it is has at least one legal move
+    Ongoing,
+    // The player whose turn it is has no legal moves and is in check; the given color has won
+    Checkmate(ChessPieceColor),
+    // The player whose turn it is has no legal moves but is not in check
+    Stalemate,
+    // The same position has occurred five times, which ends the game in a draw without a claim
+    FivefoldRepetition,
+    // 75 moves have been made by each player without a capture or Pawn move, which ends the game in a draw without a claim
+    SeventyFiveMoveRule,
+    // Neither player has enough material left to checkmate, so the position is dead
+    InsufficientMaterial,
+    // A player has claimed a draw for the given reason
+    DrawClaimed(ChessDrawClaim),
+    // The player whose turn it is has run out of time; the given color has won
+    // This is only given by a ChessClock, as the gamestate does not keep time
+    Timeout(ChessPieceColor),
+    // The player whose turn it is has run out of time, but their opponent does not have enough material to checkmate
+    TimeoutVsInsufficientMaterial,
+}
+
+// The reasons a player may claim a draw, which only end the game if a player chooses to claim them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChessDrawClaim {
+    // The same position has occurred three times
+    ThreefoldRepetition,
+    // 50 moves have been made by each player without a capture or Pawn move
+    FiftyMoveRule,
+}
+
+impl ChessGameStatus {
+    // Checks if the game has ended
+    pub fn is_over(&self) -> bool {
+        *self != ChessGameStatus::Ongoing
+    }
+
+    // Checks if the game has ended in a draw
+    pub fn is_draw(&self) -> bool {
+        !matches!(
+            self,
+            ChessGameStatus::Ongoing | ChessGameStatus::Checkmate(_) | ChessGameStatus::Timeout(_)
+        )
+    }
+}
@@ -1,12 +1,17 @@
+use crate::chess_attacks;
+use crate::chess_zobrist;
 use crate::ChessBoard;
+use crate::ChessDrawClaim;
 use crate::ChessError;
+use crate::ChessGameStatus;
 use crate::ChessMove;
+use crate::ChessMoveRecord;
 use crate::ChessPiece;
 use crate::ChessPieceColor;
 use crate::ChessPieceKind;
 use crate::ChessPoint;
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct ChessGamestate {
     // The array of pieces on the board
     pub board: ChessBoard,
@@ -20,18 +25,34 @@ pub struct ChessGamestate {
     pub white_castle_queenside: bool,
     pub black_castle_kingside: bool,
     pub black_castle_queenside: bool,
+    // The files of the Rooks that each castling right castles with, which are the a- and h-files except in Chess960
+    pub white_kingside_rook_file: usize,
+    pub white_queenside_rook_file: usize,
+    pub black_kingside_rook_file: usize,
+    pub black_queenside_rook_file: usize,
+    // Whether the game follows the Chess960 castling rules, where the King and Rooks may start on other files
+    // and a castling move is made by moving the King onto the Rook it castles with
+    pub chess960: bool,
     // The square where a pawn can be en passant-captured, if there is one
     pub en_passant_tile: Option<ChessPoint>,
     // Moves since the last capture or pawn move
     pub halfmove_clock: u32,
-    // Total number of moves in the game
+    // The number of the current full move, which starts at 1 and is incremented after each Black move
     pub fullmove_clock: u32,
+    // Every move performed so far, with the state needed to unmake it, from oldest to newest
+    history: Vec<ChessMoveRecord>,
+    // Moves which have been undone and can be redone, from newest to oldest
+    redo_moves: Vec<ChessMove>,
+    // The draw claimed by a player, which ends the game
+    draw_claim: Option<ChessDrawClaim>,
+    // The Zobrist hash of the position, which is updated incrementally as moves are made
+    hash: u64,
 }
 
 impl ChessGamestate {
     // Creates a gamestate from the default starting position
     pub fn new() -> Self {
-        ChessGamestate {
+        let mut gamestate = ChessGamestate {
             board: ChessBoard::new(),
             turn_color: ChessPieceColor::White,
             white_king_position: ChessPoint::new(4, 0),
@@ -40,10 +61,23 @@ impl ChessGamestate {
             white_castle_queenside: true,
             black_castle_kingside: true,
             black_castle_queenside: true,
+            white_kingside_rook_file: 7,
+            white_queenside_rook_file: 0,
+            black_kingside_rook_file: 7,
+            black_queenside_rook_file: 0,
+            chess960: false,
             en_passant_tile: None,
             halfmove_clock: 0,
-            fullmove_clock: 0,
-        }
+            fullmove_clock: 1,
+            history: Vec::new(),
+            redo_moves: Vec::new(),
+            draw_claim: None,
+            hash: 0,
+        };
+
+        gamestate.refresh_hash();
+
+        gamestate
     }
 
     // Checks if a move is legal, based on a combination of the moved piece and the gamestate variables
@@ -56,23 +90,29 @@ impl ChessGamestate {
         use ChessError::*;
 
         // Ensure that the source tile is not empty
-        let moved_piece = match self.board.piece_at(&queried_move.source()) {
+        let moved_piece = match self.board.piece_at(queried_move.source()) {
             Some(piece) => piece,
-            None => return Err(NoPieceAtMoveSource),
+            None => return Err(NoPieceAtMoveSource(*queried_move)),
         };
 
         // Because there may not be a captured piece, it must be stored as an Option and unwrapped later
-        let captured_piece = self.board.piece_at(&queried_move.destination());
+        let captured_piece = self.board.piece_at(queried_move.destination());
 
         // The move is a standard capture if there is a piece at the destination
         let move_is_standard_capture = captured_piece.is_some();
 
         // The move is an en passant capture if it is a pawn move to the en passant tile
-        let move_is_en_passant_capture = Self::is_en_passant_capture(queried_move, moved_piece, &self.en_passant_tile);
+        let move_is_en_passant_capture =
+            Self::is_en_passant_capture(queried_move, moved_piece, &self.en_passant_tile);
 
         // Ensure that the source tile does not contain an enemy piece
         if moved_piece.color != self.turn_color {
-            return Err(EnemyPieceAtMoveSource);
+            return Err(EnemyPieceAtMoveSource(*queried_move, moved_piece.kind));
+        }
+
+        // Castling moves the Rook along with the King, so it has its own requirements instead of the King's move pattern
+        if self.is_castling(queried_move) {
+            return self.validate_castling_move(queried_move, check_override);
         }
 
         // Ensure that the move pattern is legal for the piece
@@ -85,42 +125,141 @@ impl ChessGamestate {
             true => move_pattern_legality.capture,
             false => move_pattern_legality.standard,
         } {
-            return Err(InvalidMovePattern);
+            return Err(InvalidMovePattern(*queried_move, moved_piece.kind));
         }
 
         // Ensure that the piece does not collide with other pieces
-        if moved_piece.kind != ChessPieceKind::Knight {
-            let points_between =
-                ChessPoint::get_points_between(queried_move.source(), queried_move.destination());
-
-            for point in points_between {
-                if self.board.piece_at(&point).is_some() {
-                    return Err(MoveCollisionOccurs);
-                }
-            }
+        if moved_piece.kind != ChessPieceKind::Knight
+            && chess_attacks::between(queried_move.source(), queried_move.destination())
+                & self.board.occupied_bitboard()
+                != 0
+        {
+            return Err(MoveCollisionOccurs(*queried_move, moved_piece.kind));
         }
 
         // Ensure that the captured piece (if there is one) is an enemy piece
         if move_is_standard_capture {
             // Unwrap is safe here because there must be a destination piece for the move to be a capture
-            if captured_piece.unwrap().color == self.turn_color {
-                return Err(CannotCaptureFriendly);
+            let captured_piece = captured_piece.unwrap();
+
+            if captured_piece.color == self.turn_color {
+                return Err(CannotCaptureFriendly(*queried_move, captured_piece.kind));
+            }
+        }
+
+        // Ensure that a promotion piece is given if and only if a Pawn reaches the last rank
+        match (
+            Self::is_promotion_move(queried_move, moved_piece),
+            queried_move.promotion(),
+        ) {
+            (true, None) => return Err(PromotionPieceRequired(*queried_move)),
+            (true, Some(kind)) if !kind.is_promotion_target() => {
+                return Err(InvalidPromotionPiece(*queried_move, kind))
             }
+            (false, Some(_)) => return Err(UnexpectedPromotionPiece(*queried_move)),
+            _ => {}
         }
 
         // Ensure that the move does not put the friendly King in check
         if !check_override {
             // Copy the gamestate and perform the move on the copy
-            let mut hypothetical_gamestate = self.clone();
+            let mut hypothetical_gamestate = self.copy_position();
             hypothetical_gamestate.move_piece(queried_move);
 
+            // An en passant capture removes a Pawn from a tile other than the destination, which can expose the King
+            if move_is_en_passant_capture {
+                hypothetical_gamestate.update_for_en_passant_capture(queried_move);
+            }
+
             // A full gamestate update is unnecessary here, so the turn color and king positionsare updated individually
             // The color is updated in order to allow the is_check() validation calls to work properly without extra parameters
             hypothetical_gamestate.update_king_positions();
             hypothetical_gamestate.swap_turn_color();
 
             if hypothetical_gamestate.is_check() {
-                return Err(CannotSelfCheck);
+                return Err(CannotSelfCheck(*queried_move));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Checks the requirements of a castling move, assuming that is_castling() holds for it
+    // The King and Rook may pass over and land on each other's tiles, which matters in Chess960
+    fn validate_castling_move(
+        &self,
+        queried_move: &ChessMove,
+        check_override: bool,
+    ) -> Result<(), ChessError> {
+        use ChessError::*;
+
+        // Only Pawns promote, so a castling move cannot carry a promotion piece
+        if queried_move.promotion().is_some() {
+            return Err(UnexpectedPromotionPiece(*queried_move));
+        }
+
+        let castle_is_kingside = queried_move.change_in_x() > 0;
+
+        // Ensure that the castling right has not been lost
+        let castling_right_held = match (self.turn_color, castle_is_kingside) {
+            (ChessPieceColor::White, true) => self.white_castle_kingside,
+            (ChessPieceColor::White, false) => self.white_castle_queenside,
+            (ChessPieceColor::Black, true) => self.black_castle_kingside,
+            (ChessPieceColor::Black, false) => self.black_castle_queenside,
+        };
+
+        if !castling_right_held {
+            return Err(NoCastlingRights(*queried_move));
+        }
+
+        let (king_destination, rook_source, rook_destination) = self.castling_points(queried_move);
+
+        // In Chess960, the King must move onto the Rook that the right belongs to, rather than any other Rook
+        if self.chess960 && *queried_move.destination() != rook_source {
+            return Err(NoCastlingRights(*queried_move));
+        }
+
+        // Ensure that there is a friendly Rook to castle with
+        if !self.piece_is_at(&rook_source, ChessPieceKind::Rook, self.turn_color) {
+            return Err(NoCastlingRights(*queried_move));
+        }
+
+        // Every tile that the King or Rook passes over or lands on must be empty, apart from the King and Rook themselves
+        let castling_pieces =
+            chess_attacks::bit(queried_move.source()) | chess_attacks::bit(&rook_source);
+
+        let castling_path = chess_attacks::between(queried_move.source(), &king_destination)
+            | chess_attacks::bit(&king_destination)
+            | chess_attacks::between(&rook_source, &rook_destination)
+            | chess_attacks::bit(&rook_destination);
+
+        if castling_path & self.board.occupied_bitboard() & !castling_pieces != 0 {
+            return Err(MoveCollisionOccurs(*queried_move, ChessPieceKind::King));
+        }
+
+        // The King cannot castle out of check
+        if self.is_point_attacked(queried_move.source(), self.enemy_color()) {
+            return Err(CannotCastleOutOfCheck(*queried_move));
+        }
+
+        // The King cannot pass through an attacked tile
+        let passed_points = chess_attacks::between(queried_move.source(), &king_destination);
+
+        if chess_attacks::points(passed_points)
+            .any(|passed_point| self.is_point_attacked(&passed_point, self.enemy_color()))
+        {
+            return Err(CannotCastleThroughCheck(*queried_move));
+        }
+
+        // The King cannot land in check, which is tested after the move as the Rook may have been shielding its destination
+        if !check_override {
+            let mut hypothetical_gamestate = self.copy_position();
+            hypothetical_gamestate.move_piece(queried_move);
+            hypothetical_gamestate.update_king_positions();
+            hypothetical_gamestate.swap_turn_color();
+
+            if hypothetical_gamestate.is_check() {
+                return Err(CannotSelfCheck(*queried_move));
             }
         }
 
@@ -131,16 +270,18 @@ impl ChessGamestate {
     // This function should be called after validate_move() and move_piece()
     // Because the move has already been performed, the function must be informed of whether or not the
     // move was a capture in order to update the halfmove clock correctly
+    // For castling, the performed move must be given as the King's move to its destination, where it is found after the move
     fn update_gamestate(&mut self, performed_move: &ChessMove, move_was_capture: bool) {
         // As long as the move calling order is correct, the piece at the destination will be the moved piece
-        let moved_piece = match self.board.piece_at_mut(performed_move.destination()) {
-            Some(piece) => piece,
+        let mut moved_piece = match self.board.piece_at(performed_move.destination()) {
+            Some(piece) => *piece,
             None => {
                 unreachable!("[INTERNAL ERROR] Moved piece not found at destination after move")
             }
         };
 
         moved_piece.increment_move_count();
+        self.set_piece(performed_move.destination(), Some(moved_piece));
 
         // Check if a Pawn was moved to determine if the halfmove clock should be reset, and if the en passant functions should be called
         let moved_piece_was_pawn = moved_piece.kind == ChessPieceKind::Pawn;
@@ -153,24 +294,40 @@ impl ChessGamestate {
         // Only clears the en passant tile if the move did not already set it
         self.clear_en_passant_tile(performed_move);
 
+        if moved_piece_was_pawn {
+            self.update_for_promotion(performed_move);
+        }
+
         self.increment_move_clocks(move_was_capture || moved_piece_was_pawn);
 
         self.update_king_positions();
 
-        self.update_castling_rights();
+        self.update_castling_rights(moved_piece.kind == ChessPieceKind::King);
 
         self.swap_turn_color();
+
+        // The keys for the castling rights, en passant tile and turn color were removed by move_piece()
+        self.hash ^= self.state_hash();
     }
 
     // Performs a "simple move" - a piece is moved from one tile to another, without checking any validity requirements
+    // Castling moves also relocate the Rook, so that the King and Rook are always moved together
+    // The hash keys for everything other than the pieces are removed here, before the board changes,
+    // and are added back for the new gamestate by update_gamestate()
     fn move_piece(&mut self, requested_move: &ChessMove) -> bool {
+        self.hash ^= self.state_hash();
+
+        if self.is_castling(requested_move) {
+            self.move_castling_pieces(requested_move);
+            return false;
+        }
+
         let move_is_capture = self.board.piece_at(requested_move.destination()).is_some();
 
         let moved_piece = self.board.piece_at(requested_move.source()).copied();
 
-        self.board
-            .set_piece(requested_move.destination(), moved_piece);
-        self.board.set_piece(requested_move.source(), None);
+        self.set_piece(requested_move.destination(), moved_piece);
+        self.set_piece(requested_move.source(), None);
 
         move_is_capture
     }
@@ -179,26 +336,625 @@ impl ChessGamestate {
     // This function provides a safer interface for performing a move, as anyone writing
     // external code does not need to worry about the order of the 3 functions
     pub fn perform_move(&mut self, move_to_perform: &ChessMove) -> Result<(), ChessError> {
+        // Draws by rule end the game even though legal moves remain
+        if self.draw_claim.is_some() || self.is_drawn_by_rule() {
+            return Err(ChessError::GameOver(self.status()));
+        }
+
+        if let Err(err) = self.perform_legal_move(move_to_perform) {
+            // A finished game has no legal moves, which is more useful to report than why this particular move failed
+            let status = self.status();
+
+            if status.is_over() {
+                return Err(ChessError::GameOver(status));
+            }
+
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    // Performs a move if it is legal, even if the game has already been drawn by rule or by a claim
+    // Chess protocols replay and analyse games past the point where they were drawn, so only the rules of movement apply
+    // Performing a new move discards any undone moves, as they can no longer be redone
+    pub fn perform_legal_move(&mut self, move_to_perform: &ChessMove) -> Result<(), ChessError> {
         self.validate_move(move_to_perform, false)?;
-        let move_was_capture = self.move_piece(move_to_perform);
-        self.update_gamestate(move_to_perform, move_was_capture);
+
+        self.perform_validated_move(move_to_perform);
+        self.redo_moves.clear();
 
         Ok(())
     }
 
-    // Finds the position of the given color's King
-    fn find_king(&self, color: ChessPieceColor) -> ChessPoint {
-        for (y, row) in self.board.pieces.iter().enumerate() {
+    // Performs a move which is already known to be legal, and records it in the move history
+    pub(crate) fn perform_validated_move(&mut self, move_to_perform: &ChessMove) {
+        let castling = self.is_castling(move_to_perform);
+
+        let record = ChessMoveRecord {
+            performed_move: *move_to_perform,
+            castling,
+            captured_piece: self.get_captured_piece(move_to_perform),
+            white_castle_kingside: self.white_castle_kingside,
+            white_castle_queenside: self.white_castle_queenside,
+            black_castle_kingside: self.black_castle_kingside,
+            black_castle_queenside: self.black_castle_queenside,
+            en_passant_tile: self.en_passant_tile,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_clock: self.fullmove_clock,
+            hash: self.hash,
+        };
+
+        let move_was_capture = self.move_piece(move_to_perform);
+
+        // In Chess960 the King does not end up on the destination of a castling move, which is its Rook's tile
+        let landed_move = match castling {
+            true => ChessMove::new(
+                *move_to_perform.source(),
+                self.castling_points(move_to_perform).0,
+            ),
+            false => *move_to_perform,
+        };
+
+        self.update_gamestate(&landed_move, move_was_capture);
+
+        debug_assert_eq!(
+            self.hash,
+            self.compute_hash(),
+            "[INTERNAL ERROR] Incremental hash does not match the position after {}",
+            move_to_perform
+        );
+
+        self.history.push(record);
+    }
+
+    // Reverses the most recently performed move, restoring the exact gamestate from before it
+    // Unlike undo(), the move cannot be redone afterwards, which makes this suitable for searching through moves
+    // Returns the reversed move, or None if there are no moves to reverse
+    pub fn unmake_move(&mut self) -> Option<ChessMove> {
+        let record = self.history.pop()?;
+        let performed_move = record.performed_move;
+
+        match record.castling {
+            true => self.unmove_castling_pieces(&performed_move),
+            false => self.unmove_piece(&record),
+        }
+
+        self.white_castle_kingside = record.white_castle_kingside;
+        self.white_castle_queenside = record.white_castle_queenside;
+        self.black_castle_kingside = record.black_castle_kingside;
+        self.black_castle_queenside = record.black_castle_queenside;
+        self.en_passant_tile = record.en_passant_tile;
+        self.halfmove_clock = record.halfmove_clock;
+        self.fullmove_clock = record.fullmove_clock;
+        self.hash = record.hash;
+
+        self.update_king_positions();
+        self.swap_turn_color();
+
+        // A draw claimed after the move no longer stands once the move is taken back
+        self.draw_claim = None;
+
+        Some(performed_move)
+    }
+
+    // Moves the piece of a recorded move back to its source, and restores any piece it captured
+    // The hash is not updated, as unmake_move() restores it from the record
+    fn unmove_piece(&mut self, record: &ChessMoveRecord) {
+        let performed_move = record.performed_move;
+
+        let mut moved_piece = match self.board.piece_at(performed_move.destination()) {
+            Some(piece) => *piece,
+            None => unreachable!("[INTERNAL ERROR] Moved piece not found at destination on unmake"),
+        };
+
+        moved_piece.decrement_move_count();
+
+        if performed_move.promotion().is_some() {
+            moved_piece.kind = ChessPieceKind::Pawn;
+        }
+
+        self.board
+            .set_piece(performed_move.source(), Some(moved_piece));
+        self.board.set_piece(performed_move.destination(), None);
+
+        // An en passant capture removes a Pawn from beside the destination rather than from the destination itself
+        if let Some(captured_piece) = record.captured_piece {
+            let captured_point = match Self::is_en_passant_capture(
+                &performed_move,
+                &moved_piece,
+                &record.en_passant_tile,
+            ) {
+                true => ChessPoint::new(
+                    performed_move.destination().x(),
+                    performed_move.source().y(),
+                ),
+                false => *performed_move.destination(),
+            };
+
+            self.board.set_piece(&captured_point, Some(captured_piece));
+        }
+    }
+
+    // Takes back the most recently performed move, so that it can be redone with redo()
+    // Returns the undone move, or None if there are no moves to undo
+    pub fn undo(&mut self) -> Option<ChessMove> {
+        let undone_move = self.unmake_move()?;
+        self.redo_moves.push(undone_move);
+
+        Some(undone_move)
+    }
+
+    // Performs the most recently undone move again
+    // Returns the redone move, or None if there are no moves to redo
+    pub fn redo(&mut self) -> Option<ChessMove> {
+        let redone_move = self.redo_moves.pop()?;
+
+        // The move was legal when it was first performed from this exact gamestate, so it does not need to be validated
+        self.perform_validated_move(&redone_move);
+
+        Some(redone_move)
+    }
+
+    // Gets every move performed so far, from oldest to newest
+    pub fn history(&self) -> &[ChessMoveRecord] {
+        &self.history
+    }
+
+    // Forgets every performed and undone move, making the current position the start of the history
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.redo_moves.clear();
+    }
+
+    // Copies the gamestate without its move history, which is unnecessary for testing hypothetical moves
+    fn copy_position(&self) -> Self {
+        ChessGamestate {
+            history: Vec::new(),
+            redo_moves: Vec::new(),
+            draw_claim: None,
+            ..*self
+        }
+    }
+
+    // Gets the piece that a move would capture, if there is one
+    pub fn get_captured_piece(&self, queried_move: &ChessMove) -> Option<ChessPiece> {
+        let moved_piece = self.board.piece_at(queried_move.source())?;
+
+        // A Chess960 castling move's destination holds the friendly Rook, which is moved rather than captured
+        if self.is_castling(queried_move) {
+            return None;
+        }
+
+        // When an en passant capture is performed, the captured Pawn is at the same
+        // y-coordinate as the source point, and the same x-coordinate as the destination point
+        match Self::is_en_passant_capture(queried_move, moved_piece, &self.en_passant_tile) {
+            true => self
+                .board
+                .piece_at(&ChessPoint::new(
+                    queried_move.destination().x(),
+                    queried_move.source().y(),
+                ))
+                .copied(),
+            false => self.board.piece_at(queried_move.destination()).copied(),
+        }
+    }
+
+    // Determines whether the game is still ongoing, or how it has ended
+    // Checkmate takes precedence over the automatic draws, as the game ends as soon as the mating move is made
+    pub fn status(&self) -> ChessGameStatus {
+        if let Some(draw_claim) = self.draw_claim {
+            return ChessGameStatus::DrawClaimed(draw_claim);
+        }
+
+        if !self.has_legal_move() {
+            return match self.is_in_check() {
+                true => ChessGameStatus::Checkmate(self.enemy_color()),
+                false => ChessGameStatus::Stalemate,
+            };
+        }
+
+        if self.has_insufficient_material() {
+            return ChessGameStatus::InsufficientMaterial;
+        }
+
+        if self.repetition_count() >= 5 {
+            return ChessGameStatus::FivefoldRepetition;
+        }
+
+        if self.halfmove_clock >= 150 {
+            return ChessGameStatus::SeventyFiveMoveRule;
+        }
+
+        ChessGameStatus::Ongoing
+    }
+
+    // Checks if the game has ended in a draw by fivefold repetition, the 75-move rule or insufficient material
+    // This is cheaper than status(), as it does not look for legal moves
+    fn is_drawn_by_rule(&self) -> bool {
+        self.halfmove_clock >= 150
+            || self.has_insufficient_material()
+            || self.repetition_count() >= 5
+    }
+
+    // Checks if neither player could ever checkmate the other, such as with only the two Kings left
+    pub fn has_insufficient_material(&self) -> bool {
+        !self.has_mating_material(ChessPieceColor::White)
+            && !self.has_mating_material(ChessPieceColor::Black)
+    }
+
+    // Checks if the given color could checkmate the enemy King by any sequence of legal moves,
+    // even with the cooperation of the enemy; if a player runs out of time while their opponent
+    // cannot possibly checkmate them, the game is a draw rather than a loss
+    // Lone Knights and same-colored Bishops need enemy pieces to block the enemy King in,
+    // so whether they can checkmate depends on the enemy's material as well
+    pub fn has_mating_material(&self, color: ChessPieceColor) -> bool {
+        use ChessPieceKind::*;
+
+        let enemy_color = match color {
+            ChessPieceColor::White => ChessPieceColor::Black,
+            ChessPieceColor::Black => ChessPieceColor::White,
+        };
+
+        let friendly = |kind| self.board.pieces_bitboard(kind, color);
+
+        // Pawns can promote, and Rooks and Queens can checkmate a lone King by themselves
+        if friendly(Pawn) | friendly(Rook) | friendly(Queen) != 0 {
+            return true;
+        }
+
+        if friendly(Knight) != 0 {
+            // A single Knight can only checkmate if the enemy has pieces other than Queens to block their own King
+            // Queens are excluded because they can always escape the blocking square or capture the Knight
+            let enemy_blockers = self.board.color_bitboard(enemy_color)
+                & !self.board.kind_bitboard(King)
+                & !self.board.kind_bitboard(Queen);
+
+            let friendly_minor_pieces = (friendly(Knight) | friendly(Bishop)).count_ones();
+
+            return friendly_minor_pieces > 1 || enemy_blockers != 0;
+        }
+
+        if friendly(Bishop) != 0 {
+            // Bishops on a single tile color can never attack a King on the other tile color, so checkmate
+            // needs a Knight or Pawn somewhere on the board, or a Bishop on the other tile color
+            const DARK_TILES: u64 = 0xAA55_AA55_AA55_AA55;
+
+            let bishops = self.board.kind_bitboard(Bishop);
+            let bishops_share_tile_color = bishops & DARK_TILES == 0 || bishops & !DARK_TILES == 0;
+
+            let knights_or_pawns_remain =
+                self.board.kind_bitboard(Knight) | self.board.kind_bitboard(Pawn) != 0;
+
+            return !bishops_share_tile_color || knights_or_pawns_remain;
+        }
+
+        // A lone King can never give check
+        false
+    }
+
+    // Gets the draw that the player whose turn it is could claim, if there is one
+    pub fn claimable_draw(&self) -> Option<ChessDrawClaim> {
+        if self.repetition_count() >= 3 {
+            Some(ChessDrawClaim::ThreefoldRepetition)
+        } else if self.halfmove_clock >= 100 {
+            Some(ChessDrawClaim::FiftyMoveRule)
+        } else {
+            None
+        }
+    }
+
+    // Ends the game in a draw, if the player whose turn it is is entitled to claim one
+    pub fn claim_draw(&mut self) -> Result<ChessDrawClaim, ChessError> {
+        let status = self.status();
+
+        if status.is_over() {
+            return Err(ChessError::GameOver(status));
+        }
+
+        let draw_claim = self.claimable_draw().ok_or(ChessError::NoDrawToClaim)?;
+        self.draw_claim = Some(draw_claim);
+
+        Ok(draw_claim)
+    }
+
+    // Counts how many times the current position has occurred, including the current occurrence
+    // Positions are compared by hash, so an en passant capture which is possible but illegal
+    // (because the capturing Pawn is pinned) makes the positions count as different
+    pub fn repetition_count(&self) -> usize {
+        // Positions from before the last capture or Pawn move can never occur again
+        let repeatable_records = self.history.iter().rev().take(self.halfmove_clock as usize);
+
+        1 + repeatable_records
+            .filter(|record| record.hash == self.hash)
+            .count()
+    }
+
+    // Gets the Zobrist hash of the current position, which covers the pieces on the board, the turn color,
+    // the castling rights and the en passant tile, but not the move counts or move clocks
+    // The hash is the same as the Polyglot key of the position, so it can be used to look up opening books
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    // Calculates the hash of the current position from scratch, rather than incrementally
+    // This should always be equal to hash(), unless the public fields have been changed directly
+    pub fn compute_hash(&self) -> u64 {
+        let mut hash = self.state_hash();
+
+        for (y, row) in self.board.pieces().iter().enumerate() {
             for (x, piece) in row.iter().enumerate() {
                 if let Some(piece) = piece {
-                    if piece.kind == ChessPieceKind::King && piece.color == color {
-                        return ChessPoint::new(x, y);
-                    }
+                    hash ^= chess_zobrist::piece_key(piece, &ChessPoint::new(x, y));
                 }
             }
         }
 
-        unreachable!("[INTERNAL ERROR] Unable to find King");
+        hash
+    }
+
+    // Recalculates the hash from scratch, which must be done after changing the public fields directly
+    pub fn refresh_hash(&mut self) {
+        self.hash = self.compute_hash();
+    }
+
+    // Calculates the part of the hash which does not depend on the pieces on the board
+    fn state_hash(&self) -> u64 {
+        let mut hash = 0;
+
+        let castling_rights = [
+            self.white_castle_kingside,
+            self.white_castle_queenside,
+            self.black_castle_kingside,
+            self.black_castle_queenside,
+        ];
+
+        for (index, castling_right_held) in castling_rights.iter().enumerate() {
+            if *castling_right_held {
+                hash ^= chess_zobrist::castling_key(index);
+            }
+        }
+
+        if let Some(en_passant_tile) = self.en_passant_tile {
+            if self.en_passant_capture_is_possible(&en_passant_tile) {
+                hash ^= chess_zobrist::en_passant_key(&en_passant_tile);
+            }
+        }
+
+        if self.turn_color == ChessPieceColor::White {
+            hash ^= chess_zobrist::turn_key();
+        }
+
+        hash
+    }
+
+    // Checks if a Pawn of the player whose turn it is is beside the Pawn that moved two tiles,
+    // which Polyglot requires for the en passant tile to be included in the hash
+    // Whether the capture would actually be legal is not considered
+    fn en_passant_capture_is_possible(&self, en_passant_tile: &ChessPoint) -> bool {
+        // The capturing Pawn must be on the same rank as the Pawn that moved two tiles, which is in front of the en passant tile
+        let capturing_rank = match self.turn_color {
+            ChessPieceColor::White => en_passant_tile.y() - 1,
+            ChessPieceColor::Black => en_passant_tile.y() + 1,
+        };
+
+        [
+            en_passant_tile.x() as i32 - 1,
+            en_passant_tile.x() as i32 + 1,
+        ]
+        .into_iter()
+        .filter(|x| (0..8).contains(x))
+        .any(|x| {
+            self.piece_is_at(
+                &ChessPoint::new(x as usize, capturing_rank),
+                ChessPieceKind::Pawn,
+                self.turn_color,
+            )
+        })
+    }
+
+    // Places a piece on the board (or clears a tile), keeping the hash up to date
+    fn set_piece(&mut self, point: &ChessPoint, piece: Option<ChessPiece>) {
+        if let Some(removed_piece) = self.board.piece_at(point) {
+            self.hash ^= chess_zobrist::piece_key(removed_piece, point);
+        }
+
+        if let Some(placed_piece) = &piece {
+            self.hash ^= chess_zobrist::piece_key(placed_piece, point);
+        }
+
+        self.board.set_piece(point, piece);
+    }
+
+    // Checks if the King of the player whose turn it is is currently in check
+    pub fn is_in_check(&self) -> bool {
+        let king_position = match self.turn_color {
+            ChessPieceColor::White => self.white_king_position,
+            ChessPieceColor::Black => self.black_king_position,
+        };
+
+        self.is_point_attacked(&king_position, self.enemy_color())
+    }
+
+    // Checks if the player whose turn it is has at least one legal move
+    fn has_legal_move(&self) -> bool {
+        chess_attacks::points(self.board.color_bitboard(self.turn_color))
+            .any(|source| !self.legal_moves_from(&source).is_empty())
+    }
+
+    // Gets every legal move for the player whose turn it is
+    pub fn legal_moves(&self) -> Vec<ChessMove> {
+        let mut legal_moves = Vec::new();
+
+        for source in chess_attacks::points(self.board.color_bitboard(self.turn_color)) {
+            legal_moves.append(&mut self.legal_moves_from(&source));
+        }
+
+        legal_moves
+    }
+
+    // Gets every legal move for the piece at the given point
+    // If there is no piece at the point, or the piece belongs to the enemy, no moves are returned
+    // Promotions are returned as one move per possible promotion piece
+    pub fn legal_moves_from(&self, source: &ChessPoint) -> Vec<ChessMove> {
+        self.legal_moves_within(source, u64::MAX)
+    }
+
+    // Gets every legal capture and promotion for the player whose turn it is
+    // These are the moves which change the material on the board, which a search looks at
+    // beyond its depth limit to avoid stopping halfway through an exchange
+    pub fn legal_captures(&self) -> Vec<ChessMove> {
+        const PROMOTION_RANKS: u64 = 0xFF00_0000_0000_00FF;
+
+        let enemy = self.board.color_bitboard(self.enemy_color());
+        let pawns = self
+            .board
+            .pieces_bitboard(ChessPieceKind::Pawn, self.turn_color);
+        let en_passant_bit = self
+            .en_passant_tile
+            .map_or(0, |tile| chess_attacks::bit(&tile));
+
+        let mut legal_captures = Vec::new();
+
+        for source in chess_attacks::points(self.board.color_bitboard(self.turn_color)) {
+            let destinations = match pawns & chess_attacks::bit(&source) != 0 {
+                true => enemy | en_passant_bit | PROMOTION_RANKS,
+                false => enemy,
+            };
+
+            legal_captures.append(&mut self.legal_moves_within(&source, destinations));
+        }
+
+        legal_captures
+    }
+
+    // Gets every legal move for the piece at the given point whose destination is in the given bitboard
+    fn legal_moves_within(&self, source: &ChessPoint, destinations: u64) -> Vec<ChessMove> {
+        let mut legal_moves = Vec::new();
+
+        let moved_piece = match self.board.piece_at(source) {
+            Some(piece) if piece.color == self.turn_color => piece,
+            _ => return legal_moves,
+        };
+
+        let candidate_destinations =
+            self.candidate_destinations(source, moved_piece) & destinations;
+
+        // Only the destinations the piece could reach are fully validated
+        for destination in chess_attacks::points(candidate_destinations) {
+            let candidate_move = ChessMove::new(*source, destination);
+
+            if !Self::is_promotion_move(&candidate_move, moved_piece) {
+                if self.validate_move(&candidate_move, false).is_ok() {
+                    legal_moves.push(candidate_move);
+                }
+
+                continue;
+            }
+
+            // The choice of promotion piece does not affect legality, so only one needs to be validated
+            let promotion_moves = ChessPieceKind::PROMOTION_TARGETS
+                .map(|kind| ChessMove::new_promotion(*source, destination, kind));
+
+            if self.validate_move(&promotion_moves[0], false).is_ok() {
+                legal_moves.extend(promotion_moves);
+            }
+        }
+
+        legal_moves
+    }
+
+    // Gets the tiles that a piece could possibly move to, as a bitboard
+    // This includes every legal destination, but not every destination is legal, so each one must still be validated
+    fn candidate_destinations(&self, source: &ChessPoint, moved_piece: &ChessPiece) -> u64 {
+        use ChessPieceKind::*;
+
+        let occupied = self.board.occupied_bitboard();
+        let friendly = self.board.color_bitboard(moved_piece.color);
+
+        let destinations = match moved_piece.kind {
+            Pawn => {
+                let en_passant_bit = self
+                    .en_passant_tile
+                    .map_or(0, |tile| chess_attacks::bit(&tile));
+                let capture_targets =
+                    self.board.color_bitboard(self.enemy_color()) | en_passant_bit;
+
+                // Pawns push forwards by one or two tiles, which is left to the validation to check
+                let (one_tile_push, two_tile_push) = match moved_piece.color {
+                    ChessPieceColor::White => (
+                        chess_attacks::bit(source) << 8,
+                        chess_attacks::bit(source) << 16,
+                    ),
+                    ChessPieceColor::Black => (
+                        chess_attacks::bit(source) >> 8,
+                        chess_attacks::bit(source) >> 16,
+                    ),
+                };
+
+                (chess_attacks::pawn_attacks(moved_piece.color, source) & capture_targets)
+                    | ((one_tile_push | two_tile_push) & !occupied)
+            }
+            Knight => chess_attacks::knight_attacks(source),
+            Bishop => chess_attacks::bishop_attacks(source, occupied),
+            Rook => chess_attacks::rook_attacks(source, occupied),
+            Queen => chess_attacks::queen_attacks(source, occupied),
+            King => {
+                // In Chess960, castling moves the King onto its own Rook, which is kept out of the friendly mask below
+                if self.chess960 {
+                    return (chess_attacks::king_attacks(source) & !friendly)
+                        | self.castling_rooks_bitboard(moved_piece.color);
+                }
+
+                // Otherwise, castling moves the King two tiles along its rank
+                let castling_destinations = [source.x() as i32 - 2, source.x() as i32 + 2]
+                    .into_iter()
+                    .filter(|x| (0..8).contains(x))
+                    .fold(0, |bitboard, x| {
+                        bitboard | chess_attacks::bit(&ChessPoint::new(x as usize, source.y()))
+                    });
+
+                chess_attacks::king_attacks(source) | castling_destinations
+            }
+        };
+
+        destinations & !friendly
+    }
+
+    // Gets the tiles of the Rooks that the given color still has castling rights with, as a bitboard
+    fn castling_rooks_bitboard(&self, color: ChessPieceColor) -> u64 {
+        let (back_rank, castling_rights) = match color {
+            ChessPieceColor::White => (
+                0,
+                [
+                    (self.white_castle_kingside, self.white_kingside_rook_file),
+                    (self.white_castle_queenside, self.white_queenside_rook_file),
+                ],
+            ),
+            ChessPieceColor::Black => (
+                7,
+                [
+                    (self.black_castle_kingside, self.black_kingside_rook_file),
+                    (self.black_castle_queenside, self.black_queenside_rook_file),
+                ],
+            ),
+        };
+
+        castling_rights
+            .iter()
+            .filter(|(castling_right_held, _)| *castling_right_held)
+            .fold(0, |bitboard, (_, rook_file)| {
+                bitboard | chess_attacks::bit(&ChessPoint::new(*rook_file, back_rank))
+            })
+    }
+
+    // Finds the position of the given color's King
+    fn find_king(&self, color: ChessPieceColor) -> ChessPoint {
+        match chess_attacks::points(self.board.pieces_bitboard(ChessPieceKind::King, color)).next()
+        {
+            Some(king_position) => king_position,
+            None => unreachable!("[INTERNAL ERROR] Unable to find King"),
+        }
     }
 
     // Updates the King positions fields in the gamestate
@@ -207,44 +963,191 @@ impl ChessGamestate {
         self.black_king_position = self.find_king(ChessPieceColor::Black);
     }
 
-    // Updates the castling rights for both colors, based on the current board state
-    // Though Rooks and Kings can move back to their original positions, the castling rights are never restored,
-    // they are only removed; this means that the function does not need to check their move counts
-    // This could be done through any number of methods but this one is the simplest
-    fn update_castling_rights(&mut self) {
-        // White King
-        if self.board.piece_at(&ChessPoint::new(4, 0)).is_none() {
+    // Updates the castling rights for both colors after a move
+    // A King which moves (including by castling) loses both of its rights, and a right is lost once its Rook is
+    // no longer on its tile; rights are never restored, even if the pieces return to their original tiles
+    // A tile is checked for the expected piece rather than just for emptiness, so that a
+    // Rook being captured in its corner also removes the right
+    fn update_castling_rights(&mut self, king_moved: bool) {
+        use ChessPieceColor::*;
+        use ChessPieceKind::*;
+
+        // This is called before the turn color is swapped, so the turn color is the color that moved
+        if king_moved {
+            match self.turn_color {
+                White => {
+                    self.white_castle_kingside = false;
+                    self.white_castle_queenside = false;
+                }
+                Black => {
+                    self.black_castle_kingside = false;
+                    self.black_castle_queenside = false;
+                }
+            }
+        }
+
+        if !self.piece_is_at(
+            &ChessPoint::new(self.white_kingside_rook_file, 0),
+            Rook,
+            White,
+        ) {
             self.white_castle_kingside = false;
+        }
+
+        if !self.piece_is_at(
+            &ChessPoint::new(self.white_queenside_rook_file, 0),
+            Rook,
+            White,
+        ) {
             self.white_castle_queenside = false;
         }
 
-        // Black King
-        if self.board.piece_at(&ChessPoint::new(4, 7)).is_none() {
+        if !self.piece_is_at(
+            &ChessPoint::new(self.black_kingside_rook_file, 7),
+            Rook,
+            Black,
+        ) {
             self.black_castle_kingside = false;
+        }
+
+        if !self.piece_is_at(
+            &ChessPoint::new(self.black_queenside_rook_file, 7),
+            Rook,
+            Black,
+        ) {
             self.black_castle_queenside = false;
         }
+    }
 
-        // White Rook (right)
-        if self.board.piece_at(&ChessPoint::new(7, 0)).is_none() {
-            self.white_castle_kingside = false;
+    // Checks if a piece of the given kind and color is at the given point
+    fn piece_is_at(
+        &self,
+        point: &ChessPoint,
+        kind: ChessPieceKind,
+        color: ChessPieceColor,
+    ) -> bool {
+        match self.board.piece_at(point) {
+            Some(piece) => piece.kind == kind && piece.color == color,
+            None => false,
         }
+    }
 
-        // White Rook (left)
-        if self.board.piece_at(&ChessPoint::new(0, 0)).is_none() {
-            self.white_castle_queenside = false;
+    // Checks if a move is a castling move, which is written as a two-tile King move from the e-file,
+    // or in Chess960 as the King moving onto a friendly Rook on its back rank
+    // Whether the castling move is legal is not considered
+    pub fn is_castling(&self, queried_move: &ChessMove) -> bool {
+        let moved_piece = match self.board.piece_at(queried_move.source()) {
+            Some(piece) if piece.kind == ChessPieceKind::King => piece,
+            _ => return false,
+        };
+
+        let back_rank = match moved_piece.color {
+            ChessPieceColor::White => 0,
+            ChessPieceColor::Black => 7,
+        };
+
+        if queried_move.source().y() != back_rank || queried_move.destination().y() != back_rank {
+            return false;
         }
 
-        // Black Rook (right)
-        if self.board.piece_at(&ChessPoint::new(7, 7)).is_none() {
-            self.black_castle_kingside = false;
+        match self.chess960 {
+            true => self.piece_is_at(
+                queried_move.destination(),
+                ChessPieceKind::Rook,
+                moved_piece.color,
+            ),
+            false => queried_move.source().x() == 4 && queried_move.change_in_x().abs() == 2,
         }
+    }
 
-        // Black Rook (left)
-        if self.board.piece_at(&ChessPoint::new(0, 7)).is_none() {
-            self.black_castle_queenside = false;
+    // Gets the King's destination, the Rook's source and the Rook's destination for a castling move
+    // Whatever the starting files, the King ends on the g- or c-file and the Rook beside it on the f- or d-file
+    // This only depends on the move and the castling Rook files, so it also works after the move has been performed
+    fn castling_points(&self, castling_move: &ChessMove) -> (ChessPoint, ChessPoint, ChessPoint) {
+        let back_rank = castling_move.source().y();
+        let castle_is_kingside = castling_move.change_in_x() > 0;
+
+        let rook_file = match (back_rank == 0, castle_is_kingside) {
+            (true, true) => self.white_kingside_rook_file,
+            (true, false) => self.white_queenside_rook_file,
+            (false, true) => self.black_kingside_rook_file,
+            (false, false) => self.black_queenside_rook_file,
+        };
+
+        let (king_destination_file, rook_destination_file) = match castle_is_kingside {
+            true => (6, 5),
+            false => (2, 3),
+        };
+
+        (
+            ChessPoint::new(king_destination_file, back_rank),
+            ChessPoint::new(rook_file, back_rank),
+            ChessPoint::new(rook_destination_file, back_rank),
+        )
+    }
+
+    // Moves the King and Rook of a castling move to their destinations
+    fn move_castling_pieces(&mut self, castling_move: &ChessMove) {
+        let (king_destination, rook_source, rook_destination) = self.castling_points(castling_move);
+
+        let king = self.board.piece_at(castling_move.source()).copied();
+        let mut rook = self.board.piece_at(&rook_source).copied();
+
+        if let Some(rook) = &mut rook {
+            rook.increment_move_count();
+        }
+
+        // Both pieces are lifted before either is placed, as in Chess960 each may land on the other's tile
+        self.set_piece(castling_move.source(), None);
+        self.set_piece(&rook_source, None);
+        self.set_piece(&king_destination, king);
+        self.set_piece(&rook_destination, rook);
+    }
+
+    // Checks if a given move is a promotion move (a Pawn move to the last rank for its color)
+    fn is_promotion_move(queried_move: &ChessMove, moved_piece: &ChessPiece) -> bool {
+        let last_rank = match moved_piece.color {
+            ChessPieceColor::White => 7,
+            ChessPieceColor::Black => 0,
+        };
+
+        moved_piece.kind == ChessPieceKind::Pawn && queried_move.destination().y() == last_rank
+    }
+
+    // Replaces a promoted Pawn with the piece it was promoted to
+    fn update_for_promotion(&mut self, performed_move: &ChessMove) {
+        let promotion_kind = match performed_move.promotion() {
+            Some(kind) => kind,
+            None => return,
+        };
+
+        // The promoted piece keeps the Pawn's move count, as it is still the same piece
+        if let Some(mut promoted_piece) = self.board.piece_at(performed_move.destination()).copied()
+        {
+            promoted_piece.kind = promotion_kind;
+            self.set_piece(performed_move.destination(), Some(promoted_piece));
         }
     }
 
+    // Moves the King and Rook of a castling move back to their original tiles
+    // The hash is not updated, as unmake_move() restores it from the record
+    fn unmove_castling_pieces(&mut self, castling_move: &ChessMove) {
+        let (king_destination, rook_source, rook_destination) = self.castling_points(castling_move);
+
+        let mut king = self.board.piece_at(&king_destination).copied();
+        let mut rook = self.board.piece_at(&rook_destination).copied();
+
+        // The King's move count was incremented by update_gamestate(), and the Rook's by move_castling_pieces()
+        for piece in [&mut king, &mut rook].into_iter().flatten() {
+            piece.decrement_move_count();
+        }
+
+        self.board.set_piece(&king_destination, None);
+        self.board.set_piece(&rook_destination, None);
+        self.board.set_piece(castling_move.source(), king);
+        self.board.set_piece(&rook_source, rook);
+    }
+
     // Checks if a given move was an en passant move (two-tile Pawn move)
     fn was_en_passant_move(queried_move: &ChessMove, moved_piece: &ChessPiece) -> bool {
         moved_piece.kind == ChessPieceKind::Pawn && queried_move.change_in_y().abs() == 2
@@ -310,14 +1213,14 @@ impl ChessGamestate {
             performed_move.source().y(),
         );
 
-        self.board.set_piece(&tile_to_clear, None);
+        self.set_piece(&tile_to_clear, None);
     }
 
     // Clears the en passant tile if no en passant move was performed
     fn clear_en_passant_tile(&mut self, performed_move: &ChessMove) {
         if !Self::was_en_passant_move(
             performed_move,
-            &self.board.piece_at(performed_move.destination()).unwrap(),
+            self.board.piece_at(performed_move.destination()).unwrap(),
         ) {
             self.en_passant_tile = None;
         }
@@ -326,7 +1229,11 @@ impl ChessGamestate {
     // Increments or resets the move clocks, based on the move that was performed
     fn increment_move_clocks(&mut self, reset_halfmove_clock: bool) {
         self.halfmove_clock += 1;
-        self.fullmove_clock += 1;
+
+        // This is called before the turn color is swapped, so the turn color is the color that moved
+        if self.turn_color == ChessPieceColor::Black {
+            self.fullmove_clock += 1;
+        }
 
         if reset_halfmove_clock {
             self.halfmove_clock = 0;
@@ -343,44 +1250,56 @@ impl ChessGamestate {
         };
     }
 
+    // Gets the color of the player whose turn it is not
+    fn enemy_color(&self) -> ChessPieceColor {
+        match self.turn_color {
+            ChessPieceColor::White => ChessPieceColor::Black,
+            ChessPieceColor::Black => ChessPieceColor::White,
+        }
+    }
+
     // Checks if the enemy color's King is in check
     // This can be used after a hypothetical move to test if the move would put
     // the friendly King in check, but only after the turn color has been swapped
-    fn is_check(&self) -> bool {
+    pub(crate) fn is_check(&self) -> bool {
         let king_position = match self.turn_color {
             ChessPieceColor::White => self.black_king_position,
             ChessPieceColor::Black => self.white_king_position,
         };
 
-        // TODO: This can be optimized by only looking at the squares that enemy pieces could be in to threaten the King
-        for (y, row) in self.board.pieces.iter().enumerate() {
-            for (x, piece) in row.iter().enumerate() {
-                if let Some(piece) = piece {
-                    if piece.color == self.turn_color {
-                        let move_to_check = ChessMove::new(ChessPoint::new(x, y), king_position);
-
-                        // If any of the enemy pieces can legally move to the King's position, then the King is in check
-                        if self.validate_move(&move_to_check, true).is_ok() {
-                            return true;
-                        }
-                    }
-                }
-            }
-        }
+        self.is_point_attacked(&king_position, self.turn_color)
+    }
 
-        false
+    // Checks if any piece of the given color could capture a piece at the given point
+    // Unlike validate_move(), this does not require a piece to be at the point, which allows empty tiles
+    // (such as those a King passes over when castling) to be checked for Pawn attacks
+    // Attacks are symmetric, so the point is attacked if a piece of each kind placed on the point
+    // would attack an enemy piece of the same kind
+    fn is_point_attacked(&self, point: &ChessPoint, attacker_color: ChessPieceColor) -> bool {
+        use ChessPieceKind::*;
+
+        let occupied = self.board.occupied_bitboard();
+        let attackers = |kind| self.board.pieces_bitboard(kind, attacker_color);
+
+        // A Pawn attacks the point if a Pawn of the other color on the point would attack it
+        let defender_color = match attacker_color {
+            ChessPieceColor::White => ChessPieceColor::Black,
+            ChessPieceColor::Black => ChessPieceColor::White,
+        };
+
+        chess_attacks::pawn_attacks(defender_color, point) & attackers(Pawn) != 0
+            || chess_attacks::knight_attacks(point) & attackers(Knight) != 0
+            || chess_attacks::king_attacks(point) & attackers(King) != 0
+            || chess_attacks::bishop_attacks(point, occupied)
+                & (attackers(Bishop) | attackers(Queen))
+                != 0
+            || chess_attacks::rook_attacks(point, occupied) & (attackers(Rook) | attackers(Queen))
+                != 0
     }
+}
 
-    // TODO: Probably move this to UI
-    pub fn print_board(&self) {
-        for row in self.board.pieces.iter().rev() {
-            for piece in row.iter() {
-                match piece {
-                    Some(piece) => print!("[{}]", piece.get_piece_char()),
-                    None => print!("[\u{2001}]"),
-                }
-            }
-            println!();
-        }
+impl Default for ChessGamestate {
+    fn default() -> Self {
+        Self::new()
     }
 }
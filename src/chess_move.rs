@@ -1,8 +1,15 @@
+use crate::ChessParseError;
+use crate::ChessPieceKind;
 use crate::ChessPoint;
 
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ChessMove {
     source: ChessPoint,
     destination: ChessPoint,
+    // The piece that a Pawn is promoted to, if the move is a promotion
+    promotion: Option<ChessPieceKind>,
 }
 
 pub struct MovePatternValidity {
@@ -16,21 +23,21 @@ impl ChessMove {
         ChessMove {
             source,
             destination,
+            promotion: None,
         }
     }
 
-    // Parse a move from a combined tile position string, like "e2 e4"
-    pub fn from(encoded_move: &str) -> Self {
-        // TODO: Make sure there are only 2 substrings
-        let mut substrings = encoded_move.split_whitespace();
-
-        let from = substrings.next().unwrap();
-        let to = substrings.next().unwrap();
-
-        let from_point = ChessPoint::from(from);
-        let to_point = ChessPoint::from(to);
-
-        ChessMove::new(from_point, to_point)
+    // Creates a Pawn move which promotes the Pawn to the given piece kind
+    pub fn new_promotion(
+        source: ChessPoint,
+        destination: ChessPoint,
+        promotion: ChessPieceKind,
+    ) -> Self {
+        ChessMove {
+            source,
+            destination,
+            promotion: Some(promotion),
+        }
     }
 
     pub fn change_in_x(&self) -> i8 {
@@ -48,4 +55,100 @@ impl ChessMove {
     pub fn destination(&self) -> &ChessPoint {
         &self.destination
     }
+
+    pub fn promotion(&self) -> Option<ChessPieceKind> {
+        self.promotion
+    }
+}
+
+// Parses a move from a combined tile position string, like "e2 e4", "e7 e8 q" or "e7e8q"
+impl FromStr for ChessMove {
+    type Err = ChessParseError;
+
+    fn from_str(encoded_move: &str) -> Result<Self, Self::Err> {
+        use ChessParseError::*;
+
+        let tokens: Vec<&str> = encoded_move.split_whitespace().collect();
+
+        // The tiles and promotion piece may be written together or separately, like "e7e8q" or "e7 e8q"
+        let (source, destination, promotion) = match tokens.as_slice() {
+            [] => return Err(Empty),
+            [compact_move] => {
+                let (source, destination_and_promotion) = split_after_chars(compact_move, 2);
+                let (destination, promotion) = split_after_chars(destination_and_promotion, 2);
+
+                (source, destination, promotion)
+            }
+            [source, destination_and_promotion] => {
+                let (destination, promotion) = split_after_chars(destination_and_promotion, 2);
+
+                (*source, destination, promotion)
+            }
+            [source, destination, promotion] => (*source, *destination, *promotion),
+            _ => return Err(WrongTokenCount(tokens.len())),
+        };
+
+        let source: ChessPoint = source.parse()?;
+
+        // A missing destination is reported as an incomplete move, rather than an empty tile position
+        let destination: ChessPoint = match destination.parse() {
+            Err(Empty) => return Err(Incomplete),
+            parsed_destination => parsed_destination?,
+        };
+
+        Ok(match parse_promotion(promotion)? {
+            Some(kind) => ChessMove::new_promotion(source, destination, kind),
+            None => ChessMove::new(source, destination),
+        })
+    }
+}
+
+// Formats a move in long algebraic notation, like "e2e4" or "e7e8q", which can be parsed back into a move
+impl std::fmt::Display for ChessMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}{}", self.source, self.destination)?;
+
+        match self.promotion {
+            Some(kind) => write!(f, "{}", kind.get_kind_char().to_ascii_lowercase()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl TryFrom<&str> for ChessMove {
+    type Error = ChessParseError;
+
+    fn try_from(encoded_move: &str) -> Result<Self, Self::Error> {
+        encoded_move.parse()
+    }
+}
+
+// Splits a string after the given number of characters, or at its end if it is shorter
+fn split_after_chars(text: &str, char_count: usize) -> (&str, &str) {
+    let split_index = text
+        .char_indices()
+        .nth(char_count)
+        .map_or(text.len(), |(index, _)| index);
+
+    text.split_at(split_index)
+}
+
+// Parses an optional promotion piece letter, like "q"
+// Only a single character can be a promotion piece, so anything longer is reported as trailing characters
+fn parse_promotion(encoded_promotion: &str) -> Result<Option<ChessPieceKind>, ChessParseError> {
+    let mut chars = encoded_promotion.chars();
+
+    let promotion_char = match (chars.next(), chars.next()) {
+        (None, _) => return Ok(None),
+        (Some(promotion_char), None) => promotion_char,
+        (Some(_), Some(_)) => {
+            return Err(ChessParseError::TrailingCharacters(
+                encoded_promotion.to_string(),
+            ))
+        }
+    };
+
+    ChessPieceKind::from_char(promotion_char)
+        .map(Some)
+        .ok_or(ChessParseError::InvalidPromotionPiece(promotion_char))
 }
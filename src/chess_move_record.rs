@@ -0,0 +1,26 @@
+use crate::ChessMove;
+use crate::ChessPiece;
+use crate::ChessPoint;
+
+// A performed move, along with the parts of the gamestate which cannot be recovered from the board after the move
+// This is everything needed to unmake the move and restore the exact gamestate from before it
+#[derive(Clone, Copy, Debug)]
+pub struct ChessMoveRecord {
+    pub performed_move: ChessMove,
+    // Whether the move was castling, which also moved a Rook
+    pub castling: bool,
+    // The piece removed from the board by the move, which is not at the destination for en passant captures
+    pub captured_piece: Option<ChessPiece>,
+    // The castling rights from before the move
+    pub white_castle_kingside: bool,
+    pub white_castle_queenside: bool,
+    pub black_castle_kingside: bool,
+    pub black_castle_queenside: bool,
+    // The en passant tile from before the move
+    pub en_passant_tile: Option<ChessPoint>,
+    // The move clocks from before the move
+    pub halfmove_clock: u32,
+    pub fullmove_clock: u32,
+    // The hash of the position from before the move, used to detect repetitions
+    pub hash: u64,
+}
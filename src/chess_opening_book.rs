@@ -0,0 +1,195 @@
+use crate::ChessGamestate;
+use crate::ChessMove;
+use crate::ChessPieceKind;
+use crate::ChessPoint;
+
+// An opening book in the Polyglot .bin format, which gives the moves of opening theory for each position it covers
+// Positions are looked up by their Polyglot Zobrist hash, which is the hash that ChessGamestate keeps
+pub struct ChessOpeningBook {
+    // Sorted by hash, so that the moves of a position can be found with a binary search
+    entries: Vec<ChessBookEntry>,
+}
+
+// A move from an opening book, with how strongly the book recommends it relative to the other moves of the position
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChessBookMove {
+    pub book_move: ChessMove,
+    pub weight: u16,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ChessBookError {
+    // Each entry is 16 bytes long, so a book whose length is not a multiple of 16 is truncated or not a book at all
+    InvalidLength(usize),
+}
+
+// A single entry of the book, as stored in the file
+#[derive(Clone, Copy)]
+struct ChessBookEntry {
+    hash: u64,
+    encoded_move: u16,
+    weight: u16,
+}
+
+// The size in bytes of an entry: an 8-byte hash, a 2-byte move, a 2-byte weight and 4 bytes of learning data
+const ENTRY_SIZE: usize = 16;
+
+impl std::fmt::Display for ChessBookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        use ChessBookError::*;
+
+        match self {
+            InvalidLength(length) => write!(
+                f,
+                "The opening book is {} bytes long, which is not a whole number of entries.",
+                length
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ChessBookError {}
+
+impl ChessOpeningBook {
+    // Reads a book from the contents of a Polyglot .bin file, in which every number is big-endian
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ChessBookError> {
+        if !bytes.len().is_multiple_of(ENTRY_SIZE) {
+            return Err(ChessBookError::InvalidLength(bytes.len()));
+        }
+
+        let mut entries: Vec<ChessBookEntry> = bytes
+            .chunks_exact(ENTRY_SIZE)
+            .map(|entry_bytes| ChessBookEntry {
+                hash: u64::from_be_bytes(entry_bytes[0..8].try_into().unwrap()),
+                encoded_move: u16::from_be_bytes(entry_bytes[8..10].try_into().unwrap()),
+                weight: u16::from_be_bytes(entry_bytes[10..12].try_into().unwrap()),
+            })
+            .collect();
+
+        // Books are written sorted, but sorting again costs little and guards against books which are not
+        // The sort is stable, so the order of the moves within a position is kept
+        entries.sort_by_key(|entry| entry.hash);
+
+        Ok(ChessOpeningBook { entries })
+    }
+
+    // Gets the number of entries in the book
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Gets the book's moves for a position, in the order the book lists them
+    // Moves which are not legal in the position are left out, as they can only come from a different position with the same hash
+    pub fn moves(&self, gamestate: &ChessGamestate) -> Vec<ChessBookMove> {
+        let hash = gamestate.hash();
+        let first_index = self.entries.partition_point(|entry| entry.hash < hash);
+        let legal_moves = gamestate.legal_moves();
+
+        self.entries[first_index..]
+            .iter()
+            .take_while(|entry| entry.hash == hash)
+            .filter_map(|entry| {
+                let book_move = decode_move(entry.encoded_move, gamestate)?;
+
+                legal_moves.contains(&book_move).then_some(ChessBookMove {
+                    book_move,
+                    weight: entry.weight,
+                })
+            })
+            .collect()
+    }
+
+    // Gets the book's most highly recommended move for a position, if it has any
+    pub fn best_move(&self, gamestate: &ChessGamestate) -> Option<ChessMove> {
+        let moves = self.moves(gamestate);
+
+        // Of equally weighted moves, the one listed first is picked
+        moves
+            .iter()
+            .rev()
+            .max_by_key(|book_move| book_move.weight)
+            .map(|book_move| book_move.book_move)
+    }
+
+    // Picks one of the book's moves for a position at random, with each move's chance proportional to its weight
+    // The randomness comes from the given number, which should be uniformly random across all u64 values
+    // Moves with a weight of zero are never picked, unless every move has a weight of zero
+    pub fn weighted_random_move(
+        &self,
+        gamestate: &ChessGamestate,
+        random_number: u64,
+    ) -> Option<ChessMove> {
+        let moves = self.moves(gamestate);
+        let total_weight: u64 = moves.iter().map(|book_move| book_move.weight as u64).sum();
+
+        if total_weight == 0 {
+            return moves.first().map(|book_move| book_move.book_move);
+        }
+
+        let mut remaining_weight = random_number % total_weight;
+
+        for book_move in &moves {
+            if remaining_weight < book_move.weight as u64 {
+                return Some(book_move.book_move);
+            }
+
+            remaining_weight -= book_move.weight as u64;
+        }
+
+        unreachable!("[INTERNAL ERROR] Random weight exceeded the total weight of the book moves")
+    }
+}
+
+// Decodes a move as stored in a Polyglot book
+// From the lowest bit up, the move is stored as the destination file and rank, the source file and rank (3 bits each),
+// then the promotion piece (0 for none, then Knight, Bishop, Rook and Queen)
+// Castling is stored as the King capturing its own Rook, which is converted into the King's two-tile move
+// except in Chess960, where castling is already made that way
+fn decode_move(encoded_move: u16, gamestate: &ChessGamestate) -> Option<ChessMove> {
+    use ChessPieceKind::*;
+
+    let tile = |shift: u16| {
+        ChessPoint::new(
+            ((encoded_move >> shift) & 7) as usize,
+            ((encoded_move >> (shift + 3)) & 7) as usize,
+        )
+    };
+
+    let source = tile(6);
+    let mut destination = tile(0);
+
+    let promotion = match (encoded_move >> 12) & 7 {
+        0 => None,
+        1 => Some(Knight),
+        2 => Some(Bishop),
+        3 => Some(Rook),
+        4 => Some(Queen),
+        _ => return None,
+    };
+
+    let moved_piece = gamestate.board.piece_at(&source)?;
+    let captured_piece = gamestate.board.piece_at(&destination);
+
+    let is_castling = !gamestate.chess960
+        && moved_piece.kind == King
+        && captured_piece
+            .is_some_and(|piece| piece.kind == Rook && piece.color == moved_piece.color);
+
+    if is_castling {
+        let king_x = match destination.x() > source.x() {
+            true => 6,
+            false => 2,
+        };
+
+        destination = ChessPoint::new(king_x, source.y());
+    }
+
+    Some(match promotion {
+        Some(promotion) => ChessMove::new_promotion(source, destination, promotion),
+        None => ChessMove::new(source, destination),
+    })
+}
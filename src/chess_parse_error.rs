@@ -0,0 +1,46 @@
+// Problems that can occur when parsing a tile position like "e2" or a move like "e2 e4"
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChessParseError {
+    // There was nothing to parse
+    Empty,
+    // The input ended before a full tile position or move was given
+    Incomplete,
+    // The file of a tile was not a letter from 'a' to 'h'
+    InvalidFile(char),
+    // The rank of a tile was not a number from 1 to 8
+    InvalidRank(char),
+    // The promotion piece was not a piece letter
+    InvalidPromotionPiece(char),
+    // A move was split into more whitespace-separated parts than a source, destination and promotion piece
+    WrongTokenCount(usize),
+    // There were characters left over after a full tile position or move was parsed
+    TrailingCharacters(String),
+}
+
+impl std::fmt::Display for ChessParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        use ChessParseError::*;
+
+        match self {
+            Empty | Incomplete => write!(f, "Enter a move like \"e2 e4\" or \"Nf3\"."),
+            InvalidFile(file) => write!(f, "'{}' is not a file; files go from a to h.", file),
+            InvalidRank(rank) => write!(f, "'{}' is not a rank; ranks go from 1 to 8.", rank),
+            InvalidPromotionPiece(piece) => write!(
+                f,
+                "'{}' is not a piece; promote with q, r, b or n, like \"e7 e8 q\".",
+                piece
+            ),
+            WrongTokenCount(_) => write!(
+                f,
+                "Enter two tiles and an optional promotion piece, like \"e7 e8 q\"."
+            ),
+            TrailingCharacters(characters) => write!(
+                f,
+                "Unexpected characters at the end of the move: \"{}\".",
+                characters
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ChessParseError {}
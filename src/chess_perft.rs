@@ -0,0 +1,60 @@
+use crate::ChessGamestate;
+use crate::ChessMove;
+
+// Perft ("performance test") counts the positions reachable in an exact number of moves
+// The counts for well-known positions have been published, so comparing against them
+// checks that exactly the legal moves are generated, no more and no fewer
+impl ChessGamestate {
+    // Counts the move sequences of the given length from the current position
+    // Draws by repetition or the move rules are not considered, as the counts only depend on the legal moves
+    pub fn perft(&self, depth: u32) -> u64 {
+        let mut gamestate = self.clone();
+
+        gamestate.count_leaf_positions(depth)
+    }
+
+    // Counts the move sequences of the given length which start with each legal move
+    // This is used to narrow down a wrong perft count to the move which causes it
+    pub fn divide(&self, depth: u32) -> Vec<(ChessMove, u64)> {
+        let mut gamestate = self.clone();
+
+        if depth == 0 {
+            return Vec::new();
+        }
+
+        gamestate
+            .legal_moves()
+            .into_iter()
+            .map(|legal_move| {
+                gamestate.perform_validated_move(&legal_move);
+                let leaf_positions = gamestate.count_leaf_positions(depth - 1);
+                gamestate.unmake_move();
+
+                (legal_move, leaf_positions)
+            })
+            .collect()
+    }
+
+    fn count_leaf_positions(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let legal_moves = self.legal_moves();
+
+        // The positions after the last move do not need to be visited to be counted
+        if depth == 1 {
+            return legal_moves.len() as u64;
+        }
+
+        let mut leaf_positions = 0;
+
+        for legal_move in legal_moves {
+            self.perform_validated_move(&legal_move);
+            leaf_positions += self.count_leaf_positions(depth - 1);
+            self.unmake_move();
+        }
+
+        leaf_positions
+    }
+}
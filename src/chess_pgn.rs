@@ -0,0 +1,560 @@
+use crate::chess_san;
+use crate::ChessDrawClaim;
+use crate::ChessError;
+use crate::ChessFenError;
+use crate::ChessGameStatus;
+use crate::ChessGamestate;
+use crate::ChessMove;
+use crate::ChessPieceColor;
+use crate::STARTING_FEN;
+
+// The tags which every exported PGN game must contain, in the order they must appear
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+// Exported movetext lines are wrapped to stay within this length
+const MAX_LINE_LENGTH: usize = 80;
+
+// The result of a PGN game, as given in the Result tag and at the end of the movetext
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChessPgnResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+    // The game is still in progress, or its result is unknown
+    Unknown,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ChessPgnError {
+    InvalidTagPair,
+    UnterminatedComment,
+    UnterminatedVariation,
+    InvalidFen(ChessFenError),
+    // The move at the given ply (counting from 1) is not legal in the position it is played in
+    IllegalMove { ply: usize, san: String },
+    // There is no game in the given text
+    NoGame,
+}
+
+// A single game, as read from or written to a PGN file
+#[derive(Clone)]
+pub struct ChessPgnGame {
+    // The tag pairs of the game, in the order they were read or set
+    pub tags: Vec<(String, String)>,
+    pub result: ChessPgnResult,
+    starting_gamestate: ChessGamestate,
+    // The game's moves are kept in the move history of the current gamestate
+    current_gamestate: ChessGamestate,
+}
+
+impl std::fmt::Display for ChessPgnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        use ChessPgnError::*;
+
+        match self {
+            InvalidTagPair => write!(f, "The PGN contains a malformed tag pair."),
+            UnterminatedComment => write!(f, "The PGN contains a comment that is never closed."),
+            UnterminatedVariation => {
+                write!(f, "The PGN contains a variation that is never closed.")
+            }
+            InvalidFen(err) => write!(f, "The PGN's FEN tag is invalid: {}", err),
+            IllegalMove { ply, san } => {
+                write!(f, "Move {} of the game ({}) is not legal.", ply, san)
+            }
+            NoGame => write!(f, "The PGN does not contain a game."),
+        }
+    }
+}
+
+impl std::error::Error for ChessPgnError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ChessPgnError::InvalidFen(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl ChessPgnResult {
+    fn from(encoded_result: &str) -> Option<Self> {
+        use ChessPgnResult::*;
+
+        match encoded_result {
+            "1-0" => Some(WhiteWins),
+            "0-1" => Some(BlackWins),
+            "1/2-1/2" => Some(Draw),
+            "*" => Some(Unknown),
+            _ => None,
+        }
+    }
+
+    // Gets the result which a finished game status corresponds to
+    pub fn from_status(status: ChessGameStatus) -> Self {
+        match status {
+            ChessGameStatus::Ongoing => ChessPgnResult::Unknown,
+            ChessGameStatus::Checkmate(ChessPieceColor::White) => ChessPgnResult::WhiteWins,
+            ChessGameStatus::Checkmate(ChessPieceColor::Black) => ChessPgnResult::BlackWins,
+            ChessGameStatus::Timeout(ChessPieceColor::White) => ChessPgnResult::WhiteWins,
+            ChessGameStatus::Timeout(ChessPieceColor::Black) => ChessPgnResult::BlackWins,
+            _ => ChessPgnResult::Draw,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        use ChessPgnResult::*;
+
+        match self {
+            WhiteWins => "1-0",
+            BlackWins => "0-1",
+            Draw => "1/2-1/2",
+            Unknown => "*",
+        }
+    }
+}
+
+impl ChessPgnGame {
+    // Creates a game from the default starting position, with the Seven Tag Roster set to unknown values
+    pub fn new() -> Self {
+        Self::from_gamestate(ChessGamestate::new())
+    }
+
+    // Creates a game which starts from the given position
+    // Any moves in the gamestate's history are not part of the game
+    pub fn from_gamestate(mut starting_gamestate: ChessGamestate) -> Self {
+        starting_gamestate.clear_history();
+
+        ChessPgnGame {
+            tags: SEVEN_TAG_ROSTER
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            result: ChessPgnResult::Unknown,
+            current_gamestate: starting_gamestate.clone(),
+            starting_gamestate,
+        }
+    }
+
+    // Gets the value of a tag, if it is set
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag_name, _)| tag_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    // Sets the value of a tag, replacing the existing value if there is one
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag_name, _)| tag_name == name) {
+            Some(tag) => tag.1 = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    // Performs a move on the game's current position and records it
+    pub fn push_move(&mut self, move_to_push: &ChessMove) -> Result<(), ChessError> {
+        self.current_gamestate.perform_move(move_to_push)
+    }
+
+    // Takes back the game's last move, returning it if there was one
+    pub fn undo(&mut self) -> Option<ChessMove> {
+        self.current_gamestate.undo()
+    }
+
+    // Plays the most recently undone move again, returning it if there was one
+    pub fn redo(&mut self) -> Option<ChessMove> {
+        self.current_gamestate.redo()
+    }
+
+    // Ends the game in a draw, if the player whose turn it is is entitled to claim one
+    pub fn claim_draw(&mut self) -> Result<ChessDrawClaim, ChessError> {
+        self.current_gamestate.claim_draw()
+    }
+
+    pub fn moves(&self) -> Vec<ChessMove> {
+        self.current_gamestate
+            .history()
+            .iter()
+            .map(|record| record.performed_move)
+            .collect()
+    }
+
+    pub fn starting_gamestate(&self) -> &ChessGamestate {
+        &self.starting_gamestate
+    }
+
+    // Gets the position after all of the game's moves have been played
+    pub fn current_gamestate(&self) -> &ChessGamestate {
+        &self.current_gamestate
+    }
+
+    // Gets the game's moves in Standard Algebraic Notation
+    // The moves are replayed on a single gamestate, whose position after each move gives that move's suffix
+    pub fn san_moves(&self) -> Vec<String> {
+        let mut gamestate = self.starting_gamestate.clone();
+        let mut san_moves = Vec::new();
+
+        for record in self.current_gamestate.history() {
+            let played_move = &record.performed_move;
+
+            // Moves are only recorded after they are performed successfully, so they are always legal
+            let mut san = match played_move.san_without_suffix(&gamestate) {
+                Ok(san) => san,
+                Err(_) => unreachable!("[INTERNAL ERROR] Recorded move is not legal"),
+            };
+
+            let _ = gamestate.perform_move(played_move);
+
+            san.push_str(chess_san::san_suffix(&gamestate));
+            san_moves.push(san);
+        }
+
+        san_moves
+    }
+
+    // Parses the first game in a PGN string
+    pub fn parse(pgn: &str) -> Result<Self, ChessPgnError> {
+        Self::parse_all(pgn)?
+            .into_iter()
+            .next()
+            .ok_or(ChessPgnError::NoGame)
+    }
+
+    // Parses every game in a PGN string, such as the contents of a PGN database file
+    pub fn parse_all(pgn: &str) -> Result<Vec<Self>, ChessPgnError> {
+        let mut games = Vec::new();
+        let mut tokens = tokenize(pgn)?.into_iter().peekable();
+
+        while tokens.peek().is_some() {
+            let mut tags = Vec::new();
+
+            while let Some(PgnToken::Tag(name, value)) = tokens.peek() {
+                tags.push((name.clone(), value.clone()));
+                tokens.next();
+            }
+
+            let mut game = match tags.iter().find(|(name, _)| name == "FEN") {
+                Some((_, fen)) => Self::from_gamestate(
+                    ChessGamestate::from_fen(fen).map_err(ChessPgnError::InvalidFen)?,
+                ),
+                None => Self::new(),
+            };
+
+            for (name, value) in &tags {
+                game.set_tag(name, value);
+            }
+
+            // Chess960 games are marked by their Variant tag, as their FEN may not show that they follow its castling rules
+            if game.tag("Variant").is_some_and(is_chess960_variant) {
+                game.starting_gamestate.chess960 = true;
+                game.current_gamestate.chess960 = true;
+            }
+
+            // The movetext ends at the game termination marker, or at the start of the next game's tags
+            while let Some(token) = tokens.next_if(|token| !matches!(token, PgnToken::Tag(..))) {
+                match token {
+                    PgnToken::Move(san) => {
+                        let parsed_move = ChessMove::from_san(&san, &game.current_gamestate);
+
+                        match parsed_move.map(|parsed_move| game.push_move(&parsed_move)) {
+                            Ok(Ok(())) => {}
+                            _ => {
+                                return Err(ChessPgnError::IllegalMove {
+                                    ply: game.current_gamestate.history().len() + 1,
+                                    san,
+                                })
+                            }
+                        }
+                    }
+                    PgnToken::Result(result) => {
+                        game.result = result;
+                        break;
+                    }
+                    PgnToken::Tag(..) => unreachable!("[INTERNAL ERROR] Tag pair within movetext"),
+                }
+            }
+
+            // A game without a termination marker takes its result from the Result tag
+            if let Some(result) = game.tag("Result").and_then(ChessPgnResult::from) {
+                if game.result == ChessPgnResult::Unknown {
+                    game.result = result;
+                }
+            }
+
+            games.push(game);
+        }
+
+        Ok(games)
+    }
+
+    // Exports the game as PGN, with the Seven Tag Roster first and the movetext wrapped to 80 characters
+    pub fn to_pgn(&self) -> String {
+        let mut pgn = String::new();
+
+        let mut tags = self.tags.clone();
+
+        // The Result tag must always agree with the game termination marker
+        tags.retain(|(name, _)| name != "Result" && name != "SetUp" && name != "FEN");
+        tags.push(("Result".to_string(), self.result.as_str().to_string()));
+
+        let starting_fen = self.starting_gamestate.to_fen();
+
+        // A Chess960 game always gives its starting position, even when it is the standard one
+        if starting_fen != STARTING_FEN || self.starting_gamestate.chess960 {
+            tags.push(("SetUp".to_string(), "1".to_string()));
+            tags.push(("FEN".to_string(), starting_fen));
+        }
+
+        if self.starting_gamestate.chess960 && !tags.iter().any(|(name, _)| name == "Variant") {
+            tags.push(("Variant".to_string(), "Chess960".to_string()));
+        }
+
+        // Roster tags are written first in their standard order, then any others in their stored order
+        for (roster_name, default_value) in SEVEN_TAG_ROSTER {
+            let value = tags
+                .iter()
+                .find(|(name, _)| name == roster_name)
+                .map_or(default_value, |(_, value)| value.as_str());
+
+            pgn.push_str(&format_tag(roster_name, value));
+        }
+
+        for (name, value) in &tags {
+            if !SEVEN_TAG_ROSTER
+                .iter()
+                .any(|(roster_name, _)| roster_name == name)
+            {
+                pgn.push_str(&format_tag(name, value));
+            }
+        }
+
+        pgn.push('\n');
+
+        let mut movetext_tokens = Vec::new();
+        let mut fullmove_clock = self.starting_gamestate.fullmove_clock;
+        let mut turn_color = self.starting_gamestate.turn_color;
+
+        for (index, san) in self.san_moves().into_iter().enumerate() {
+            // Move numbers are given before White's moves, and before the first move if Black moves first
+            match turn_color {
+                ChessPieceColor::White => movetext_tokens.push(format!("{}.", fullmove_clock)),
+                ChessPieceColor::Black if index == 0 => {
+                    movetext_tokens.push(format!("{}...", fullmove_clock))
+                }
+                ChessPieceColor::Black => {}
+            }
+
+            movetext_tokens.push(san);
+
+            if turn_color == ChessPieceColor::Black {
+                fullmove_clock += 1;
+            }
+
+            turn_color = match turn_color {
+                ChessPieceColor::White => ChessPieceColor::Black,
+                ChessPieceColor::Black => ChessPieceColor::White,
+            };
+        }
+
+        movetext_tokens.push(self.result.as_str().to_string());
+
+        let mut line_length = 0;
+
+        for token in movetext_tokens {
+            if line_length > 0 && line_length + 1 + token.len() > MAX_LINE_LENGTH {
+                pgn.push('\n');
+                line_length = 0;
+            } else if line_length > 0 {
+                pgn.push(' ');
+                line_length += 1;
+            }
+
+            line_length += token.len();
+            pgn.push_str(&token);
+        }
+
+        pgn.push('\n');
+
+        pgn
+    }
+}
+
+impl Default for ChessPgnGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The meaningful parts of a PGN string, with comments, move numbers and annotations removed
+enum PgnToken {
+    Tag(String, String),
+    Move(String),
+    Result(ChessPgnResult),
+}
+
+// Splits a PGN string into tokens, skipping comments, variations, NAGs and escaped lines
+fn tokenize(pgn: &str) -> Result<Vec<PgnToken>, ChessPgnError> {
+    use ChessPgnError::*;
+
+    let mut tokens = Vec::new();
+
+    // Lines beginning with '%' are escaped and must be ignored entirely
+    let unescaped_pgn: Vec<&str> = pgn.lines().filter(|line| !line.starts_with('%')).collect();
+    let unescaped_pgn = unescaped_pgn.join("\n");
+
+    let mut chars = unescaped_pgn.chars().peekable();
+
+    while let Some(current_char) = chars.next() {
+        match current_char {
+            '[' => {
+                let tag_pair = scan_tag_pair(&mut chars).ok_or(InvalidTagPair)?;
+                tokens.push(parse_tag_pair(&tag_pair).ok_or(InvalidTagPair)?);
+            }
+            '{' => {
+                if !chars.by_ref().any(|c| c == '}') {
+                    return Err(UnterminatedComment);
+                }
+            }
+            ';' => {
+                chars.by_ref().find(|c| *c == '\n');
+            }
+            // Variations may be nested, and are skipped entirely as only the main line is kept
+            '(' => {
+                let mut depth = 1;
+
+                while depth > 0 {
+                    match chars.next() {
+                        Some('(') => depth += 1,
+                        Some(')') => depth -= 1,
+                        Some('{') => {
+                            if !chars.by_ref().any(|c| c == '}') {
+                                return Err(UnterminatedComment);
+                            }
+                        }
+                        Some(_) => {}
+                        None => return Err(UnterminatedVariation),
+                    }
+                }
+            }
+            c if c.is_whitespace() => {}
+            _ => {
+                let mut symbol = current_char.to_string();
+
+                while let Some(c) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '[' | '{' | ';' | '(' | ')') {
+                        break;
+                    }
+
+                    symbol.push(*c);
+                    chars.next();
+                }
+
+                if let Some(token) = parse_movetext_symbol(&symbol) {
+                    tokens.push(token);
+                }
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+// Reads the contents of a tag pair up to its closing bracket, which does not count while inside the quoted value
+// Escapes are kept as they are, so that an escaped quote does not end the value early
+fn scan_tag_pair(chars: &mut impl Iterator<Item = char>) -> Option<String> {
+    let mut tag_pair = String::new();
+    let mut in_value = false;
+
+    while let Some(current_char) = chars.next() {
+        match current_char {
+            ']' if !in_value => return Some(tag_pair),
+            '"' => in_value = !in_value,
+            '\\' if in_value => {
+                tag_pair.push(current_char);
+                tag_pair.push(chars.next()?);
+                continue;
+            }
+            _ => {}
+        }
+
+        tag_pair.push(current_char);
+    }
+
+    // The PGN ended before the tag pair was closed
+    None
+}
+
+// Parses the contents of a tag pair, like 'Event "Casual Game"', without the surrounding brackets
+fn parse_tag_pair(tag_pair: &str) -> Option<PgnToken> {
+    let (name, quoted_value) = tag_pair.trim().split_once(char::is_whitespace)?;
+    let quoted_value = quoted_value.trim();
+
+    if name.is_empty() || quoted_value.len() < 2 {
+        return None;
+    }
+
+    let escaped_value = quoted_value.strip_prefix('"')?.strip_suffix('"')?;
+
+    // Backslashes escape the character after them, and any other quote would have ended the value
+    let mut value = String::new();
+    let mut value_chars = escaped_value.chars();
+
+    while let Some(value_char) = value_chars.next() {
+        match value_char {
+            '\\' => value.push(value_chars.next()?),
+            '"' => return None,
+            _ => value.push(value_char),
+        }
+    }
+
+    Some(PgnToken::Tag(name.to_string(), value))
+}
+
+// Parses a single movetext symbol, returning None for symbols without meaning to the game,
+// such as move numbers and NAGs
+fn parse_movetext_symbol(symbol: &str) -> Option<PgnToken> {
+    if let Some(result) = ChessPgnResult::from(symbol) {
+        return Some(PgnToken::Result(result));
+    }
+
+    // Castling may be written with zeros, which must not be mistaken for a move number
+    if symbol.starts_with("0-0") {
+        return Some(PgnToken::Move(symbol.to_string()));
+    }
+
+    // NAGs like "$1" are annotations only
+    if symbol.starts_with('$') {
+        return None;
+    }
+
+    // Move numbers may be attached directly to the move, like "1.e4" or "12...Nf6"
+    let san = symbol.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+
+    match san.is_empty() {
+        true => None,
+        false => Some(PgnToken::Move(san.to_string())),
+    }
+}
+
+// Formats a tag pair for export, escaping quotes and backslashes in the value
+fn format_tag(name: &str, value: &str) -> String {
+    format!(
+        "[{} \"{}\"]\n",
+        name,
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+// Checks if a Variant tag names Chess960, which is written in several ways, like "Chess960" or "Fischerandom"
+fn is_chess960_variant(variant: &str) -> bool {
+    let variant = variant.to_ascii_lowercase();
+
+    variant.contains("960") || variant.contains("fischer")
+}
@@ -8,7 +8,7 @@ pub struct ChessPiece {
     pub move_count: u32,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ChessPieceKind {
     Pawn,
     Rook,
@@ -18,12 +18,65 @@ pub enum ChessPieceKind {
     King,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ChessPieceColor {
     White,
     Black,
 }
 
+impl ChessPieceKind {
+    // The piece kinds that a Pawn can be promoted to, from most to least valuable
+    pub const PROMOTION_TARGETS: [ChessPieceKind; 4] = [
+        ChessPieceKind::Queen,
+        ChessPieceKind::Rook,
+        ChessPieceKind::Bishop,
+        ChessPieceKind::Knight,
+    ];
+
+    // Parses a piece kind from its algebraic notation letter, like 'N' or 'q'
+    pub fn from_char(piece_char: char) -> Option<Self> {
+        use ChessPieceKind::*;
+
+        match piece_char.to_ascii_lowercase() {
+            'p' => Some(Pawn),
+            'r' => Some(Rook),
+            'n' => Some(Knight),
+            'b' => Some(Bishop),
+            'q' => Some(Queen),
+            'k' => Some(King),
+            _ => None,
+        }
+    }
+
+    // Gets the algebraic notation letter for the piece kind, which is always uppercase
+    pub fn get_kind_char(&self) -> char {
+        use ChessPieceKind::*;
+
+        match self {
+            Pawn => 'P',
+            Rook => 'R',
+            Knight => 'N',
+            Bishop => 'B',
+            Queen => 'Q',
+            King => 'K',
+        }
+    }
+
+    // Checks whether a Pawn is allowed to be promoted to this piece kind
+    pub fn is_promotion_target(&self) -> bool {
+        use ChessPieceKind::*;
+
+        matches!(self, Rook | Knight | Bishop | Queen)
+    }
+}
+
+// Formats a piece kind as its name, like "Knight"
+impl std::fmt::Display for ChessPieceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
 impl ChessPiece {
     pub fn new(kind: ChessPieceKind, color: ChessPieceColor) -> Self {
         ChessPiece {
@@ -56,9 +109,9 @@ impl ChessPiece {
 
     // Checks whether the piece can move to the given square, based only on its movement patterns
     pub fn can_make_move(&self, queried_move: &ChessMove) -> MovePatternValidity {
-        let change_in_x: u8 = queried_move.change_in_x().abs() as u8;
+        let change_in_x: u8 = queried_move.change_in_x().unsigned_abs();
         let change_in_y_unadjusted = queried_move.change_in_y();
-        let change_in_y: u8 = change_in_y_unadjusted.abs() as u8;
+        let change_in_y: u8 = change_in_y_unadjusted.unsigned_abs();
         // Y-axis pawn movement rules are inverted for black pawns
         let change_in_y_color_adjusted = match self.color {
             ChessPieceColor::White => change_in_y_unadjusted,
@@ -125,6 +178,7 @@ impl ChessPiece {
                 }
             }
             King => {
+                // Castling is recognised and checked by the gamestate before the move pattern, so it is not allowed here
                 let king_move = (change_in_x <= 1 && change_in_y <= 1)
                     && !(change_in_x == 0 && change_in_y == 0);
 
@@ -140,4 +194,9 @@ impl ChessPiece {
     pub fn increment_move_count(&mut self) {
         self.move_count += 1;
     }
+
+    // Reverts the move count of the piece when one of its moves is unmade
+    pub fn decrement_move_count(&mut self) {
+        self.move_count -= 1;
+    }
 }
@@ -1,3 +1,7 @@
+use crate::ChessParseError;
+
+use std::str::FromStr;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ChessPoint {
     x: usize,
@@ -16,18 +20,14 @@ impl ChessPoint {
         ChessPoint { x, y }
     }
 
-    // Parse a point from a tile position string, like "e2"
-    pub fn from(encoded_point: &str) -> Self {
-        let mut chars = encoded_point.chars();
-
-        let x = chars.next().unwrap();
-        let y = chars.next().unwrap();
-
-        // These are ASCII values, so we can subtract 97 and 49 to get the actual values
-        let x = x as usize - 97;
-        let y = y as usize - 49;
+    // Creates a point from its index in a bitboard, where a1 is 0, b1 is 1 and h8 is 63
+    pub fn from_index(index: usize) -> ChessPoint {
+        ChessPoint::new(index % 8, index / 8)
+    }
 
-        ChessPoint::new(x, y)
+    // Gets the index of the point in a bitboard, where a1 is 0, b1 is 1 and h8 is 63
+    pub fn index(&self) -> usize {
+        8 * self.y + self.x
     }
 
     pub fn x(&self) -> usize {
@@ -77,8 +77,8 @@ impl ChessPoint {
 
         // Each coordinate must be incremented by 1 or -1, depending on the direction
         // of travel, or 0 if the piece is not moving in that direction
-        let x_increment = (change_in_x as i32).signum();
-        let y_increment = (change_in_y as i32).signum();
+        let x_increment = change_in_x.signum();
+        let y_increment = change_in_y.signum();
 
         while source_x != destination_x || source_y != destination_y {
             source_x += x_increment;
@@ -94,6 +94,55 @@ impl ChessPoint {
     }
 }
 
+// Parses a point from a tile position string, like "e2"
+impl FromStr for ChessPoint {
+    type Err = ChessParseError;
+
+    fn from_str(encoded_point: &str) -> Result<Self, Self::Err> {
+        use ChessParseError::*;
+
+        let mut chars = encoded_point.chars();
+
+        let x = chars.next().ok_or(Empty)?;
+        let y = chars.next().ok_or(Incomplete)?;
+
+        if !('a'..='h').contains(&x) {
+            return Err(InvalidFile(x));
+        }
+
+        if !('1'..='8').contains(&y) {
+            return Err(InvalidRank(y));
+        }
+
+        let trailing_characters = chars.as_str();
+
+        if !trailing_characters.is_empty() {
+            return Err(TrailingCharacters(trailing_characters.to_string()));
+        }
+
+        // The characters are known to be in range, so their offsets from 'a' and '1' are valid coordinates
+        Ok(ChessPoint::new(
+            x as usize - 'a' as usize,
+            y as usize - '1' as usize,
+        ))
+    }
+}
+
+impl TryFrom<&str> for ChessPoint {
+    type Error = ChessParseError;
+
+    fn try_from(encoded_point: &str) -> Result<Self, Self::Error> {
+        encoded_point.parse()
+    }
+}
+
 fn validate_bounds(x: usize, y: usize) -> bool {
     x < 8 && y < 8
 }
+
+// Formats a point as a tile position string, like "e2"
+impl std::fmt::Display for ChessPoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}{}", (b'a' + self.x as u8) as char, self.y + 1)
+    }
+}
@@ -0,0 +1,264 @@
+use crate::ChessGameStatus;
+use crate::ChessGamestate;
+use crate::ChessMove;
+use crate::ChessPieceKind;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChessSanError {
+    // The string is not valid SAN, like "Zz9" or "e"
+    Malformed,
+    // No legal move in the position matches the string
+    NoMatchingMove,
+    // More than one legal move matches the string, so more disambiguation is needed
+    AmbiguousMove,
+}
+
+impl std::fmt::Display for ChessSanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        use ChessSanError::*;
+
+        match self {
+            Malformed => write!(f, "Enter a move in SAN, like \"Nf3\" or \"exd5\"."),
+            NoMatchingMove => write!(f, "That move is not legal in this position."),
+            AmbiguousMove => write!(
+                f,
+                "More than one piece can make that move; add its file or rank, like \"Nbd7\"."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ChessSanError {}
+
+impl ChessMove {
+    // Finds the legal move described by a Standard Algebraic Notation string, like "Nf3", "exd5", "O-O" or "e8=Q+"
+    // SAN only describes a move relative to a position, so the gamestate is needed to find the moved piece
+    pub fn from_san(san: &str, gamestate: &ChessGamestate) -> Result<Self, ChessSanError> {
+        use ChessSanError::*;
+
+        // Check, mate and annotation suffixes do not affect which move is described
+        let san = san.trim().trim_end_matches(['+', '#', '!', '?']);
+
+        if matches!(san, "O-O" | "0-0" | "O-O-O" | "0-0-0") {
+            let castle_is_kingside = san.len() == 3;
+
+            // The castling move is found by its side rather than its destination, which differs in Chess960
+            return gamestate
+                .legal_moves()
+                .into_iter()
+                .find(|legal_move| {
+                    gamestate.is_castling(legal_move)
+                        && (legal_move.change_in_x() > 0) == castle_is_kingside
+                })
+                .ok_or(NoMatchingMove);
+        }
+
+        let mut chars: Vec<char> = san.chars().collect();
+
+        // The piece letter is omitted for Pawn moves
+        let moved_kind = match chars.first() {
+            Some(piece_char) if piece_char.is_ascii_uppercase() => {
+                let kind = ChessPieceKind::from_char(*piece_char).ok_or(Malformed)?;
+                chars.remove(0);
+                kind
+            }
+            _ => ChessPieceKind::Pawn,
+        };
+
+        // The promotion piece is given at the end, usually after an '='
+        let promotion = match chars.last() {
+            Some(piece_char) if piece_char.is_ascii_uppercase() => {
+                let kind = ChessPieceKind::from_char(*piece_char).ok_or(Malformed)?;
+                chars.pop();
+
+                if chars.last() == Some(&'=') {
+                    chars.pop();
+                }
+
+                Some(kind)
+            }
+            _ => None,
+        };
+
+        // The destination is always the last two characters, and anything before it is disambiguation
+        if chars.len() < 2 {
+            return Err(Malformed);
+        }
+
+        let destination = chars.split_off(chars.len() - 2);
+
+        if !is_file_char(destination[0]) || !is_rank_char(destination[1]) {
+            return Err(Malformed);
+        }
+
+        let destination: String = destination.into_iter().collect();
+
+        // A capture marker may only come directly before the destination
+        if chars.iter().rev().skip(1).any(|c| *c == 'x') {
+            return Err(Malformed);
+        }
+
+        let disambiguation: Vec<char> = chars.into_iter().filter(|c| *c != 'x').collect();
+
+        let (source_file, source_rank) = match disambiguation.as_slice() {
+            [] => (None, None),
+            [file] if is_file_char(*file) => (Some(*file), None),
+            [rank] if is_rank_char(*rank) => (None, Some(*rank)),
+            [file, rank] if is_file_char(*file) && is_rank_char(*rank) => {
+                (Some(*file), Some(*rank))
+            }
+            _ => return Err(Malformed),
+        };
+
+        let mut matching_moves = gamestate.legal_moves().into_iter().filter(|legal_move| {
+            let moved_piece = gamestate.board.piece_at(legal_move.source()).unwrap();
+            let source = legal_move.source().to_string();
+
+            // Castling is only written as O-O or O-O-O, even when it is entered as a King move
+            moved_piece.kind == moved_kind
+                && !gamestate.is_castling(legal_move)
+                && legal_move.destination().to_string() == destination
+                && legal_move.promotion() == promotion
+                && source_file.is_none_or(|file| source.starts_with(file))
+                && source_rank.is_none_or(|rank| source.ends_with(rank))
+        });
+
+        let found_move = matching_moves.next().ok_or(NoMatchingMove)?;
+
+        match matching_moves.next() {
+            Some(_) => Err(AmbiguousMove),
+            None => Ok(found_move),
+        }
+    }
+
+    // Formats the move in Standard Algebraic Notation, including disambiguation and check or mate suffixes
+    // SAN can only describe a legal move, so any other move is reported as NoMatchingMove
+    pub fn to_san(&self, gamestate: &ChessGamestate) -> Result<String, ChessSanError> {
+        let mut san = self.san_without_suffix(gamestate)?;
+
+        // The move is performed on a copy of the gamestate to find out whether it gives check or mate
+        let mut resulting_gamestate = gamestate.clone();
+
+        if resulting_gamestate.perform_move(self).is_ok() {
+            san.push_str(san_suffix(&resulting_gamestate));
+        }
+
+        Ok(san)
+    }
+
+    // Formats the move in SAN without the check or mate suffix, which depends on the position after the move
+    // This allows a caller which performs the move anyway to find the suffix without copying the gamestate
+    pub(crate) fn san_without_suffix(
+        &self,
+        gamestate: &ChessGamestate,
+    ) -> Result<String, ChessSanError> {
+        let legal_moves = gamestate.legal_moves();
+
+        if !legal_moves.contains(self) {
+            return Err(ChessSanError::NoMatchingMove);
+        }
+
+        let moved_piece = gamestate
+            .board
+            .piece_at(self.source())
+            .ok_or(ChessSanError::NoMatchingMove)?;
+
+        let move_is_capture = gamestate.board.piece_at(self.destination()).is_some()
+            || (moved_piece.kind == ChessPieceKind::Pawn && self.change_in_x() != 0);
+
+        let mut san = String::new();
+
+        match moved_piece.kind {
+            ChessPieceKind::King if gamestate.is_castling(self) => {
+                san.push_str(match self.change_in_x() > 0 {
+                    true => "O-O",
+                    false => "O-O-O",
+                });
+            }
+            ChessPieceKind::Pawn => {
+                // Pawn captures are identified by the file the Pawn came from
+                if move_is_capture {
+                    san.push_str(&self.source().to_string()[0..1]);
+                    san.push('x');
+                }
+
+                san.push_str(&self.destination().to_string());
+
+                if let Some(promotion) = self.promotion() {
+                    san.push('=');
+                    san.push(promotion.get_kind_char());
+                }
+            }
+            _ => {
+                san.push(moved_piece.kind.get_kind_char());
+                san.push_str(&get_disambiguation(
+                    gamestate,
+                    &legal_moves,
+                    self,
+                    moved_piece.kind,
+                ));
+
+                if move_is_capture {
+                    san.push('x');
+                }
+
+                san.push_str(&self.destination().to_string());
+            }
+        }
+
+        Ok(san)
+    }
+}
+
+// Gets the SAN suffix for the move which led to the given gamestate: '#' for mate, '+' for check, or nothing
+pub(crate) fn san_suffix(resulting_gamestate: &ChessGamestate) -> &'static str {
+    if let ChessGameStatus::Checkmate(_) = resulting_gamestate.status() {
+        "#"
+    } else if resulting_gamestate.is_in_check() {
+        "+"
+    } else {
+        ""
+    }
+}
+
+fn is_file_char(file_char: char) -> bool {
+    ('a'..='h').contains(&file_char)
+}
+
+fn is_rank_char(rank_char: char) -> bool {
+    ('1'..='8').contains(&rank_char)
+}
+
+// Gets the shortest source description which distinguishes a piece move from moves
+// of other pieces of the same kind to the same destination
+fn get_disambiguation(
+    gamestate: &ChessGamestate,
+    legal_moves: &[ChessMove],
+    formatted_move: &ChessMove,
+    moved_kind: ChessPieceKind,
+) -> String {
+    let other_sources: Vec<_> = legal_moves
+        .iter()
+        .filter(|legal_move| {
+            legal_move.destination() == formatted_move.destination()
+                && legal_move.source() != formatted_move.source()
+                && gamestate
+                    .board
+                    .piece_at(legal_move.source())
+                    .is_some_and(|piece| piece.kind == moved_kind)
+        })
+        .map(|legal_move| *legal_move.source())
+        .collect();
+
+    let source = formatted_move.source();
+
+    if other_sources.is_empty() {
+        String::new()
+    } else if other_sources.iter().all(|other| other.x() != source.x()) {
+        source.to_string()[0..1].to_string()
+    } else if other_sources.iter().all(|other| other.y() != source.y()) {
+        source.to_string()[1..2].to_string()
+    } else {
+        source.to_string()
+    }
+}
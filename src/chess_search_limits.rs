@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+// The limits on how long the engine may search for a move
+// The search stops at whichever limit is reached first, and only stops on its own if neither is given
+// once it reaches its maximum depth
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChessSearchLimits {
+    // The number of moves to look ahead, not counting captures at the end of a line
+    pub depth: Option<u32>,
+    // The time to spend searching
+    pub move_time: Option<Duration>,
+}
+
+impl ChessSearchLimits {
+    // The depth searched to when a protocol asks for a move without giving any limit, as the search would never end
+    pub const DEFAULT_DEPTH: u32 = 6;
+
+    // Creates limits which search to the given depth, however long it takes
+    pub fn depth(depth: u32) -> Self {
+        ChessSearchLimits {
+            depth: Some(depth),
+            move_time: None,
+        }
+    }
+
+    // Creates limits which search for the given amount of time, however deep that reaches
+    pub fn move_time(move_time: Duration) -> Self {
+        ChessSearchLimits {
+            depth: None,
+            move_time: Some(move_time),
+        }
+    }
+
+    // Creates limits for a player with the given time left on their clock, who gains the increment after each move
+    // If the number of moves until the next time control is unknown, the time is spread over a typical number of moves
+    pub fn from_clock(time_left: Duration, increment: Duration, moves_to_go: Option<u32>) -> Self {
+        const TYPICAL_MOVES_TO_GO: u32 = 30;
+        // Kept in reserve for the time it takes to send the move, so that the flag does not fall while sending it
+        const MOVE_OVERHEAD: Duration = Duration::from_millis(50);
+
+        let moves_to_go = moves_to_go.unwrap_or(TYPICAL_MOVES_TO_GO).max(1);
+        let move_time = time_left / moves_to_go + increment * 3 / 4;
+
+        // Never plan to use more than most of the remaining time, however large the increment is
+        let usable_time = (time_left * 4 / 5).saturating_sub(MOVE_OVERHEAD);
+
+        ChessSearchLimits::move_time(move_time.min(usable_time).max(Duration::from_millis(1)))
+    }
+}
@@ -0,0 +1,39 @@
+use crate::ChessEngine;
+use crate::ChessMove;
+
+// The outcome of a search, or of one iteration of it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChessSearchResult {
+    // The best move found, or None if the player whose turn it is has no legal moves
+    pub best_move: Option<ChessMove>,
+    // The score of the best move for the player whose turn it is, in centipawns
+    // Checkmates are scored as ChessEngine::MATE_SCORE minus the number of moves until mate
+    pub score: i32,
+    // The depth of the last completed iteration
+    pub depth: u32,
+    // The number of positions visited
+    pub nodes: u64,
+    // How full the transposition table is, in permille
+    pub hashfull: u32,
+    // The line of play expected from both players, starting with the best move
+    pub principal_variation: Vec<ChessMove>,
+}
+
+impl ChessSearchResult {
+    // Gets the number of moves (not plies) until the side to move delivers checkmate, or is checkmated
+    // if the result is negative, or None if the score is not a checkmate score
+    pub fn mate_in(&self) -> Option<i32> {
+        let plies_to_mate = ChessEngine::MATE_SCORE - self.score.abs();
+
+        if plies_to_mate > ChessEngine::MAX_PLY as i32 {
+            return None;
+        }
+
+        let moves_to_mate = (plies_to_mate + 1) / 2;
+
+        match self.score > 0 {
+            true => Some(moves_to_mate),
+            false => Some(-moves_to_mate),
+        }
+    }
+}
@@ -0,0 +1,1239 @@
+use crate::ChessGamestate;
+use crate::ChessPieceColor;
+use crate::ChessPieceKind;
+use crate::ChessTablebaseError;
+use crate::ChessWdl;
+
+// A single Syzygy WDL or DTZ table, read into memory and decoded far enough that positions can be looked up in it
+//
+// A table stores one value for every placement of its pieces, after removing the placements which are mirror images
+// of one another. The placement is turned into an index by grouping the pieces, and the values are compressed by
+// recursive pairing (repeatedly replacing the most common pair of adjacent symbols with a new symbol) followed by a
+// canonical Huffman code. The values are split into blocks, and a sparse index gives the block of every span-th value.
+//
+// Tables with Pawns are split into four parts, one for each file the leading Pawn can be on once mirrored onto the
+// a-d files. WDL tables also store both sides to move, unless both sides have the same pieces, while DTZ tables only
+// store whichever side to move compressed better.
+//
+// The layout follows the reference probing code that comes with the tables, in which tiles are numbered a1 = 0 to
+// h8 = 63, and pieces are coded 1 to 6 for Pawn, Knight, Bishop, Rook, Queen and King, plus 8 for Black
+pub(crate) struct ChessSyzygyTable {
+    // The file name, like "KQvKR.rtbw", which is given in errors about the table's contents
+    file_name: String,
+    kind: ChessSyzygyKind,
+    bytes: Vec<u8>,
+    piece_count: usize,
+    has_pawns: bool,
+    // Whether any piece other than a King is the only one of its kind and color, as then three pieces are indexed together
+    has_unique_pieces: bool,
+    // Whether both sides have the same pieces, in which case only White to move is stored
+    symmetric: bool,
+    // Whether both sides have Pawns, in which case the other side's Pawns have a group of their own
+    both_sides_have_pawns: bool,
+    // The compressed values of each stored side to move and each file of the leading Pawn, indexed [side][file]
+    pairs: Vec<Vec<ChessPairsData>>,
+    // The offset of the DTZ maps, which give the stored values' actual distances
+    map_offset: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ChessSyzygyKind {
+    Wdl,
+    Dtz,
+}
+
+// The values of one side to move and one file, and what is needed to find the value of an index among them
+#[derive(Default)]
+struct ChessPairsData {
+    flags: u8,
+    // The piece codes in the order the pieces are indexed
+    pieces: [u8; MAX_PIECES],
+    // The number of pieces in each group, and the factor each group's index is multiplied by
+    // The last factor is the number of indices in the table
+    group_lengths: Vec<usize>,
+    group_factors: Vec<u64>,
+    // The value of every index, if the table stores only a single value
+    single_value: Option<u16>,
+    block_size: u64,
+    span: u64,
+    sparse_index_size: usize,
+    block_count: usize,
+    block_lengths_size: usize,
+    min_symbol_length: u32,
+    // The offset of the lowest symbol of each code length
+    lowest_symbols_offset: usize,
+    // The lowest code of each length, left-aligned in 64 bits, from the shortest length to the longest
+    base: Vec<u64>,
+    // The number of values each symbol stands for, minus one
+    symbol_lengths: Vec<u32>,
+    // The offset of the symbol tree, which gives the pair of symbols each symbol stands for
+    tree_offset: usize,
+    sparse_index_offset: usize,
+    block_lengths_offset: usize,
+    data_offset: usize,
+    // For mapped DTZ tables, the start of the map of each WDL result, in map entries from the start of the maps
+    map_indices: [usize; 4],
+}
+
+// The flags of a side and file of a table, which are only set in DTZ tables apart from SINGLE_VALUE
+const FLAG_BLACK_TO_MOVE: u8 = 1;
+const FLAG_MAPPED: u8 = 2;
+const FLAG_WIN_PLIES: u8 = 4;
+const FLAG_LOSS_PLIES: u8 = 8;
+const FLAG_WIDE: u8 = 16;
+const FLAG_SINGLE_VALUE: u8 = 128;
+
+// The flags at the start of a table
+const TABLE_FLAG_HAS_PAWNS: u8 = 2;
+
+const MAX_PIECES: usize = 7;
+
+// A symbol tree entry whose right symbol is this stands for a single value rather than a pair of symbols
+const LEAF_SYMBOL: u16 = 0xFFF;
+
+static BINOMIAL: [[u64; 64]; MAX_PIECES + 1] = binomial();
+static TRIANGLE: [usize; 64] = triangle();
+static BELOW_DIAGONAL: [usize; 64] = below_diagonal();
+static KING_PAIRS: [[usize; 64]; 10] = king_pairs();
+static PAWN_ORDER: [usize; 64] = pawn_order();
+static LEAD_PAWN_INDICES: [[u64; 64]; 6] = lead_pawn_indices();
+static LEAD_PAWN_FILE_SIZES: [[u64; 4]; 6] = lead_pawn_file_sizes();
+
+impl ChessSyzygyTable {
+    // Reads a table from the contents of its file, given the name of the material it covers, like "KQvKR"
+    // The magic number has already been checked when the tablebase was opened
+    pub(crate) fn from_bytes(
+        material_name: &str,
+        kind: ChessSyzygyKind,
+        bytes: Vec<u8>,
+    ) -> Result<Self, ChessTablebaseError> {
+        let file_name = match kind {
+            ChessSyzygyKind::Wdl => format!("{}.rtbw", material_name),
+            ChessSyzygyKind::Dtz => format!("{}.rtbz", material_name),
+        };
+
+        let (white_pieces, black_pieces) = match material_name.split_once('v') {
+            Some(sides) => sides,
+            None => return Err(ChessTablebaseError::InvalidTable(file_name)),
+        };
+
+        let pawn_count = |side: &str| side.matches('P').count();
+
+        // Every non-King piece which is the only one of its kind and color counts, but Kings do not
+        let has_unique_pieces = [white_pieces, black_pieces].iter().any(|side| {
+            "PNBRQ"
+                .chars()
+                .any(|letter| side.matches(letter).count() == 1)
+        });
+
+        let mut table = ChessSyzygyTable {
+            kind,
+            bytes,
+            piece_count: white_pieces.len() + black_pieces.len(),
+            has_pawns: pawn_count(white_pieces) + pawn_count(black_pieces) > 0,
+            has_unique_pieces,
+            symmetric: white_pieces == black_pieces,
+            both_sides_have_pawns: pawn_count(white_pieces) > 0 && pawn_count(black_pieces) > 0,
+            pairs: Vec::new(),
+            map_offset: 0,
+            file_name,
+        };
+
+        table.read_layout()?;
+
+        Ok(table)
+    }
+
+    // Gets the result of a position for the player whose turn it is
+    // If the table's pieces are the other way around from the position's, the colors are swapped before looking it up
+    pub(crate) fn probe_wdl(
+        &self,
+        gamestate: &ChessGamestate,
+        colors_swapped: bool,
+    ) -> Result<ChessWdl, ChessTablebaseError> {
+        let (value, _) = match self.stored_value(gamestate, colors_swapped)? {
+            Some(stored_value) => stored_value,
+            None => unreachable!("[INTERNAL ERROR] WDL table is missing a side to move"),
+        };
+
+        wdl_from_score(value as i32 - 2).ok_or_else(|| self.invalid())
+    }
+
+    // Gets the number of plies to the next capture or Pawn move of a position with the given result
+    // DTZ tables only store one side to move, so this gives None if the position has the other side to move
+    pub(crate) fn probe_dtz(
+        &self,
+        gamestate: &ChessGamestate,
+        colors_swapped: bool,
+        wdl: ChessWdl,
+    ) -> Result<Option<i32>, ChessTablebaseError> {
+        let (value, file) = match self.stored_value(gamestate, colors_swapped)? {
+            Some(stored_value) => stored_value,
+            None => return Ok(None),
+        };
+
+        let pairs = &self.pairs[0][file];
+        let mut dtz = value as usize;
+
+        // Mapped tables store an index into the map of the position's result, rather than the distance itself
+        if pairs.flags & FLAG_MAPPED != 0 {
+            let map_index = match wdl {
+                ChessWdl::Loss => 1,
+                ChessWdl::BlessedLoss => 3,
+                ChessWdl::Draw => 0,
+                ChessWdl::CursedWin => 2,
+                ChessWdl::Win => 0,
+            };
+
+            let entry = pairs.map_indices[map_index] + dtz;
+
+            dtz = match pairs.flags & FLAG_WIDE != 0 {
+                true => self.read_u16_le(self.map_offset + 2 * entry)? as usize,
+                false => self.read_u8(self.map_offset + entry)? as usize,
+            };
+        }
+
+        // Distances are stored in moves rather than plies unless the flags say otherwise, to make them compress better
+        let stored_in_moves = match wdl {
+            ChessWdl::Win => pairs.flags & FLAG_WIN_PLIES == 0,
+            ChessWdl::Loss => pairs.flags & FLAG_LOSS_PLIES == 0,
+            ChessWdl::CursedWin | ChessWdl::BlessedLoss => true,
+            ChessWdl::Draw => false,
+        };
+
+        if stored_in_moves {
+            dtz *= 2;
+        }
+
+        Ok(Some(dtz as i32 + 1))
+    }
+
+    // Reads where everything is in the file: the piece order and groups of each side and file, the sizes of the
+    // compressed values, the DTZ maps, the sparse indices, the block lengths, and the compressed blocks themselves
+    fn read_layout(&mut self) -> Result<(), ChessTablebaseError> {
+        // The magic number comes first, then the table's own flags
+        let mut offset = 4;
+        let table_flags = self.read_u8(offset)?;
+        offset += 1;
+
+        if (table_flags & TABLE_FLAG_HAS_PAWNS != 0) != self.has_pawns {
+            return Err(self.invalid());
+        }
+
+        let side_count = match (self.kind, self.symmetric) {
+            (ChessSyzygyKind::Wdl, false) => 2,
+            _ => 1,
+        };
+
+        let file_count = match self.has_pawns {
+            true => 4,
+            false => 1,
+        };
+
+        self.pairs = (0..side_count)
+            .map(|_| (0..file_count).map(|_| ChessPairsData::default()).collect())
+            .collect();
+
+        // Each byte holds the first side's value in its low four bits and the second side's in its high four bits
+        for file in 0..file_count {
+            let first_order = self.read_u8(offset)?;
+            let second_order = match self.both_sides_have_pawns {
+                true => self.read_u8(offset + 1)?,
+                false => 0xFF,
+            };
+            offset += 1 + self.both_sides_have_pawns as usize;
+
+            for piece_index in 0..self.piece_count {
+                let piece_byte = self.read_u8(offset)?;
+                offset += 1;
+
+                for side in 0..side_count {
+                    self.pairs[side][file].pieces[piece_index] = match side {
+                        0 => piece_byte & 0xF,
+                        _ => piece_byte >> 4,
+                    };
+                }
+            }
+
+            for side in 0..side_count {
+                let order = match side {
+                    0 => [first_order & 0xF, second_order & 0xF],
+                    _ => [first_order >> 4, second_order >> 4],
+                };
+
+                self.set_groups(side, file, order)?;
+            }
+        }
+
+        offset += offset & 1;
+
+        for file in 0..file_count {
+            for side in 0..side_count {
+                offset = self.read_sizes(side, file, offset)?;
+            }
+        }
+
+        if self.kind == ChessSyzygyKind::Dtz {
+            offset = self.read_dtz_maps(offset, file_count)?;
+        }
+
+        for file in 0..file_count {
+            for side in 0..side_count {
+                let pairs = &mut self.pairs[side][file];
+                pairs.sparse_index_offset = offset;
+                offset += pairs.sparse_index_size * 6;
+            }
+        }
+
+        for file in 0..file_count {
+            for side in 0..side_count {
+                let pairs = &mut self.pairs[side][file];
+                pairs.block_lengths_offset = offset;
+                offset += pairs.block_lengths_size * 2;
+            }
+        }
+
+        // Each side and file's blocks start on a 64-byte boundary
+        for file in 0..file_count {
+            for side in 0..side_count {
+                offset = (offset + 0x3F) & !0x3F;
+
+                let pairs = &mut self.pairs[side][file];
+                pairs.data_offset = offset;
+                offset += pairs.block_count * pairs.block_size as usize;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Splits the pieces of a side and file into groups, and works out the factor each group's index is multiplied by
+    // The leading group is the leading Pawns, or the first two or three pieces (including both Kings) without Pawns,
+    // and each further group is a run of identical pieces. The order gives which position in the multiplication
+    // the leading group and the other side's Pawns take, which is chosen per table to compress best
+    fn set_groups(
+        &mut self,
+        side: usize,
+        file: usize,
+        order: [u8; 2],
+    ) -> Result<(), ChessTablebaseError> {
+        let invalid = self.invalid();
+        let has_pawns = self.has_pawns;
+        let has_unique_pieces = self.has_unique_pieces;
+        let both_sides_have_pawns = self.both_sides_have_pawns;
+        let piece_count = self.piece_count;
+        let pairs = &mut self.pairs[side][file];
+
+        let mut leading_pieces_left: i32 = match (has_pawns, has_unique_pieces) {
+            (true, _) => 0,
+            (false, true) => 3,
+            (false, false) => 2,
+        };
+
+        let mut group_lengths = vec![1];
+
+        for piece_index in 1..piece_count {
+            leading_pieces_left -= 1;
+
+            if leading_pieces_left > 0 || pairs.pieces[piece_index] == pairs.pieces[piece_index - 1]
+            {
+                *group_lengths.last_mut().unwrap() += 1;
+            } else {
+                group_lengths.push(1);
+            }
+        }
+
+        let group_count = group_lengths.len();
+        let mut group_factors = vec![0; group_count + 1];
+
+        let mut next_group = match both_sides_have_pawns {
+            true => 2,
+            false => 1,
+        };
+
+        let mut free_tiles = 64 - group_lengths[0];
+
+        if both_sides_have_pawns {
+            free_tiles -= *group_lengths.get(1).ok_or(invalid.clone())?;
+        }
+
+        let mut factor: u64 = 1;
+        let mut position = 0;
+
+        while next_group < group_count
+            || position == order[0] as usize
+            || position == order[1] as usize
+        {
+            if position == order[0] as usize {
+                group_factors[0] = factor;
+
+                factor *= match (has_pawns, has_unique_pieces) {
+                    (true, _) => *LEAD_PAWN_FILE_SIZES
+                        .get(group_lengths[0])
+                        .ok_or(invalid.clone())?
+                        .get(file)
+                        .ok_or(invalid.clone())?,
+                    (false, true) => 31332,
+                    (false, false) => 462,
+                };
+            } else if position == order[1] as usize {
+                let length = *group_lengths.get(1).ok_or(invalid.clone())?;
+
+                group_factors[1] = factor;
+                factor *=
+                    binomial_coefficient(length, 48 - group_lengths[0]).ok_or(invalid.clone())?;
+            } else {
+                let length = *group_lengths.get(next_group).ok_or(invalid.clone())?;
+
+                group_factors[next_group] = factor;
+                factor *= binomial_coefficient(length, free_tiles).ok_or(invalid.clone())?;
+                free_tiles = free_tiles.checked_sub(length).ok_or(invalid.clone())?;
+                next_group += 1;
+            }
+
+            position += 1;
+        }
+
+        group_factors[group_count] = factor;
+
+        pairs.group_lengths = group_lengths;
+        pairs.group_factors = group_factors;
+
+        Ok(())
+    }
+
+    // Reads the sizes of a side and file's compressed values, and its Huffman code and symbol tree, from the given offset
+    // Gives the offset just past them
+    fn read_sizes(
+        &mut self,
+        side: usize,
+        file: usize,
+        mut offset: usize,
+    ) -> Result<usize, ChessTablebaseError> {
+        let flags = self.read_u8(offset)?;
+        offset += 1;
+
+        if flags & FLAG_SINGLE_VALUE != 0 {
+            let single_value = self.read_u8(offset)? as u16;
+            let pairs = &mut self.pairs[side][file];
+
+            pairs.flags = flags;
+            pairs.single_value = Some(single_value);
+
+            return Ok(offset + 1);
+        }
+
+        let block_size_log = self.read_u8(offset)? as u32;
+        let span_log = self.read_u8(offset + 1)? as u32;
+        let padding = self.read_u8(offset + 2)? as usize;
+        let block_count = self.read_u32_le(offset + 3)? as usize;
+        let max_symbol_length = self.read_u8(offset + 7)? as u32;
+        let min_symbol_length = self.read_u8(offset + 8)? as u32;
+        offset += 9;
+
+        if block_size_log >= 32
+            || span_log >= 32
+            || min_symbol_length == 0
+            || max_symbol_length < min_symbol_length
+            || max_symbol_length >= 64
+        {
+            return Err(self.invalid());
+        }
+
+        let lowest_symbols_offset = offset;
+        let code_length_count = (max_symbol_length - min_symbol_length + 1) as usize;
+
+        // Longer codes have lower values in a canonical Huffman code, so the lowest code of each length is found from the
+        // longest length up; each is then left-aligned in 64 bits, so that a code's length can be found by comparison
+        let mut base = vec![0u64; code_length_count];
+
+        for length_index in (0..code_length_count - 1).rev() {
+            let lowest_symbol = self.read_u16_le(offset + 2 * length_index)? as u64;
+            let next_lowest_symbol = self.read_u16_le(offset + 2 * length_index + 2)? as u64;
+
+            base[length_index] = base[length_index + 1]
+                .wrapping_add(lowest_symbol)
+                .wrapping_sub(next_lowest_symbol)
+                / 2;
+        }
+
+        for (length_index, code) in base.iter_mut().enumerate() {
+            *code <<= 64 - length_index as u32 - min_symbol_length;
+        }
+
+        offset += code_length_count * 2;
+
+        let symbol_count = self.read_u16_le(offset)? as usize;
+        offset += 2;
+
+        let tree_offset = offset;
+        let mut symbol_lengths = vec![0; symbol_count];
+        let mut visited = vec![false; symbol_count];
+
+        for symbol in 0..symbol_count {
+            if !visited[symbol] {
+                symbol_lengths[symbol] =
+                    self.symbol_length(tree_offset, symbol, &mut symbol_lengths, &mut visited)?;
+            }
+        }
+
+        offset += symbol_count * 3 + (symbol_count & 1);
+
+        let table_size = *self.pairs[side][file].group_factors.last().unwrap();
+        let span = 1u64 << span_log;
+
+        let pairs = &mut self.pairs[side][file];
+
+        pairs.flags = flags;
+        pairs.block_size = 1 << block_size_log;
+        pairs.span = span;
+        // The sparse index has an entry for every span of values, and the block lengths are padded so that the last
+        // entry never points past them
+        pairs.sparse_index_size = table_size.div_ceil(span) as usize;
+        pairs.block_count = block_count;
+        pairs.block_lengths_size = block_count + padding;
+        pairs.min_symbol_length = min_symbol_length;
+        pairs.lowest_symbols_offset = lowest_symbols_offset;
+        pairs.base = base;
+        pairs.symbol_lengths = symbol_lengths;
+        pairs.tree_offset = tree_offset;
+
+        Ok(offset)
+    }
+
+    // Works out how many values a symbol stands for, minus one, by adding up the symbols it is the pair of
+    // The tree has no cycles, so each symbol is marked as visited before its children are followed
+    fn symbol_length(
+        &self,
+        tree_offset: usize,
+        symbol: usize,
+        symbol_lengths: &mut [u32],
+        visited: &mut [bool],
+    ) -> Result<u32, ChessTablebaseError> {
+        visited[symbol] = true;
+
+        let (left, right) = self.symbol_pair(tree_offset, symbol)?;
+
+        if right == LEAF_SYMBOL {
+            return Ok(0);
+        }
+
+        for child in [left, right] {
+            let child = child as usize;
+
+            if child >= symbol_lengths.len() {
+                return Err(self.invalid());
+            }
+
+            if !visited[child] {
+                symbol_lengths[child] =
+                    self.symbol_length(tree_offset, child, symbol_lengths, visited)?;
+            }
+        }
+
+        Ok(symbol_lengths[left as usize] + symbol_lengths[right as usize] + 1)
+    }
+
+    // Reads the DTZ maps, which translate the stored values of mapped tables into distances, from the given offset
+    // Each file with a mapped table has four maps, for wins, losses, cursed wins and blessed losses in that order,
+    // each made of its length followed by its entries, which are two bytes each in wide tables
+    fn read_dtz_maps(
+        &mut self,
+        mut offset: usize,
+        file_count: usize,
+    ) -> Result<usize, ChessTablebaseError> {
+        self.map_offset = offset;
+
+        for file in 0..file_count {
+            let flags = self.pairs[0][file].flags;
+
+            if flags & FLAG_MAPPED == 0 {
+                continue;
+            }
+
+            for map_index in 0..4 {
+                if flags & FLAG_WIDE != 0 {
+                    offset += offset & 1;
+
+                    self.pairs[0][file].map_indices[map_index] = (offset - self.map_offset) / 2 + 1;
+                    offset += 2 * self.read_u16_le(offset)? as usize + 2;
+                } else {
+                    self.pairs[0][file].map_indices[map_index] = offset - self.map_offset + 1;
+                    offset += self.read_u8(offset)? as usize + 1;
+                }
+            }
+        }
+
+        Ok(offset + (offset & 1))
+    }
+
+    // Finds the stored value of a position, along with the file of its leading Pawn (or the a-file without Pawns)
+    // Gives None if the table is a DTZ table which stores the other side to move
+    fn stored_value(
+        &self,
+        gamestate: &ChessGamestate,
+        colors_swapped: bool,
+    ) -> Result<Option<(u16, usize)>, ChessTablebaseError> {
+        // Tables are stored with the first side of their name as White, and symmetric tables only store White to move,
+        // so the colors and the board are flipped when Black is the first side, or when Black moves in a symmetric table
+        let black_to_move = gamestate.turn_color == ChessPieceColor::Black;
+        let flipped = colors_swapped || (self.symmetric && black_to_move);
+
+        let (color_flip, tile_flip) = match flipped {
+            true => (8, 56),
+            false => (0, 0),
+        };
+
+        let stored_side = (flipped != black_to_move) as usize;
+
+        let mut tiles = [0usize; MAX_PIECES];
+        let mut pieces = [0u8; MAX_PIECES];
+        let mut piece_total = 0;
+        let mut lead_pawn_count = 0;
+        let mut lead_pawns = 0;
+        let mut file = 0;
+
+        // Tables with Pawns are split by the file of the leading Pawn, which is the Pawn nearest the edge of the board,
+        // and the lowest of those; the leading Pawns are those of the color which the table lists first
+        if self.has_pawns {
+            let lead_color = match (self.pairs[0][0].pieces[0] ^ color_flip) & 8 {
+                0 => ChessPieceColor::White,
+                _ => ChessPieceColor::Black,
+            };
+
+            lead_pawns = gamestate
+                .board
+                .pieces_bitboard(ChessPieceKind::Pawn, lead_color);
+
+            for tile in bit_indices(lead_pawns) {
+                tiles[piece_total] = tile ^ tile_flip;
+                piece_total += 1;
+            }
+
+            lead_pawn_count = piece_total;
+
+            // The first Pawn with the highest order is the leading one
+            let mut leading_index = 0;
+
+            for index in 1..lead_pawn_count {
+                if PAWN_ORDER[tiles[index]] > PAWN_ORDER[tiles[leading_index]] {
+                    leading_index = index;
+                }
+            }
+
+            tiles.swap(0, leading_index);
+            file = edge_distance(tiles[0] % 8);
+        }
+
+        let pairs_flags = self.pairs[0][file].flags;
+
+        if self.kind == ChessSyzygyKind::Dtz
+            && (pairs_flags & FLAG_BLACK_TO_MOVE) as usize != stored_side
+            && (self.has_pawns || !self.symmetric)
+        {
+            return Ok(None);
+        }
+
+        for tile in bit_indices(gamestate.board.occupied_bitboard() ^ lead_pawns) {
+            let piece = match gamestate.board.pieces()[tile / 8][tile % 8] {
+                Some(piece) => piece,
+                None => unreachable!("[INTERNAL ERROR] Occupied tile has no piece"),
+            };
+
+            if piece_total == MAX_PIECES {
+                return Err(self.invalid());
+            }
+
+            tiles[piece_total] = tile ^ tile_flip;
+            pieces[piece_total] = piece_code(piece.kind, piece.color) ^ color_flip;
+            piece_total += 1;
+        }
+
+        if piece_total != self.piece_count {
+            return Err(self.invalid());
+        }
+
+        let pairs = &self.pairs[stored_side % self.pairs.len()][file];
+
+        // The pieces are put in the order the table indexes them in
+        for index in lead_pawn_count..piece_total.saturating_sub(1) {
+            for other_index in index + 1..piece_total {
+                if pairs.pieces[index] == pieces[other_index] {
+                    pieces.swap(index, other_index);
+                    tiles.swap(index, other_index);
+                    break;
+                }
+            }
+        }
+
+        let tiles = &mut tiles[..piece_total];
+
+        // The board is mirrored so that the leading piece is on the a-d files
+        if tiles[0] % 8 > 3 {
+            for tile in tiles.iter_mut() {
+                *tile ^= 7;
+            }
+        }
+
+        let mut index = match self.has_pawns {
+            true => self.lead_pawns_index(tiles, lead_pawn_count),
+            false => self.leading_pieces_index(tiles, pairs)?,
+        };
+
+        index *= pairs.group_factors[0];
+
+        // The remaining groups are indexed by which of the tiles not taken by earlier groups their pieces are on
+        // The other side's Pawns come first when both sides have Pawns, and cannot be on the first or last rank
+        let mut remaining_pawns = self.both_sides_have_pawns;
+        let mut group_start = pairs.group_lengths[0];
+
+        for group in 1..pairs.group_lengths.len() {
+            let group_length = pairs.group_lengths[group];
+            let group_end = group_start + group_length;
+
+            tiles[group_start..group_end].sort_unstable();
+
+            let mut group_index = 0;
+
+            for member in 0..group_length {
+                let tile = tiles[group_start + member];
+                let earlier_tiles_below = tiles[..group_start]
+                    .iter()
+                    .filter(|&&earlier_tile| earlier_tile < tile)
+                    .count();
+
+                let free_tile_index = (tile - earlier_tiles_below)
+                    .checked_sub(8 * remaining_pawns as usize)
+                    .ok_or_else(|| self.invalid())?;
+
+                group_index += binomial_coefficient(member + 1, free_tile_index)
+                    .ok_or_else(|| self.invalid())?;
+            }
+
+            remaining_pawns = false;
+            index += group_index * pairs.group_factors[group];
+            group_start = group_end;
+        }
+
+        Ok(Some((self.decompress(pairs, index)?, file)))
+    }
+
+    // Indexes the leading Pawns, which are all of the leading color's Pawns, by their tiles in order from the lowest order up
+    fn lead_pawns_index(&self, tiles: &mut [usize], lead_pawn_count: usize) -> u64 {
+        let mut index = LEAD_PAWN_INDICES[lead_pawn_count][tiles[0]];
+
+        tiles[1..lead_pawn_count].sort_by_key(|&tile| PAWN_ORDER[tile]);
+
+        for (member, &tile) in tiles[1..lead_pawn_count].iter().enumerate() {
+            index += BINOMIAL[member + 1][PAWN_ORDER[tile]];
+        }
+
+        index
+    }
+
+    // Indexes the leading pieces of a table without Pawns, which are the two Kings and, if there is a unique piece,
+    // the first of the other pieces; the board is mirrored so that the first piece is in the a1-d1-d4 triangle
+    // and, if it is on the a1-h8 diagonal, so that the first piece off the diagonal is below it
+    fn leading_pieces_index(
+        &self,
+        tiles: &mut [usize],
+        pairs: &ChessPairsData,
+    ) -> Result<u64, ChessTablebaseError> {
+        if tiles[0] / 8 > 3 {
+            for tile in tiles.iter_mut() {
+                *tile ^= 56;
+            }
+        }
+
+        for index in 0..pairs.group_lengths[0] {
+            let diagonal_offset = diagonal_offset(tiles[index]);
+
+            if diagonal_offset == 0 {
+                continue;
+            }
+
+            if diagonal_offset > 0 {
+                for tile in tiles[index..].iter_mut() {
+                    *tile = ((*tile >> 3) | (*tile << 3)) & 63;
+                }
+            }
+
+            break;
+        }
+
+        if !self.has_unique_pieces {
+            return Ok(KING_PAIRS[TRIANGLE[tiles[0]]][tiles[1]] as u64);
+        }
+
+        // Three pieces are indexed together, with the tiles of the later pieces shifted down past the earlier ones
+        // Placements with pieces on the diagonal come after those without, in order of how many are on it
+        let (first, second, third) = (tiles[0], tiles[1], tiles[2]);
+        let second_adjust = (second > first) as usize;
+        let third_adjust = (third > first) as usize + (third > second) as usize;
+
+        let index = if diagonal_offset(first) != 0 {
+            (TRIANGLE[first] * 63 + second - second_adjust) * 62 + third - third_adjust
+        } else if diagonal_offset(second) != 0 {
+            (6 * 63 + (first / 8) * 28 + BELOW_DIAGONAL[second]) * 62 + third - third_adjust
+        } else if diagonal_offset(third) != 0 {
+            6 * 63 * 62
+                + 4 * 28 * 62
+                + (first / 8) * 7 * 28
+                + (second / 8 - second_adjust) * 28
+                + BELOW_DIAGONAL[third]
+        } else {
+            6 * 63 * 62
+                + 4 * 28 * 62
+                + 4 * 7 * 28
+                + (first / 8) * 7 * 6
+                + (second / 8 - second_adjust) * 6
+                + (third / 8 - third_adjust)
+        };
+
+        Ok(index as u64)
+    }
+
+    // Decompresses the value at an index of a side and file
+    fn decompress(&self, pairs: &ChessPairsData, index: u64) -> Result<u16, ChessTablebaseError> {
+        if let Some(single_value) = pairs.single_value {
+            return Ok(single_value);
+        }
+
+        // The sparse index gives the block and offset within it of the value in the middle of each span
+        // From there, the blocks are stepped through until the one holding the value is reached
+        let sparse_entry = (index / pairs.span) as usize;
+
+        if sparse_entry >= pairs.sparse_index_size {
+            return Err(self.invalid());
+        }
+
+        let sparse_entry_offset = pairs.sparse_index_offset + 6 * sparse_entry;
+        let mut block = self.read_u32_le(sparse_entry_offset)? as usize;
+        let mut offset = self.read_u16_le(sparse_entry_offset + 4)? as i64;
+
+        offset += (index % pairs.span) as i64 - (pairs.span / 2) as i64;
+
+        // Each block holds one more value than its stored length
+        let block_length = |block: usize| -> Result<i64, ChessTablebaseError> {
+            if block >= pairs.block_lengths_size {
+                return Err(self.invalid());
+            }
+
+            Ok(self.read_u16_le(pairs.block_lengths_offset + 2 * block)? as i64 + 1)
+        };
+
+        while offset < 0 {
+            block = block.checked_sub(1).ok_or_else(|| self.invalid())?;
+            offset += block_length(block)?;
+        }
+
+        while offset >= block_length(block)? {
+            offset -= block_length(block)?;
+            block += 1;
+        }
+
+        if block >= pairs.block_count {
+            return Err(self.invalid());
+        }
+
+        // The codes are read from the start of the block, 64 bits at a time, until reaching the symbol holding the value
+        let mut data_offset = pairs.data_offset + block * pairs.block_size as usize;
+        let mut buffer = self.read_u64_be(data_offset)?;
+        let mut buffer_bits = 64;
+        data_offset += 8;
+
+        let mut symbol;
+
+        loop {
+            let mut length_index = 0;
+
+            while buffer < pairs.base[length_index] {
+                length_index += 1;
+
+                if length_index == pairs.base.len() {
+                    return Err(self.invalid());
+                }
+            }
+
+            let code_length = length_index as u32 + pairs.min_symbol_length;
+            let lowest_symbol = self.read_u16_le(pairs.lowest_symbols_offset + 2 * length_index)?;
+
+            symbol = (((buffer - pairs.base[length_index]) >> (64 - code_length)) as u16)
+                .wrapping_add(lowest_symbol) as usize;
+
+            let symbol_length = *pairs
+                .symbol_lengths
+                .get(symbol)
+                .ok_or_else(|| self.invalid())? as i64;
+
+            if offset <= symbol_length {
+                break;
+            }
+
+            offset -= symbol_length + 1;
+            buffer <<= code_length;
+            buffer_bits -= code_length;
+
+            if buffer_bits <= 32 {
+                buffer_bits += 32;
+                buffer |= (self.read_u32_be(data_offset)? as u64) << (64 - buffer_bits);
+                data_offset += 4;
+            }
+        }
+
+        // The symbol stands for a run of values made by pairing symbols, so the pairs are followed down to the value
+        while pairs.symbol_lengths[symbol] != 0 {
+            let (left, right) = self.symbol_pair(pairs.tree_offset, symbol)?;
+            let left_length = pairs.symbol_lengths[left as usize] as i64;
+
+            if offset <= left_length {
+                symbol = left as usize;
+            } else {
+                offset -= left_length + 1;
+                symbol = right as usize;
+            }
+        }
+
+        Ok(self.symbol_pair(pairs.tree_offset, symbol)?.0)
+    }
+
+    // Reads the pair of symbols which a symbol stands for, as two 12-bit numbers packed into three bytes
+    // A leaf symbol holds its value as the left symbol
+    fn symbol_pair(
+        &self,
+        tree_offset: usize,
+        symbol: usize,
+    ) -> Result<(u16, u16), ChessTablebaseError> {
+        let entry_offset = tree_offset + 3 * symbol;
+        let bytes = self
+            .bytes
+            .get(entry_offset..entry_offset + 3)
+            .ok_or_else(|| self.invalid())?;
+
+        let left = ((bytes[1] as u16 & 0xF) << 8) | bytes[0] as u16;
+        let right = ((bytes[2] as u16) << 4) | (bytes[1] as u16 >> 4);
+
+        Ok((left, right))
+    }
+
+    fn read_bytes<const N: usize>(&self, offset: usize) -> Result<[u8; N], ChessTablebaseError> {
+        match self.bytes.get(offset..offset + N) {
+            Some(bytes) => Ok(bytes.try_into().unwrap()),
+            None => Err(self.invalid()),
+        }
+    }
+
+    fn read_u8(&self, offset: usize) -> Result<u8, ChessTablebaseError> {
+        self.read_bytes::<1>(offset).map(|bytes| bytes[0])
+    }
+
+    fn read_u16_le(&self, offset: usize) -> Result<u16, ChessTablebaseError> {
+        self.read_bytes(offset).map(u16::from_le_bytes)
+    }
+
+    fn read_u32_le(&self, offset: usize) -> Result<u32, ChessTablebaseError> {
+        self.read_bytes(offset).map(u32::from_le_bytes)
+    }
+
+    fn read_u32_be(&self, offset: usize) -> Result<u32, ChessTablebaseError> {
+        self.read_bytes(offset).map(u32::from_be_bytes)
+    }
+
+    fn read_u64_be(&self, offset: usize) -> Result<u64, ChessTablebaseError> {
+        self.read_bytes(offset).map(u64::from_be_bytes)
+    }
+
+    // Gets the error for a table whose contents are malformed
+    pub(crate) fn invalid(&self) -> ChessTablebaseError {
+        ChessTablebaseError::InvalidTable(self.file_name.clone())
+    }
+}
+
+// Gets the score of a WDL result, from -2 for a loss to 2 for a win, which is how WDL tables store results
+pub(crate) fn wdl_score(wdl: ChessWdl) -> i32 {
+    use ChessWdl::*;
+
+    match wdl {
+        Loss => -2,
+        BlessedLoss => -1,
+        Draw => 0,
+        CursedWin => 1,
+        Win => 2,
+    }
+}
+
+fn wdl_from_score(score: i32) -> Option<ChessWdl> {
+    use ChessWdl::*;
+
+    Some(match score {
+        -2 => Loss,
+        -1 => BlessedLoss,
+        0 => Draw,
+        1 => CursedWin,
+        2 => Win,
+        _ => return None,
+    })
+}
+
+fn piece_code(kind: ChessPieceKind, color: ChessPieceColor) -> u8 {
+    use ChessPieceKind::*;
+
+    let kind_code = match kind {
+        Pawn => 1,
+        Knight => 2,
+        Bishop => 3,
+        Rook => 4,
+        Queen => 5,
+        King => 6,
+    };
+
+    match color {
+        ChessPieceColor::White => kind_code,
+        ChessPieceColor::Black => kind_code + 8,
+    }
+}
+
+// Gets the tile indices of every set bit in a bitboard, from a1 to h8
+fn bit_indices(mut bitboard: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if bitboard == 0 {
+            return None;
+        }
+
+        let index = bitboard.trailing_zeros() as usize;
+        bitboard &= bitboard - 1;
+
+        Some(index)
+    })
+}
+
+// Gets the number of ways to choose k tiles out of n, or None if it is out of the range of the table
+fn binomial_coefficient(k: usize, n: usize) -> Option<u64> {
+    BINOMIAL.get(k)?.get(n).copied()
+}
+
+// Gets how far a file is from the nearer edge of the board, from 0 for the a and h files to 3 for the d and e files
+fn edge_distance(file: usize) -> usize {
+    file.min(7 - file)
+}
+
+// Gets how far a tile is above the a1-h8 diagonal, which is negative below it
+const fn diagonal_offset(tile: usize) -> i32 {
+    (tile / 8) as i32 - (tile % 8) as i32
+}
+
+// The tables below are calculated at compile time, in the same way as the reference probing code
+
+// Calculates the number of ways to choose k of n tiles, for up to seven pieces
+const fn binomial() -> [[u64; 64]; MAX_PIECES + 1] {
+    let mut binomial = [[0; 64]; MAX_PIECES + 1];
+    binomial[0][0] = 1;
+
+    let mut n = 1;
+
+    while n < 64 {
+        let mut k = 0;
+
+        while k <= MAX_PIECES && k <= n {
+            let with_tile = match k > 0 {
+                true => binomial[k - 1][n - 1],
+                false => 0,
+            };
+            let without_tile = match k < n {
+                true => binomial[k][n - 1],
+                false => 0,
+            };
+
+            binomial[k][n] = with_tile + without_tile;
+            k += 1;
+        }
+
+        n += 1;
+    }
+
+    binomial
+}
+
+// Numbers the tiles of the a1-d1-d4 triangle, with the six tiles below the diagonal first and the four on it last
+const fn triangle() -> [usize; 64] {
+    let mut triangle = [0; 64];
+    let mut code = 0;
+    let mut tile = 0;
+
+    while tile < 28 {
+        if diagonal_offset(tile) < 0 && tile % 8 <= 3 {
+            triangle[tile] = code;
+            code += 1;
+        }
+
+        tile += 1;
+    }
+
+    tile = 0;
+
+    while tile < 28 {
+        if diagonal_offset(tile) == 0 && tile % 8 <= 3 {
+            triangle[tile] = code;
+            code += 1;
+        }
+
+        tile += 1;
+    }
+
+    triangle
+}
+
+// Numbers the 28 tiles below the a1-h8 diagonal
+const fn below_diagonal() -> [usize; 64] {
+    let mut below_diagonal = [0; 64];
+    let mut code = 0;
+    let mut tile = 0;
+
+    while tile < 64 {
+        if diagonal_offset(tile) < 0 {
+            below_diagonal[tile] = code;
+            code += 1;
+        }
+
+        tile += 1;
+    }
+
+    below_diagonal
+}
+
+// Numbers the 462 legal placements of two Kings with the first in the a1-d1-d4 triangle, by the first King's triangle
+// number and the second King's tile; if the first King is on the diagonal, the second is not above it
+// Placements with both Kings on the diagonal are numbered last
+const fn king_pairs() -> [[usize; 64]; 10] {
+    let triangle = triangle();
+    let mut king_pairs = [[0; 64]; 10];
+    let mut code = 0;
+    let mut pass = 0;
+
+    while pass < 2 {
+        let mut triangle_index = 0;
+
+        while triangle_index < 10 {
+            let mut first = 0;
+
+            while first < 28 {
+                // Tiles outside the triangle are also numbered 0, so only b1 is taken for the number 0
+                if triangle[first] == triangle_index && (triangle_index > 0 || first == 1) {
+                    let mut second = 0;
+
+                    while second < 64 {
+                        let adjacent = (first % 8).abs_diff(second % 8) <= 1
+                            && (first / 8).abs_diff(second / 8) <= 1;
+                        let first_on_diagonal = diagonal_offset(first) == 0;
+                        let both_on_diagonal = first_on_diagonal && diagonal_offset(second) == 0;
+
+                        let legal = !(adjacent || first_on_diagonal && diagonal_offset(second) > 0);
+
+                        if legal && both_on_diagonal == (pass == 1) {
+                            king_pairs[triangle_index][second] = code;
+                            code += 1;
+                        }
+
+                        second += 1;
+                    }
+                }
+
+                first += 1;
+            }
+
+            triangle_index += 1;
+        }
+
+        pass += 1;
+    }
+
+    king_pairs
+}
+
+// Orders the tiles a Pawn can be on from 47 down to 0, from the edge files to the middle, from the lowest rank up,
+// and with each tile of the a-d files just before its mirror image; the leading Pawn is the one with the highest order,
+// and the order of a leading Pawn's tile is the number of tiles the other leading Pawns can be on
+const fn pawn_order() -> [usize; 64] {
+    let mut pawn_order = [0; 64];
+    let mut available = 48;
+    let mut file = 0;
+
+    while file < 4 {
+        let mut rank = 1;
+
+        while rank < 7 {
+            let tile = 8 * rank + file;
+
+            available -= 1;
+            pawn_order[tile] = available;
+            available -= 1;
+            pawn_order[tile ^ 7] = available;
+
+            rank += 1;
+        }
+
+        file += 1;
+    }
+
+    pawn_order
+}
+
+// Numbers the placements of one to five leading Pawns within the part of the table for the leading Pawn's file,
+// counting from the leading Pawn on the second rank upwards
+const fn lead_pawn_indices() -> [[u64; 64]; 6] {
+    let binomial = binomial();
+    let pawn_order = pawn_order();
+    let mut lead_pawn_indices = [[0; 64]; 6];
+    let mut lead_pawn_count = 1;
+
+    while lead_pawn_count <= 5 {
+        let mut file = 0;
+
+        while file < 4 {
+            let mut index = 0;
+            let mut rank = 1;
+
+            while rank < 7 {
+                let tile = 8 * rank + file;
+
+                lead_pawn_indices[lead_pawn_count][tile] = index;
+                index += binomial[lead_pawn_count - 1][pawn_order[tile]];
+                rank += 1;
+            }
+
+            file += 1;
+        }
+
+        lead_pawn_count += 1;
+    }
+
+    lead_pawn_indices
+}
+
+// Counts the placements of one to five leading Pawns with the leading Pawn on each of the a-d files
+const fn lead_pawn_file_sizes() -> [[u64; 4]; 6] {
+    let binomial = binomial();
+    let pawn_order = pawn_order();
+    let mut lead_pawn_file_sizes = [[0; 4]; 6];
+    let mut lead_pawn_count = 1;
+
+    while lead_pawn_count <= 5 {
+        let mut file = 0;
+
+        while file < 4 {
+            let mut rank = 1;
+
+            while rank < 7 {
+                lead_pawn_file_sizes[lead_pawn_count][file] +=
+                    binomial[lead_pawn_count - 1][pawn_order[8 * rank + file]];
+                rank += 1;
+            }
+
+            file += 1;
+        }
+
+        lead_pawn_count += 1;
+    }
+
+    lead_pawn_file_sizes
+}
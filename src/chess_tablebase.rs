@@ -0,0 +1,518 @@
+use crate::chess_syzygy_table::wdl_score;
+use crate::chess_syzygy_table::ChessSyzygyKind;
+use crate::chess_syzygy_table::ChessSyzygyTable;
+use crate::ChessGamestate;
+use crate::ChessMove;
+use crate::ChessPieceColor;
+use crate::ChessPieceKind;
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+// A set of Syzygy endgame tablebases, which give the perfect result of every position with few enough pieces
+// Each table covers one material balance, like "KQvKR", in a WDL file (.rtbw) giving the win, draw or loss result
+// and a DTZ file (.rtbz) giving the distance to the next capture or Pawn move on the way to that result
+//
+// Tables leave out positions whose best move is a capture, and DTZ tables only store one side to move, so positions
+// are looked up with a small search: captures (and for DTZ, Pawn moves) are tried first, and a DTZ table which stores
+// the other side to move is looked up after each move. Positions decided by the rules alone are never looked up,
+// so tables for material which cannot mate, like KBvK, are not needed
+pub struct ChessTablebase {
+    // The files found for each material balance, keyed by names like "KQvKR"
+    wdl_tables: HashMap<String, ChessTableFile>,
+    dtz_tables: HashMap<String, ChessTableFile>,
+    // The most pieces, Kings included, in any table found
+    max_pieces: u32,
+}
+
+// The result of a position for the player whose turn it is, as stored in a WDL table
+// Cursed wins and blessed losses are wins and losses which the fifty-move rule turns into draws
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChessWdl {
+    Loss,
+    BlessedLoss,
+    Draw,
+    CursedWin,
+    Win,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChessTablebaseError {
+    // The tablebase directory or one of its files could not be read
+    Io(String),
+    // A file is named like a table, but does not start with the table's magic number, or its contents are malformed
+    InvalidTable(String),
+    // The position has more pieces than any Syzygy table covers
+    TooManyPieces(u32),
+    // Tables only cover positions without castling rights
+    CastlingRights,
+    // No table was found for the material balance of the position, or of a position reached by a capture from it
+    MissingTable(String),
+}
+
+// A table file found in the tablebase directory, which is only read the first time a position is looked up in it
+struct ChessTableFile {
+    path: PathBuf,
+    table: OnceLock<Result<ChessSyzygyTable, ChessTablebaseError>>,
+}
+
+// The first four bytes of every WDL and DTZ file
+const WDL_MAGIC: [u8; 4] = [0x71, 0xE8, 0x23, 0x5D];
+const DTZ_MAGIC: [u8; 4] = [0xD7, 0x66, 0x0C, 0xA5];
+
+// Syzygy tables exist for up to seven pieces
+const MAX_TABLE_PIECES: u32 = 7;
+
+impl std::fmt::Display for ChessTablebaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        use ChessTablebaseError::*;
+
+        match self {
+            Io(message) => write!(f, "The tablebase could not be read: {}", message),
+            InvalidTable(name) => write!(f, "The file {} is not a valid Syzygy table.", name),
+            TooManyPieces(piece_count) => write!(
+                f,
+                "The position has {} pieces, but tablebases cover at most {}.",
+                piece_count, MAX_TABLE_PIECES
+            ),
+            CastlingRights => write!(f, "Tablebases do not cover positions with castling rights."),
+            MissingTable(name) => write!(f, "The tablebase has no {} table.", name),
+        }
+    }
+}
+
+impl std::error::Error for ChessTablebaseError {}
+
+impl ChessTablebase {
+    // Finds the tables in a directory, checking that each is a Syzygy file
+    // Files which are not named like tables are ignored, so the tables may share a directory with other files
+    // The tables' contents are only read once a position is first looked up in them
+    pub fn open(directory: impl AsRef<Path>) -> Result<Self, ChessTablebaseError> {
+        let mut tablebase = ChessTablebase {
+            wdl_tables: HashMap::new(),
+            dtz_tables: HashMap::new(),
+            max_pieces: 0,
+        };
+
+        let directory_entries =
+            fs::read_dir(directory).map_err(|err| ChessTablebaseError::Io(err.to_string()))?;
+
+        for directory_entry in directory_entries {
+            let path = directory_entry
+                .map_err(|err| ChessTablebaseError::Io(err.to_string()))?
+                .path();
+
+            let (name, extension) = match (path.file_stem(), path.extension()) {
+                (Some(name), Some(extension)) => (
+                    name.to_string_lossy().to_string(),
+                    extension.to_string_lossy().to_string(),
+                ),
+                _ => continue,
+            };
+
+            let (tables, magic) = match extension.as_str() {
+                "rtbw" => (&mut tablebase.wdl_tables, WDL_MAGIC),
+                "rtbz" => (&mut tablebase.dtz_tables, DTZ_MAGIC),
+                _ => continue,
+            };
+
+            let piece_count = match table_piece_count(&name) {
+                Some(piece_count) => piece_count,
+                None => continue,
+            };
+
+            if read_magic(&path)? != magic {
+                return Err(ChessTablebaseError::InvalidTable(format!(
+                    "{}.{}",
+                    name, extension
+                )));
+            }
+
+            tables.insert(
+                name,
+                ChessTableFile {
+                    path,
+                    table: OnceLock::new(),
+                },
+            );
+            tablebase.max_pieces = tablebase.max_pieces.max(piece_count);
+        }
+
+        Ok(tablebase)
+    }
+
+    // Gets the most pieces, Kings included, in any of the tables found
+    pub fn max_pieces(&self) -> u32 {
+        self.max_pieces
+    }
+
+    // Gets the number of WDL tables found
+    pub fn table_count(&self) -> usize {
+        self.wdl_tables.len()
+    }
+
+    // Gets the result of a position for the player whose turn it is, which only needs the WDL tables
+    pub fn probe_wdl(&self, gamestate: &ChessGamestate) -> Result<ChessWdl, ChessTablebaseError> {
+        check_castling_rights(gamestate)?;
+
+        let (wdl, _) = self.search_wdl(&mut gamestate.clone(), false)?;
+
+        Ok(wdl)
+    }
+
+    // Gets the number of plies until the next capture or Pawn move with best play, positive if the player whose turn it is
+    // wins and negative if they lose, or 0 if the position is drawn or the game is over
+    // Cursed wins and blessed losses are given 100 plies further from 0, so that they are always beyond the fifty-move rule
+    // Like the tables themselves, a distance may be one ply too high when the tables store it in moves
+    pub fn probe_dtz(&self, gamestate: &ChessGamestate) -> Result<i32, ChessTablebaseError> {
+        check_castling_rights(gamestate)?;
+
+        let (_, dtz) = self.search_dtz(&mut gamestate.clone())?;
+
+        Ok(dtz)
+    }
+
+    // Finds the move which gives the best result for the player whose turn it is, along with that result
+    // Of equally good moves, the winning move reaching a capture or Pawn move soonest is picked, which keeps wins within
+    // the fifty-move rule, or the losing move putting it off longest
+    // Moves leading to positions which cannot be looked up, like promotions into a missing table, are left out;
+    // if no move can be looked up, the first move's problem is given
+    pub fn best_move(
+        &self,
+        gamestate: &ChessGamestate,
+    ) -> Result<Option<(ChessMove, ChessWdl)>, ChessTablebaseError> {
+        check_castling_rights(gamestate)?;
+
+        let mut best: Option<(ChessMove, ChessWdl, i32)> = None;
+        let mut first_error = None;
+        let mut gamestate = gamestate.clone();
+
+        for candidate_move in gamestate.legal_moves() {
+            let zeroing = is_zeroing(&gamestate, &candidate_move);
+
+            gamestate.perform_validated_move(&candidate_move);
+            let probe_result = self.move_result(&mut gamestate, zeroing);
+            gamestate.unmake_move();
+
+            let (wdl, dtz) = match probe_result {
+                Ok(move_result) => move_result,
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                    continue;
+                }
+            };
+
+            // A lower distance is better when winning and, being negative, when losing too
+            let is_better = match best {
+                Some((_, best_wdl, best_dtz)) => (wdl, -dtz) > (best_wdl, -best_dtz),
+                None => true,
+            };
+
+            if is_better {
+                best = Some((candidate_move, wdl, dtz));
+            }
+        }
+
+        match (best, first_error) {
+            (None, Some(err)) => Err(err),
+            (best, _) => Ok(best.map(|(best_move, wdl, _)| (best_move, wdl))),
+        }
+    }
+
+    // Gets the result and DTZ of the position before a move, as reached by that move, given the position after it
+    fn move_result(
+        &self,
+        gamestate: &mut ChessGamestate,
+        zeroing: bool,
+    ) -> Result<(ChessWdl, i32), ChessTablebaseError> {
+        if gamestate.is_in_check() && gamestate.legal_moves().is_empty() {
+            return Ok((ChessWdl::Win, 1));
+        }
+
+        // After a capture or Pawn move, only the result matters, as the move itself resets the count
+        if zeroing {
+            let (opponent_wdl, _) = self.search_wdl(gamestate, false)?;
+            let wdl = invert_wdl(opponent_wdl);
+
+            return Ok((wdl, dtz_before_zeroing(wdl)));
+        }
+
+        let (opponent_wdl, opponent_dtz) = self.search_dtz(gamestate)?;
+        let dtz = -opponent_dtz;
+
+        Ok((invert_wdl(opponent_wdl), dtz + dtz.signum()))
+    }
+
+    // Gets the result of a position, along with whether the best move is a capture (or a Pawn move, if checking
+    // zeroing Pawn moves too) that keeps the result, in which case DTZ tables store an arbitrary value for it
+    // Captures are searched first, as the tables store arbitrary values for positions where a capture is best,
+    // and do not know about en passant
+    fn search_wdl(
+        &self,
+        gamestate: &mut ChessGamestate,
+        check_zeroing_pawn_moves: bool,
+    ) -> Result<(ChessWdl, bool), ChessTablebaseError> {
+        let legal_moves = gamestate.legal_moves();
+
+        if legal_moves.is_empty() {
+            return Ok(match gamestate.is_in_check() {
+                true => (ChessWdl::Loss, false),
+                false => (ChessWdl::Draw, false),
+            });
+        }
+
+        // Tables for material which cannot mate, like KvK or KBvK, are not generated, as every position is drawn
+        if gamestate.has_insufficient_material() {
+            return Ok((ChessWdl::Draw, false));
+        }
+
+        let mut best_wdl = ChessWdl::Loss;
+        let mut searched_move_count = 0;
+
+        for candidate_move in &legal_moves {
+            let is_capture = gamestate.get_captured_piece(candidate_move).is_some();
+
+            if !(is_capture || check_zeroing_pawn_moves && is_zeroing(gamestate, candidate_move)) {
+                continue;
+            }
+
+            searched_move_count += 1;
+
+            gamestate.perform_validated_move(candidate_move);
+            let probe_result = self.search_wdl(gamestate, false);
+            gamestate.unmake_move();
+
+            let wdl = invert_wdl(probe_result?.0);
+
+            if wdl > best_wdl {
+                best_wdl = wdl;
+
+                if wdl == ChessWdl::Win {
+                    return Ok((wdl, true));
+                }
+            }
+        }
+
+        // If every move was searched, the table is not needed, and its value could be wrong anyway
+        let every_move_searched = searched_move_count == legal_moves.len();
+
+        let table_wdl = match every_move_searched {
+            true => best_wdl,
+            false => self.probe_table_wdl(gamestate)?,
+        };
+
+        match best_wdl >= table_wdl {
+            true => Ok((best_wdl, best_wdl > ChessWdl::Draw || every_move_searched)),
+            false => Ok((table_wdl, false)),
+        }
+    }
+
+    // Gets the result and DTZ of a position, with the DTZ signed and extended as described for probe_dtz()
+    fn search_dtz(
+        &self,
+        gamestate: &mut ChessGamestate,
+    ) -> Result<(ChessWdl, i32), ChessTablebaseError> {
+        let (wdl, zeroing_is_best) = self.search_wdl(gamestate, true)?;
+
+        // DTZ tables do not store draws, and a game which is over has no moves left to count
+        if wdl == ChessWdl::Draw || gamestate.legal_moves().is_empty() {
+            return Ok((wdl, 0));
+        }
+
+        if zeroing_is_best {
+            return Ok((wdl, dtz_before_zeroing(wdl)));
+        }
+
+        let extension = match wdl {
+            ChessWdl::CursedWin | ChessWdl::BlessedLoss => 100,
+            _ => 0,
+        };
+
+        let (table, colors_swapped) =
+            self.find_table(&self.dtz_tables, ChessSyzygyKind::Dtz, gamestate)?;
+
+        if let Some(dtz) = table.probe_dtz(gamestate, colors_swapped, wdl)? {
+            return Ok((wdl, (dtz + extension) * wdl_score(wdl).signum()));
+        }
+
+        // The table stores the other side to move, so the DTZ is found from the moves of this position, keeping the
+        // lowest of those which keep the result; when losing, that is the one putting off the loss the longest
+        let mut best_dtz: Option<i32> = None;
+
+        for candidate_move in gamestate.legal_moves() {
+            let zeroing = is_zeroing(gamestate, &candidate_move);
+
+            gamestate.perform_validated_move(&candidate_move);
+            let probe_result = self.move_result(gamestate, zeroing);
+            gamestate.unmake_move();
+
+            let (_, dtz) = probe_result?;
+
+            if dtz.signum() == wdl_score(wdl).signum() && best_dtz.is_none_or(|best| dtz < best) {
+                best_dtz = Some(dtz);
+            }
+        }
+
+        match best_dtz {
+            Some(dtz) => Ok((wdl, dtz)),
+            // Only a table which disagrees with the tables of the positions after each move gets here
+            None => Err(table.invalid()),
+        }
+    }
+
+    fn probe_table_wdl(&self, gamestate: &ChessGamestate) -> Result<ChessWdl, ChessTablebaseError> {
+        let (table, colors_swapped) =
+            self.find_table(&self.wdl_tables, ChessSyzygyKind::Wdl, gamestate)?;
+
+        table.probe_wdl(gamestate, colors_swapped)
+    }
+
+    // Finds the table for a position's material balance, reading it if this is the first time it is used
+    // Tables are named with the stronger side first, so the table may be found with the sides swapped, as is also given
+    fn find_table<'a>(
+        &self,
+        tables: &'a HashMap<String, ChessTableFile>,
+        kind: ChessSyzygyKind,
+        gamestate: &ChessGamestate,
+    ) -> Result<(&'a ChessSyzygyTable, bool), ChessTablebaseError> {
+        let piece_count = gamestate.board.occupied_bitboard().count_ones();
+
+        if piece_count > MAX_TABLE_PIECES {
+            return Err(ChessTablebaseError::TooManyPieces(piece_count));
+        }
+
+        let name = material_name(gamestate);
+
+        let swapped_name = match name.split_once('v') {
+            Some((white_pieces, black_pieces)) => format!("{}v{}", black_pieces, white_pieces),
+            None => unreachable!("[INTERNAL ERROR] Material name has no separator"),
+        };
+
+        let (table_name, table_file, colors_swapped) = match tables.get(&name) {
+            Some(table_file) => (name, table_file, false),
+            None => match tables.get(&swapped_name) {
+                Some(table_file) => (swapped_name, table_file, true),
+                None => return Err(ChessTablebaseError::MissingTable(name)),
+            },
+        };
+
+        let table = table_file.table.get_or_init(|| {
+            let bytes = fs::read(&table_file.path).map_err(|err| {
+                ChessTablebaseError::Io(format!("{}: {}", table_file.path.display(), err))
+            })?;
+
+            ChessSyzygyTable::from_bytes(&table_name, kind, bytes)
+        });
+
+        match table {
+            Ok(table) => Ok((table, colors_swapped)),
+            Err(err) => Err(err.clone()),
+        }
+    }
+}
+
+// Names the material balance of a position the way tables are named, with White's pieces first, like "KRPvKR"
+fn material_name(gamestate: &ChessGamestate) -> String {
+    use ChessPieceKind::*;
+
+    let side_name = |color| {
+        let mut side_name = String::new();
+
+        for (kind, letter) in [
+            (King, 'K'),
+            (Queen, 'Q'),
+            (Rook, 'R'),
+            (Bishop, 'B'),
+            (Knight, 'N'),
+            (Pawn, 'P'),
+        ] {
+            let count = gamestate.board.pieces_bitboard(kind, color).count_ones();
+            side_name.extend(std::iter::repeat_n(letter, count as usize));
+        }
+
+        side_name
+    };
+
+    format!(
+        "{}v{}",
+        side_name(ChessPieceColor::White),
+        side_name(ChessPieceColor::Black)
+    )
+}
+
+// Gets the number of pieces of a table from its name, or None if the name is not a table name like "KQvKR"
+fn table_piece_count(name: &str) -> Option<u32> {
+    let (first_side, second_side) = name.split_once('v')?;
+
+    for side in [first_side, second_side] {
+        let mut letters = side.chars();
+
+        if letters.next() != Some('K') || !letters.all(|letter| "QRBNP".contains(letter)) {
+            return None;
+        }
+    }
+
+    let piece_count = (first_side.len() + second_side.len()) as u32;
+
+    (piece_count <= MAX_TABLE_PIECES).then_some(piece_count)
+}
+
+fn read_magic(path: &Path) -> Result<[u8; 4], ChessTablebaseError> {
+    let mut magic = [0; 4];
+
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .map_err(|err| ChessTablebaseError::Io(format!("{}: {}", path.display(), err)))?;
+
+    Ok(magic)
+}
+
+fn check_castling_rights(gamestate: &ChessGamestate) -> Result<(), ChessTablebaseError> {
+    match gamestate.white_castle_kingside
+        || gamestate.white_castle_queenside
+        || gamestate.black_castle_kingside
+        || gamestate.black_castle_queenside
+    {
+        true => Err(ChessTablebaseError::CastlingRights),
+        false => Ok(()),
+    }
+}
+
+// Checks if a move resets the fifty-move count, by being a capture or a Pawn move
+fn is_zeroing(gamestate: &ChessGamestate, candidate_move: &ChessMove) -> bool {
+    gamestate.get_captured_piece(candidate_move).is_some()
+        || gamestate
+            .board
+            .piece_at(candidate_move.source())
+            .is_some_and(|piece| piece.kind == ChessPieceKind::Pawn)
+}
+
+// Gets the DTZ of a position whose best move is a capture or Pawn move with the given result, which is one ply away
+fn dtz_before_zeroing(wdl: ChessWdl) -> i32 {
+    use ChessWdl::*;
+
+    match wdl {
+        Loss => -1,
+        BlessedLoss => -101,
+        Draw => 0,
+        CursedWin => 101,
+        Win => 1,
+    }
+}
+
+// Gets the result of a position for the other player
+fn invert_wdl(wdl: ChessWdl) -> ChessWdl {
+    use ChessWdl::*;
+
+    match wdl {
+        Loss => Win,
+        BlessedLoss => CursedWin,
+        Draw => Draw,
+        CursedWin => BlessedLoss,
+        Win => Loss,
+    }
+}
@@ -0,0 +1,197 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+// The time each player gets for their moves, made up of one or more stages
+// Each stage but the last lasts for a number of moves, after which the next stage's time is added to the clock
+// If the last stage also lasts for a number of moves, it is repeated for as long as the game goes on
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChessTimeControl {
+    stages: Vec<ChessTimeControlStage>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChessTimeControlStage {
+    // The number of moves each player must make within the stage, or None if the stage lasts for the rest of the game
+    pub moves: Option<u32>,
+    // The time added to each player's clock at the start of the stage
+    pub base_time: Duration,
+    pub bonus: ChessClockBonus,
+}
+
+// The extra time a player gets for each move
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChessClockBonus {
+    None,
+    // Fischer increment: the time is added after each move, so time can build up over quick moves
+    Increment(Duration),
+    // Bronstein delay: the time spent on a move is given back after it, up to the delay
+    Bronstein(Duration),
+    // Simple (US) delay: the clock only starts counting down once the delay has passed
+    Delay(Duration),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChessTimeControlError {
+    // There were no stages to parse
+    Empty,
+    // A stage could not be read, like "40/" or "5+x"
+    InvalidStage(String),
+    // A stage before the last lasts for the rest of the game, so the stages after it could never be reached
+    // The stage is numbered from 1
+    UnreachableStage(usize),
+    // A stage lasts for zero moves, so it would be over before it began
+    // The stage is numbered from 1
+    ZeroMoveStage(usize),
+}
+
+impl std::fmt::Display for ChessTimeControlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        use ChessTimeControlError::*;
+
+        match self {
+            Empty => write!(
+                f,
+                "Enter a time control like \"5+3\" or \"40/90+30,30+30\"."
+            ),
+            InvalidStage(stage) => write!(
+                f,
+                "\"{}\" is not a time control stage; stages look like \"5\", \"3+2\", \"5d3\", \"5b3\" or \"40/90+30\".",
+                stage
+            ),
+            UnreachableStage(stage_number) => write!(
+                f,
+                "Stage {} lasts for the rest of the game, so no stages can come after it.",
+                stage_number
+            ),
+            ZeroMoveStage(stage_number) => write!(
+                f,
+                "Stage {} lasts for no moves; a stage must last for at least one move.",
+                stage_number
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ChessTimeControlError {}
+
+impl ChessTimeControl {
+    // Creates a time control from its stages, checking that there is at least one, that each lasts for at least one move,
+    // and that each can be reached
+    pub fn new(stages: Vec<ChessTimeControlStage>) -> Result<Self, ChessTimeControlError> {
+        if stages.is_empty() {
+            return Err(ChessTimeControlError::Empty);
+        }
+
+        if let Some(index) = stages.iter().position(|stage| stage.moves == Some(0)) {
+            return Err(ChessTimeControlError::ZeroMoveStage(index + 1));
+        }
+
+        if let Some(index) = stages[..stages.len() - 1]
+            .iter()
+            .position(|stage| stage.moves.is_none())
+        {
+            return Err(ChessTimeControlError::UnreachableStage(index + 1));
+        }
+
+        Ok(ChessTimeControl { stages })
+    }
+
+    pub fn stages(&self) -> &[ChessTimeControlStage] {
+        &self.stages
+    }
+
+    // Creates a time control with the given time for the whole game, and no bonus time
+    pub fn sudden_death(base_time: Duration) -> Self {
+        ChessTimeControl {
+            stages: vec![ChessTimeControlStage {
+                moves: None,
+                base_time,
+                bonus: ChessClockBonus::None,
+            }],
+        }
+    }
+
+    // Creates a time control with the given time for the whole game, and the given increment after each move
+    pub fn fischer(base_time: Duration, increment: Duration) -> Self {
+        ChessTimeControl {
+            stages: vec![ChessTimeControlStage {
+                moves: None,
+                base_time,
+                bonus: ChessClockBonus::Increment(increment),
+            }],
+        }
+    }
+}
+
+impl ChessClockBonus {
+    // Gets the time that the bonus is for, which is zero without a bonus
+    pub fn duration(&self) -> Duration {
+        use ChessClockBonus::*;
+
+        match self {
+            None => Duration::ZERO,
+            Increment(duration) | Bronstein(duration) | Delay(duration) => *duration,
+        }
+    }
+}
+
+// Parses a time control from comma-separated stages, in which the base time is in minutes and the bonus in seconds
+// A stage is written as the base time, optionally preceded by a move count and a '/', and optionally followed by
+// '+' and an increment, 'd' and a simple delay, or 'b' and a Bronstein delay
+// For example, "3+2" is 3 minutes with a 2-second increment, and "40/90+30,30+30" is 90 minutes for 40 moves,
+// then 30 minutes for the rest of the game, with a 30-second increment throughout
+impl FromStr for ChessTimeControl {
+    type Err = ChessTimeControlError;
+
+    fn from_str(encoded_time_control: &str) -> Result<Self, Self::Err> {
+        let stages = encoded_time_control
+            .split(',')
+            .map(str::trim)
+            .filter(|encoded_stage| !encoded_stage.is_empty())
+            .map(|encoded_stage| {
+                parse_stage(encoded_stage)
+                    .ok_or_else(|| ChessTimeControlError::InvalidStage(encoded_stage.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        ChessTimeControl::new(stages)
+    }
+}
+
+fn parse_stage(encoded_stage: &str) -> Option<ChessTimeControlStage> {
+    let (moves, timing) = match encoded_stage.split_once('/') {
+        Some((moves, timing)) => match moves.parse::<u32>().ok()? {
+            0 => return None,
+            moves => (Some(moves), timing),
+        },
+        None => (None, encoded_stage),
+    };
+
+    let (base_minutes, bonus) = match timing.find(['+', 'd', 'b']) {
+        Some(bonus_index) => {
+            let bonus_seconds = parse_duration(&timing[bonus_index + 1..], 1.0)?;
+
+            let bonus = match &timing[bonus_index..bonus_index + 1] {
+                "+" => ChessClockBonus::Increment(bonus_seconds),
+                "d" => ChessClockBonus::Delay(bonus_seconds),
+                _ => ChessClockBonus::Bronstein(bonus_seconds),
+            };
+
+            (&timing[..bonus_index], bonus)
+        }
+        None => (timing, ChessClockBonus::None),
+    };
+
+    Some(ChessTimeControlStage {
+        moves,
+        base_time: parse_duration(base_minutes, 60.0)?,
+        bonus,
+    })
+}
+
+// Parses a non-negative number of the given unit, like "1.5" minutes, into a duration
+fn parse_duration(encoded_duration: &str, seconds_per_unit: f64) -> Option<Duration> {
+    let units: f64 = encoded_duration.parse().ok()?;
+
+    Duration::try_from_secs_f64(units * seconds_per_unit).ok()
+}
@@ -0,0 +1,185 @@
+use crate::ChessEngine;
+use crate::ChessMove;
+
+use std::mem;
+
+// A fixed-size cache of search results, keyed by the Zobrist hash of the position they were found in
+// The same position is often reached by different move orders, so a search can reuse the result instead of searching it again
+pub struct ChessTranspositionTable {
+    entries: Vec<Option<ChessTranspositionEntry>>,
+    // Counts the searches the table has been used for, so that entries left over from earlier searches can be replaced first
+    generation: u8,
+}
+
+// What a stored score says about the true score of its position, which depends on the search window it was found with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChessBound {
+    // The score is exact
+    Exact,
+    // The search stopped early at a cutoff, so the true score is at least the stored score
+    Lower,
+    // No move reached the bottom of the window, so the true score is at most the stored score
+    Upper,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChessTranspositionEntry {
+    // The full hash of the position, as different positions can share a slot in the table
+    hash: u64,
+    depth: u32,
+    bound: ChessBound,
+    // Mate scores are stored as the distance to mate from this position rather than from the root of the search
+    score: i32,
+    best_move: Option<ChessMove>,
+    generation: u8,
+}
+
+impl ChessTranspositionTable {
+    // The size of the table in megabytes, if no other size is given
+    pub const DEFAULT_SIZE_MB: usize = 16;
+
+    // Creates an empty table which takes up about the given number of megabytes
+    pub fn new(size_mb: usize) -> Self {
+        let entry_count =
+            (size_mb * 1024 * 1024 / mem::size_of::<Option<ChessTranspositionEntry>>()).max(1);
+
+        ChessTranspositionTable {
+            entries: vec![None; entry_count],
+            generation: 0,
+        }
+    }
+
+    // Gets the number of entries the table can hold
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    // Removes every entry
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+        self.generation = 0;
+    }
+
+    // Marks the start of a new search, after which the entries of earlier searches are the first to be replaced
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    // Gets the entry stored for a position, if there is one
+    pub fn probe(&self, hash: u64) -> Option<&ChessTranspositionEntry> {
+        self.entries[self.slot(hash)]
+            .as_ref()
+            .filter(|entry| entry.hash == hash)
+    }
+
+    // Stores a search result for a position, found at the given ply from the root of the search
+    // The entry in the position's slot is only replaced if it comes from an earlier search, belongs to the same position,
+    // or was searched less deeply, so that the most expensive results are kept the longest
+    pub fn store(
+        &mut self,
+        hash: u64,
+        depth: u32,
+        bound: ChessBound,
+        score: i32,
+        best_move: Option<ChessMove>,
+        ply: usize,
+    ) {
+        let slot = self.slot(hash);
+        let generation = self.generation;
+
+        let mut best_move = best_move;
+
+        if let Some(existing_entry) = &self.entries[slot] {
+            let same_position = existing_entry.hash == hash;
+
+            let replaceable = same_position
+                || existing_entry.generation != generation
+                || depth >= existing_entry.depth;
+
+            if !replaceable {
+                return;
+            }
+
+            // A search which failed low has no best move, but the move found by an earlier search is still worth trying first
+            if same_position && best_move.is_none() {
+                best_move = existing_entry.best_move;
+            }
+        }
+
+        self.entries[slot] = Some(ChessTranspositionEntry {
+            hash,
+            depth,
+            bound,
+            score: score_to_table(score, ply),
+            best_move,
+            generation,
+        });
+    }
+
+    // Gets how full the table is with entries from the current search, in permille, as reported to chess GUIs
+    // Only the first thousand slots are counted, which is a good enough sample as positions are spread evenly
+    pub fn hashfull(&self) -> u32 {
+        let sample = &self.entries[..self.entries.len().min(1000)];
+
+        let used_count = sample
+            .iter()
+            .flatten()
+            .filter(|entry| entry.generation == self.generation)
+            .count();
+
+        (used_count * 1000 / sample.len()) as u32
+    }
+
+    fn slot(&self, hash: u64) -> usize {
+        (hash % self.entries.len() as u64) as usize
+    }
+}
+
+impl Default for ChessTranspositionTable {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SIZE_MB)
+    }
+}
+
+impl ChessTranspositionEntry {
+    // Gets the number of plies the position was searched to
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    pub fn bound(&self) -> ChessBound {
+        self.bound
+    }
+
+    // Gets the score of the position for the player whose turn it is there, when it is reached at the given ply from the root
+    pub fn score(&self, ply: usize) -> i32 {
+        score_from_table(self.score, ply)
+    }
+
+    pub fn best_move(&self) -> Option<ChessMove> {
+        self.best_move
+    }
+}
+
+// Checks if a score is a checkmate score, which counts plies from the root of the search
+fn is_mate_score(score: i32) -> bool {
+    score.abs() >= ChessEngine::MATE_SCORE - ChessEngine::MAX_PLY as i32
+}
+
+// Converts a mate score counted from the root into one counted from the stored position
+fn score_to_table(score: i32, ply: usize) -> i32 {
+    match (is_mate_score(score), score > 0) {
+        (true, true) => score + ply as i32,
+        (true, false) => score - ply as i32,
+        (false, _) => score,
+    }
+}
+
+// Converts a mate score counted from the stored position into one counted from the root
+fn score_from_table(score: i32, ply: usize) -> i32 {
+    match (is_mate_score(score), score > 0) {
+        (true, true) => score - ply as i32,
+        (true, false) => score + ply as i32,
+        (false, _) => score,
+    }
+}
@@ -0,0 +1,361 @@
+use crate::ChessEngine;
+use crate::ChessGamestate;
+use crate::ChessMove;
+use crate::ChessPieceColor;
+use crate::ChessSearchLimits;
+use crate::ChessSearchResult;
+use crate::ChessTranspositionTable;
+
+use std::io::BufRead;
+use std::io::Write;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+
+// A front end for the Universal Chess Interface, which lets chess GUIs and tournament managers use the engine
+// Commands are read one line at a time, and searches run on a separate thread so that "stop" can be received during them
+pub struct ChessUci {
+    // The position set by the last "position" command
+    gamestate: ChessGamestate,
+    // The engine is moved onto the search thread during a search, and handed back when the search finishes
+    engine: Option<ChessEngine>,
+    search_thread: Option<JoinHandle<ChessEngine>>,
+    // Whether the running search continues until it is told to stop
+    search_is_infinite: bool,
+    // Stops the engine's search, which is needed while the engine itself is on the search thread
+    stop_signal: Arc<AtomicBool>,
+    // The size of the engine's transposition table in megabytes, as set by the Hash option
+    hash_size_mb: usize,
+    // Whether positions follow the Chess960 castling rules, as set by the UCI_Chess960 option
+    // Castling moves are then sent and received as the King moving onto its Rook
+    chess960: bool,
+    // Shared with the search thread, which writes the info lines and the best move
+    output: Arc<Mutex<Box<dyn Write + Send>>>,
+}
+
+impl ChessUci {
+    pub fn new(output: impl Write + Send + 'static) -> Self {
+        let engine = ChessEngine::new();
+
+        ChessUci {
+            gamestate: ChessGamestate::new(),
+            stop_signal: engine.stop_signal(),
+            engine: Some(engine),
+            search_thread: None,
+            search_is_infinite: false,
+            hash_size_mb: ChessTranspositionTable::DEFAULT_SIZE_MB,
+            chess960: false,
+            output: Arc::new(Mutex::new(Box::new(output))),
+        }
+    }
+
+    // Handles commands until "quit" is received or the input ends
+    // If the input ends during a search, the search is allowed to finish unless it is infinite
+    pub fn run(&mut self, input: impl BufRead) {
+        for line in input.lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(_) => break,
+            };
+
+            if !self.handle_command(&line) {
+                return;
+            }
+        }
+
+        match self.search_is_infinite {
+            true => self.stop_search(),
+            false => self.finish_search(),
+        }
+    }
+
+    // Handles a single command, returning false once the engine should quit
+    // Unknown commands are ignored, as the protocol requires
+    pub fn handle_command(&mut self, command: &str) -> bool {
+        let tokens: Vec<&str> = command.split_whitespace().collect();
+
+        match tokens.as_slice() {
+            ["uci", ..] => {
+                self.send("id name LTChess");
+                self.send("id author The LTChess developers");
+                self.send(&format!(
+                    "option name Hash type spin default {} min 1 max {}",
+                    ChessTranspositionTable::DEFAULT_SIZE_MB,
+                    MAX_HASH_SIZE_MB
+                ));
+                self.send("option name UCI_Chess960 type check default false");
+                self.send("uciok");
+            }
+            ["isready", ..] => self.send("readyok"),
+            ["ucinewgame", ..] => {
+                self.stop_search();
+
+                // A new engine forgets the move ordering tables learned in the previous game
+                let engine = ChessEngine::with_hash_size(self.hash_size_mb);
+                self.stop_signal = engine.stop_signal();
+                self.engine = Some(engine);
+                self.gamestate = ChessGamestate::new();
+            }
+            ["setoption", "name", "Hash", "value", size_mb] => {
+                self.stop_search();
+
+                match size_mb.parse::<usize>() {
+                    Ok(size_mb) if (1..=MAX_HASH_SIZE_MB).contains(&size_mb) => {
+                        self.hash_size_mb = size_mb;
+
+                        if let Some(engine) = &mut self.engine {
+                            engine.set_hash_size(size_mb);
+                        }
+                    }
+                    _ => self.send(&format!("info string Invalid Hash size: {}", size_mb)),
+                }
+            }
+            ["setoption", "name", "UCI_Chess960", "value", enabled] => match *enabled {
+                "true" => self.chess960 = true,
+                "false" => self.chess960 = false,
+                _ => self.send(&format!(
+                    "info string Invalid UCI_Chess960 value: {}",
+                    enabled
+                )),
+            },
+            ["position", arguments @ ..] => {
+                self.stop_search();
+
+                match parse_position(arguments, self.chess960) {
+                    Ok(gamestate) => self.gamestate = gamestate,
+                    // Keeping the previous position would answer the next "go" for the wrong board
+                    Err(message) => {
+                        self.gamestate = ChessGamestate::new();
+                        self.gamestate.chess960 = self.chess960;
+
+                        self.send(&format!(
+                            "info string {}; the position is reset to the starting position",
+                            message
+                        ));
+                    }
+                }
+            }
+            ["go", arguments @ ..] => {
+                self.stop_search();
+                self.start_search(arguments);
+            }
+            ["stop", ..] => self.stop_search(),
+            ["quit", ..] => {
+                self.stop_search();
+                return false;
+            }
+            _ => {}
+        }
+
+        true
+    }
+
+    // Starts searching the current position on a separate thread, which sends the best move when it is done
+    fn start_search(&mut self, arguments: &[&str]) {
+        let mut engine = match self.engine.take() {
+            Some(engine) => engine,
+            None => unreachable!("[INTERNAL ERROR] Engine not returned by the previous search"),
+        };
+
+        let (limits, infinite) = parse_go(arguments, self.gamestate.turn_color);
+        self.search_is_infinite = infinite;
+
+        let gamestate = self.gamestate.clone();
+        let output = Arc::clone(&self.output);
+        let stop_signal = Arc::clone(&self.stop_signal);
+
+        // The signal is cleared here rather than on the search thread, so that a "stop" sent straight after "go" is not lost
+        stop_signal.store(false, Ordering::Relaxed);
+
+        self.search_thread = Some(thread::spawn(move || {
+            let start_time = Instant::now();
+
+            let result = engine.search_with_progress(&gamestate, &limits, |iteration| {
+                send_to(&output, &info_line(iteration, start_time));
+            });
+
+            // An infinite search must not send its best move until it is told to stop, even if it has finished
+            while infinite && !stop_signal.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(5));
+            }
+
+            let best_move = match result.best_move {
+                Some(best_move) => best_move.to_string(),
+                None => "0000".to_string(),
+            };
+
+            send_to(&output, &format!("bestmove {}", best_move));
+
+            engine
+        }));
+    }
+
+    // Stops the running search, if there is one, and waits for it to send its best move
+    fn stop_search(&mut self) {
+        self.stop_signal.store(true, Ordering::Relaxed);
+        self.finish_search();
+    }
+
+    // Waits for the running search, if there is one, to send its best move
+    fn finish_search(&mut self) {
+        let search_thread = match self.search_thread.take() {
+            Some(search_thread) => search_thread,
+            None => return,
+        };
+
+        match search_thread.join() {
+            Ok(engine) => self.engine = Some(engine),
+            Err(_) => unreachable!("[INTERNAL ERROR] Search thread panicked"),
+        }
+    }
+
+    fn send(&self, line: &str) {
+        send_to(&self.output, line);
+    }
+}
+
+// The largest transposition table the Hash option allows, in megabytes
+const MAX_HASH_SIZE_MB: usize = 65_536;
+
+// Writes a line to the shared output, flushing it straight away so that the GUI receives it
+fn send_to(output: &Mutex<Box<dyn Write + Send>>, line: &str) {
+    let mut output = match output.lock() {
+        Ok(output) => output,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+
+    // There is nobody to report a closed output to, so write errors are ignored
+    let _ = writeln!(output, "{}", line);
+    let _ = output.flush();
+}
+
+// Parses the arguments of a "position" command, like "startpos moves e2e4 e7e5" or "fen <FEN> moves e7e8q"
+// In Chess960 mode, the Chess960 castling rules apply even to positions whose FEN looks like standard chess
+fn parse_position(arguments: &[&str], chess960: bool) -> Result<ChessGamestate, String> {
+    let moves_index = arguments
+        .iter()
+        .position(|argument| *argument == "moves")
+        .unwrap_or(arguments.len());
+
+    let (position, moves) = arguments.split_at(moves_index);
+
+    let mut gamestate = match position {
+        ["startpos"] => ChessGamestate::new(),
+        ["fen", fen_fields @ ..] => ChessGamestate::from_fen(&fen_fields.join(" "))
+            .map_err(|err| format!("Invalid FEN: {}", err))?,
+        _ => return Err("Expected \"startpos\" or \"fen\"".to_string()),
+    };
+
+    if chess960 {
+        gamestate.chess960 = true;
+    }
+
+    // The first argument of the moves is the "moves" keyword itself
+    // GUIs send every move of the game, even after it has been drawn by rule, so only the moves' legality is checked
+    for encoded_move in moves.iter().skip(1) {
+        let parsed_move: ChessMove = encoded_move
+            .parse()
+            .map_err(|err| format!("Invalid move {}: {}", encoded_move, err))?;
+
+        gamestate
+            .perform_legal_move(&parsed_move)
+            .map_err(|err| format!("Illegal move {}: {}", encoded_move, err))?;
+    }
+
+    Ok(gamestate)
+}
+
+// Parses the arguments of a "go" command into search limits, and whether the search is infinite
+// Arguments which the engine does not support, like "ponder" or "searchmoves", are ignored
+fn parse_go(arguments: &[&str], turn_color: ChessPieceColor) -> (ChessSearchLimits, bool) {
+    let mut limits = ChessSearchLimits::default();
+    let mut infinite = false;
+
+    let mut time_left = None;
+    let mut increment = Duration::ZERO;
+    let mut moves_to_go = None;
+
+    let (time_left_name, increment_name) = match turn_color {
+        ChessPieceColor::White => ("wtime", "winc"),
+        ChessPieceColor::Black => ("btime", "binc"),
+    };
+
+    let mut arguments = arguments.iter();
+
+    while let Some(name) = arguments.next() {
+        if *name == "infinite" {
+            infinite = true;
+            continue;
+        }
+
+        // Every other supported argument is followed by a number
+        // Some GUIs send a negative time once a clock has run out, which is treated as no time left
+        let value = match arguments
+            .clone()
+            .next()
+            .and_then(|value| value.parse::<i64>().ok())
+            .map(|value| value.max(0) as u64)
+        {
+            Some(value) => {
+                arguments.next();
+                value
+            }
+            None => continue,
+        };
+
+        match *name {
+            "depth" => limits.depth = Some(value as u32),
+            "movetime" => limits.move_time = Some(Duration::from_millis(value)),
+            "movestogo" => moves_to_go = Some(value as u32),
+            _ if *name == time_left_name => time_left = Some(Duration::from_millis(value)),
+            _ if *name == increment_name => increment = Duration::from_millis(value),
+            _ => {}
+        }
+    }
+
+    // A fixed time per move takes precedence over the clock
+    if let (Some(time_left), None) = (time_left, limits.move_time) {
+        limits.move_time =
+            ChessSearchLimits::from_clock(time_left, increment, moves_to_go).move_time;
+    }
+
+    // Without any limit the search would never end, so a default depth is used unless the search is infinite
+    if !infinite && limits.depth.is_none() && limits.move_time.is_none() {
+        limits.depth = Some(ChessSearchLimits::DEFAULT_DEPTH);
+    }
+
+    (limits, infinite)
+}
+
+// Formats the result of a search iteration as an info line, like "info depth 5 score cp 30 nodes 12000 nps 800000 hashfull 4 time 15 pv e2e4 e7e5"
+fn info_line(iteration: &ChessSearchResult, start_time: Instant) -> String {
+    let score = match iteration.mate_in() {
+        Some(moves_to_mate) => format!("mate {}", moves_to_mate),
+        None => format!("cp {}", iteration.score),
+    };
+
+    let elapsed_milliseconds = start_time.elapsed().as_millis();
+    let nodes_per_second = iteration.nodes as u128 * 1000 / elapsed_milliseconds.max(1);
+
+    let principal_variation: Vec<String> = iteration
+        .principal_variation
+        .iter()
+        .map(|pv_move| pv_move.to_string())
+        .collect();
+
+    format!(
+        "info depth {} score {} nodes {} nps {} hashfull {} time {} pv {}",
+        iteration.depth,
+        score,
+        iteration.nodes,
+        nodes_per_second,
+        iteration.hashfull,
+        elapsed_milliseconds,
+        principal_variation.join(" ")
+    )
+}
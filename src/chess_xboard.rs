@@ -0,0 +1,328 @@
+use crate::ChessDrawClaim;
+use crate::ChessEngine;
+use crate::ChessGameStatus;
+use crate::ChessGamestate;
+use crate::ChessMove;
+use crate::ChessPieceColor;
+use crate::ChessSearchLimits;
+use crate::ChessTranspositionTable;
+
+use std::io::BufRead;
+use std::io::Write;
+use std::time::Duration;
+
+// A front end for the Chess Engine Communication Protocol, which is spoken by XBoard, WinBoard and older tooling
+// Unlike the UCI front end, the engine keeps track of the game itself, and replies to the opponent's moves on its own
+pub struct ChessXboard {
+    gamestate: ChessGamestate,
+    engine: ChessEngine,
+    // The color the engine plays, or None in force mode, where moves are only recorded
+    engine_color: Option<ChessPieceColor>,
+    // Whether moves are sent prefixed with "usermove", which is negotiated with the usermove feature
+    // Moves are accepted either way, so this is only used to tell moves apart from unknown commands
+    usermove_prefix: bool,
+    // The search depth limit set by "sd"
+    max_depth: Option<u32>,
+    // The exact time per move set by "st"
+    time_per_move: Option<Duration>,
+    // The time control set by "level": moves per time control (0 for the whole game) and the increment
+    moves_per_session: u32,
+    increment: Duration,
+    // The time left on the engine's clock, as last reported by "time"
+    time_left: Option<Duration>,
+    // The size of the engine's transposition table in megabytes, as set by "memory"
+    hash_size_mb: usize,
+    // Whether the game is Fischer Random (Chess960), as set by "variant fischerandom" until the next "new"
+    chess960: bool,
+    output: Box<dyn Write>,
+}
+
+impl ChessXboard {
+    pub fn new(output: impl Write + 'static) -> Self {
+        ChessXboard {
+            gamestate: ChessGamestate::new(),
+            engine: ChessEngine::new(),
+            engine_color: Some(ChessPieceColor::Black),
+            usermove_prefix: false,
+            max_depth: None,
+            time_per_move: None,
+            moves_per_session: 0,
+            increment: Duration::ZERO,
+            time_left: None,
+            hash_size_mb: ChessTranspositionTable::DEFAULT_SIZE_MB,
+            chess960: false,
+            output: Box::new(output),
+        }
+    }
+
+    // Handles commands until "quit" is received or the input ends
+    pub fn run(&mut self, input: impl BufRead) {
+        for line in input.lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(_) => return,
+            };
+
+            if !self.handle_command(&line) {
+                return;
+            }
+        }
+    }
+
+    // Handles a single command, returning false once the engine should quit
+    pub fn handle_command(&mut self, command: &str) -> bool {
+        let tokens: Vec<&str> = command.split_whitespace().collect();
+
+        match tokens.as_slice() {
+            [] | ["xboard"] | ["random"] | ["post"] | ["nopost"] | ["hard"] | ["easy"] => {}
+            ["computer"] | ["result", ..] | ["otim", ..] | ["accepted", ..] => {}
+            ["protover", ..] => {
+                self.send(
+                    "feature myname=\"LTChess\" setboard=1 usermove=1 san=0 ping=1 memory=1 sigint=0 sigterm=0 colors=0 variants=\"normal,fischerandom\" done=1",
+                );
+                self.usermove_prefix = true;
+            }
+            ["rejected", "usermove"] => self.usermove_prefix = false,
+            ["rejected", ..] => {}
+            ["new"] => {
+                self.gamestate = ChessGamestate::new();
+                self.engine = ChessEngine::with_hash_size(self.hash_size_mb);
+                self.engine_color = Some(ChessPieceColor::Black);
+                self.max_depth = None;
+                self.time_per_move = None;
+                self.chess960 = false;
+            }
+            // The variant is sent after "new", and the starting position follows with "setboard"
+            ["variant", "normal"] => self.chess960 = false,
+            ["variant", "fischerandom"] => {
+                self.chess960 = true;
+                self.gamestate.chess960 = true;
+            }
+            ["variant", ..] => self.send_error("unsupported variant", command),
+            ["force"] => self.engine_color = None,
+            ["go"] => {
+                self.engine_color = Some(self.gamestate.turn_color);
+                self.play_engine_move();
+            }
+            ["playother"] => {
+                self.engine_color = Some(match self.gamestate.turn_color {
+                    ChessPieceColor::White => ChessPieceColor::Black,
+                    ChessPieceColor::Black => ChessPieceColor::White,
+                });
+            }
+            ["setboard", fen_fields @ ..] => {
+                match ChessGamestate::from_fen(&fen_fields.join(" ")) {
+                    Ok(gamestate) => {
+                        self.gamestate = gamestate;
+                        self.gamestate.chess960 |= self.chess960;
+                    }
+                    Err(err) => self.send(&format!("tellusererror Illegal position: {}", err)),
+                }
+            }
+            ["usermove", encoded_move] => self.play_user_move(encoded_move),
+            ["undo"] => {
+                self.gamestate.unmake_move();
+            }
+            ["remove"] => {
+                self.gamestate.unmake_move();
+                self.gamestate.unmake_move();
+            }
+            ["level", moves_per_session, base_time, increment] => {
+                match parse_level(moves_per_session, base_time, increment) {
+                    Some((moves_per_session, base_time, increment)) => {
+                        self.moves_per_session = moves_per_session;
+                        self.increment = increment;
+                        self.time_left = Some(base_time);
+                        self.time_per_move = None;
+                    }
+                    None => self.send_error("invalid time control", command),
+                }
+            }
+            ["st", seconds] => match seconds.parse::<u64>() {
+                Ok(seconds) => self.time_per_move = Some(Duration::from_secs(seconds)),
+                Err(_) => self.send_error("invalid time per move", command),
+            },
+            ["sd", depth] => match depth.parse::<u32>() {
+                Ok(depth) => self.max_depth = Some(depth.max(1)),
+                Err(_) => self.send_error("invalid depth", command),
+            },
+            // Clock times are given in centiseconds
+            ["time", centiseconds] => match centiseconds.parse::<u64>() {
+                Ok(centiseconds) => self.time_left = Some(Duration::from_millis(centiseconds * 10)),
+                Err(_) => self.send_error("invalid time", command),
+            },
+            // The memory limit covers the whole engine, but the transposition table is the only part of it that can grow
+            ["memory", size_mb] => match size_mb.parse::<usize>() {
+                Ok(size_mb) => {
+                    self.hash_size_mb = size_mb.max(1);
+                    self.engine.set_hash_size(self.hash_size_mb);
+                }
+                Err(_) => self.send_error("invalid memory size", command),
+            },
+            ["ping", number] => self.send(&format!("pong {}", number)),
+            ["quit"] => return false,
+            // Without the usermove feature, moves are sent on their own
+            [encoded_move]
+                if !self.usermove_prefix && encoded_move.parse::<ChessMove>().is_ok() =>
+            {
+                self.play_user_move(encoded_move)
+            }
+            _ => self.send_error("unknown command", command),
+        }
+
+        true
+    }
+
+    // Performs the opponent's move, then replies with the engine's move if it is the engine's turn
+    fn play_user_move(&mut self, encoded_move: &str) {
+        // Moves are normally in coordinate notation, but SAN is accepted as well
+        let parsed_move = encoded_move
+            .parse::<ChessMove>()
+            .or_else(|_| ChessMove::from_san(encoded_move, &self.gamestate));
+
+        let game_was_over = self.gamestate.status().is_over();
+
+        let performed = match parsed_move {
+            // Games are replayed and analysed past a draw by rule, so only the move's legality is checked
+            Ok(parsed_move) => self.gamestate.perform_legal_move(&parsed_move).is_ok(),
+            Err(_) => false,
+        };
+
+        if !performed {
+            self.send(&format!("Illegal move: {}", encoded_move));
+            return;
+        }
+
+        // The result of a game which was already over has been reported, so moves past its end are only recorded
+        if game_was_over {
+            return;
+        }
+
+        if self.report_result() {
+            return;
+        }
+
+        if self.engine_color == Some(self.gamestate.turn_color) {
+            self.play_engine_move();
+        }
+    }
+
+    // Searches for the engine's move and plays it, unless the game is already over
+    fn play_engine_move(&mut self) {
+        if self.report_result() {
+            return;
+        }
+
+        let limits = self.search_limits();
+        let result = self.engine.search(&self.gamestate, &limits);
+
+        let best_move = match result.best_move {
+            Some(best_move) => best_move,
+            None => unreachable!("[INTERNAL ERROR] Engine did not find a move in an ongoing game"),
+        };
+
+        // Fischer Random castling is sent as O-O or O-O-O, as the King's move alone can be ambiguous
+        let encoded_move = match (self.chess960, self.gamestate.is_castling(&best_move)) {
+            (true, true) if best_move.change_in_x() > 0 => "O-O".to_string(),
+            (true, true) => "O-O-O".to_string(),
+            _ => best_move.to_string(),
+        };
+
+        if let Err(err) = self.gamestate.perform_move(&best_move) {
+            unreachable!("[INTERNAL ERROR] Engine chose an illegal move: {}", err);
+        }
+
+        self.send(&format!("move {}", encoded_move));
+        self.report_result();
+    }
+
+    // Gets the search limits for the engine's next move, from whichever time control has been set
+    fn search_limits(&self) -> ChessSearchLimits {
+        let mut limits = match (self.time_per_move, self.time_left) {
+            (Some(time_per_move), _) => ChessSearchLimits::move_time(time_per_move),
+            (None, Some(time_left)) => {
+                ChessSearchLimits::from_clock(time_left, self.increment, self.moves_to_go())
+            }
+            (None, None) => ChessSearchLimits::default(),
+        };
+
+        limits.depth = self.max_depth;
+
+        // Without any limit the search would never end, so a default depth is used
+        if limits.depth.is_none() && limits.move_time.is_none() {
+            limits.depth = Some(ChessSearchLimits::DEFAULT_DEPTH);
+        }
+
+        limits
+    }
+
+    // Gets the number of moves the engine must make before its clock is next topped up, if the time control has sessions
+    fn moves_to_go(&self) -> Option<u32> {
+        if self.moves_per_session == 0 {
+            return None;
+        }
+
+        let moves_played = self.gamestate.fullmove_clock - 1;
+
+        Some(self.moves_per_session - moves_played % self.moves_per_session)
+    }
+
+    // Sends the result of the game if it has ended, returning whether it has
+    fn report_result(&mut self) -> bool {
+        use ChessGameStatus::*;
+
+        let result = match self.gamestate.status() {
+            Ongoing => return false,
+            Checkmate(ChessPieceColor::White) => "1-0 {White mates}",
+            Checkmate(ChessPieceColor::Black) => "0-1 {Black mates}",
+            Stalemate => "1/2-1/2 {Stalemate}",
+            InsufficientMaterial => "1/2-1/2 {Insufficient material}",
+            FivefoldRepetition => "1/2-1/2 {Fivefold repetition}",
+            SeventyFiveMoveRule => "1/2-1/2 {75-move rule}",
+            DrawClaimed(ChessDrawClaim::ThreefoldRepetition) => "1/2-1/2 {Draw by repetition}",
+            DrawClaimed(ChessDrawClaim::FiftyMoveRule) => "1/2-1/2 {50-move rule}",
+            Timeout(ChessPieceColor::White) => "1-0 {Black forfeits on time}",
+            Timeout(ChessPieceColor::Black) => "0-1 {White forfeits on time}",
+            TimeoutVsInsufficientMaterial => "1/2-1/2 {Timeout vs insufficient material}",
+        };
+
+        self.send(result);
+
+        true
+    }
+
+    fn send_error(&mut self, error_type: &str, command: &str) {
+        self.send(&format!("Error ({}): {}", error_type, command));
+    }
+
+    fn send(&mut self, line: &str) {
+        // There is nobody to report a closed output to, so write errors are ignored
+        let _ = writeln!(self.output, "{}", line);
+        let _ = self.output.flush();
+    }
+}
+
+// Parses the arguments of a "level" command, like "40 5 0" or "0 2:30 1"
+// The base time is in minutes, optionally with seconds, and the increment is in seconds
+fn parse_level(
+    moves_per_session: &str,
+    base_time: &str,
+    increment: &str,
+) -> Option<(u32, Duration, Duration)> {
+    let moves_per_session = moves_per_session.parse().ok()?;
+
+    let base_seconds = match base_time.split_once(':') {
+        Some((minutes, seconds)) => {
+            minutes.parse::<u64>().ok()? * 60 + seconds.parse::<u64>().ok()?
+        }
+        None => base_time.parse::<u64>().ok()? * 60,
+    };
+
+    let increment = Duration::try_from_secs_f64(increment.parse().ok()?).ok()?;
+
+    Some((
+        moves_per_session,
+        Duration::from_secs(base_seconds),
+        increment,
+    ))
+}
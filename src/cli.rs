@@ -0,0 +1,18 @@
+// The command line front ends of the binary: the terminal game, the full-screen terminal UI and the perft command
+// The UCI and XBoard front ends are part of the library, as they only read and write text
+mod chess_game_options;
+// The parts of a game shared by the terminal game and the terminal UI: starting it, the engine's moves and the clock,
+// and how its events are described to the players
+mod chess_game_session;
+mod chess_perft_command;
+mod chess_terminal_game;
+#[cfg(feature = "tui")]
+mod chess_tui;
+
+pub use chess_game_options::parse_game_options;
+pub use chess_game_options::GameOptions;
+pub use chess_game_options::USAGE;
+pub use chess_perft_command::run_perft;
+pub use chess_terminal_game::run as run_terminal_game;
+#[cfg(feature = "tui")]
+pub use chess_tui::run as run_tui;
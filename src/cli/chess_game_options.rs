@@ -0,0 +1,149 @@
+use crate::cli::chess_game_session::opponent_color;
+
+use ltchess::ChessGamestate;
+use ltchess::ChessOpeningBook;
+use ltchess::ChessPieceColor;
+use ltchess::ChessSearchLimits;
+use ltchess::ChessTablebase;
+use ltchess::ChessTimeControl;
+use ltchess::ChessTranspositionTable;
+
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::hash::BuildHasher;
+use std::time::Duration;
+
+// How the binary is run, shown when the command line arguments cannot be read
+pub const USAGE: &str = "\
+Usage: ltchess [--play <white|black>] [--depth <plies>] [--time <seconds>] [--hash <MB>]
+               [--book <path>] [--book-selection <random|best>] [--tb-path <directory>]
+               [--chess960 <index|random>] [--clock <time control>]
+       ltchess tui [options]
+       ltchess perft <depth> [FEN]
+       ltchess uci
+       ltchess xboard";
+
+// The options for a game, given as command line arguments
+pub struct GameOptions {
+    // The color the engine plays, if one of the players is the engine
+    pub engine_color: Option<ChessPieceColor>,
+    // How long the engine thinks about each move, if given; otherwise it plays to its clock, or thinks for two seconds
+    pub search_limits: Option<ChessSearchLimits>,
+    // The size of the engine's transposition table in megabytes
+    pub hash_size_mb: usize,
+    // The book the engine plays its opening moves from, if it was given one
+    pub opening_book: Option<ChessOpeningBook>,
+    // Whether the engine always plays the book's most recommended move, rather than picking one at random by weight
+    pub book_plays_best: bool,
+    // The Syzygy tablebases which the "tb" command looks positions up in, if a directory of them was given
+    pub tablebase: Option<ChessTablebase>,
+    // The index of the Chess960 starting position to play from, if the game is a Chess960 game
+    pub chess960_index: Option<usize>,
+    // The time control the game is played under, if it is timed
+    pub time_control: Option<ChessTimeControl>,
+}
+
+// Reads the game options from the command line arguments
+// Without any, two players share the terminal; "--play <color>" plays against the engine as that color
+pub fn parse_game_options(args: &[String]) -> Result<GameOptions, String> {
+    let mut player_color = None;
+    let mut depth = None;
+    let mut move_time = None;
+    let mut hash_size_mb = ChessTranspositionTable::DEFAULT_SIZE_MB;
+    let mut opening_book = None;
+    let mut book_plays_best = false;
+    let mut tablebase = None;
+    let mut chess960_index = None;
+    let mut time_control = None;
+
+    let mut args = args.iter();
+
+    while let Some(option) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("The option {} needs a value.", option))?;
+
+        match option.as_str() {
+            "--play" => {
+                player_color = Some(match value.as_str() {
+                    "white" => ChessPieceColor::White,
+                    "black" => ChessPieceColor::Black,
+                    _ => return Err(format!("\"{}\" is not a color to play as.", value)),
+                })
+            }
+            "--depth" => match value.parse::<u32>() {
+                Ok(plies) if plies > 0 => depth = Some(plies),
+                _ => return Err(format!("\"{}\" is not a valid search depth.", value)),
+            },
+            // Times too long to be held in a Duration, like 1e30 seconds, are rejected rather than overflowing it
+            "--time" => match value.parse::<f64>().map(Duration::try_from_secs_f64) {
+                Ok(Ok(time)) if !time.is_zero() => move_time = Some(time),
+                _ => return Err(format!("\"{}\" is not a valid time per move.", value)),
+            },
+            "--hash" => match value.parse::<usize>() {
+                Ok(size_mb) if size_mb > 0 => hash_size_mb = size_mb,
+                _ => return Err(format!("\"{}\" is not a valid hash size.", value)),
+            },
+            "--book" => opening_book = Some(load_opening_book(value)?),
+            "--book-selection" => {
+                book_plays_best = match value.as_str() {
+                    "random" => false,
+                    "best" => true,
+                    _ => return Err(format!("\"{}\" is not a way to pick book moves.", value)),
+                }
+            }
+            "--tb-path" => {
+                tablebase = Some(ChessTablebase::open(value).map_err(|err| err.to_string())?)
+            }
+            "--chess960" => {
+                chess960_index = Some(match value.as_str() {
+                    "random" => {
+                        RandomState::new().hash_one(()) as usize
+                            % ChessGamestate::CHESS960_POSITION_COUNT
+                    }
+                    _ => match value.parse::<usize>() {
+                        Ok(index) if index < ChessGamestate::CHESS960_POSITION_COUNT => index,
+                        _ => {
+                            return Err(format!(
+                                "\"{}\" is not a Chess960 position from 0 to 959.",
+                                value
+                            ))
+                        }
+                    },
+                })
+            }
+            "--clock" => {
+                time_control = Some(
+                    value
+                        .parse::<ChessTimeControl>()
+                        .map_err(|err| err.to_string())?,
+                )
+            }
+            _ => return Err(format!("Unknown option {}.", option)),
+        }
+    }
+
+    let search_limits = match (depth, move_time) {
+        (None, None) => None,
+        (depth, move_time) => Some(ChessSearchLimits { depth, move_time }),
+    };
+
+    Ok(GameOptions {
+        engine_color: player_color.map(opponent_color),
+        search_limits,
+        hash_size_mb,
+        opening_book,
+        book_plays_best,
+        tablebase,
+        chess960_index,
+        time_control,
+    })
+}
+
+// Reads a Polyglot opening book, describing the problem if it cannot be loaded
+fn load_opening_book(path: &str) -> Result<ChessOpeningBook, String> {
+    let bytes = fs::read(path)
+        .map_err(|err| format!("The opening book {} could not be read: {}", path, err))?;
+
+    ChessOpeningBook::from_bytes(&bytes).map_err(|err| err.to_string())
+}
@@ -0,0 +1,171 @@
+use crate::cli::GameOptions;
+
+use ltchess::ChessClock;
+use ltchess::ChessDrawClaim;
+use ltchess::ChessEngine;
+use ltchess::ChessGameStatus;
+use ltchess::ChessGamestate;
+use ltchess::ChessMove;
+use ltchess::ChessPgnGame;
+use ltchess::ChessPieceColor;
+use ltchess::ChessSearchLimits;
+
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::time::Duration;
+use std::time::Instant;
+
+// Creates the record of a new game, which starts from the Chess960 position given in the options if there is one
+pub fn new_game_record(options: &GameOptions) -> ChessPgnGame {
+    match options
+        .chess960_index
+        .and_then(ChessGamestate::new_chess960)
+    {
+        Some(starting_gamestate) => {
+            let mut record = ChessPgnGame::from_gamestate(starting_gamestate);
+            record.set_tag("Variant", "Chess960");
+            record
+        }
+        None => ChessPgnGame::new(),
+    }
+}
+
+// Starts the clock of a timed game, which runs from the moment the board is first shown
+pub fn start_clock(options: &GameOptions, turn_color: ChessPieceColor) -> Option<ChessClock> {
+    options.time_control.clone().map(|time_control| {
+        let mut clock = ChessClock::new(time_control, turn_color);
+        clock.start(Instant::now());
+        clock
+    })
+}
+
+// Picks the engine's move in an ongoing game, from its opening book if it has one, or else by searching
+pub fn choose_engine_move(
+    options: &GameOptions,
+    engine: &mut ChessEngine,
+    clock: Option<&ChessClock>,
+    game: &ChessGamestate,
+) -> ChessMove {
+    // Moves from the opening book are played without searching
+    if let Some(book_move) = book_move(options, game) {
+        return book_move;
+    }
+
+    let search_limits = engine_search_limits(options, clock, game.turn_color);
+
+    // The game is not over, so there is always a legal move to play
+    match engine.search(game, &search_limits).best_move {
+        Some(best_move) => best_move,
+        None => unreachable!("[INTERNAL ERROR] Engine did not find a legal move"),
+    }
+}
+
+// Gets how long the engine may think about its move
+// Limits given on the command line always apply; otherwise the engine plays to its clock, or thinks for two seconds
+fn engine_search_limits(
+    options: &GameOptions,
+    clock: Option<&ChessClock>,
+    engine_color: ChessPieceColor,
+) -> ChessSearchLimits {
+    match (options.search_limits, clock) {
+        (Some(search_limits), _) => search_limits,
+        (None, Some(clock)) => ChessSearchLimits::from_clock(
+            clock.time_left(engine_color, Instant::now()),
+            clock.bonus(engine_color).duration(),
+            clock.moves_to_go(engine_color),
+        ),
+        (None, None) => ChessSearchLimits::move_time(Duration::from_secs(2)),
+    }
+}
+
+// Picks the engine's move from its opening book, if it has one and the position is in it
+fn book_move(options: &GameOptions, game: &ChessGamestate) -> Option<ChessMove> {
+    let opening_book = options.opening_book.as_ref()?;
+
+    match options.book_plays_best {
+        true => opening_book.best_move(game),
+        // Each RandomState is seeded randomly, which is enough randomness to vary the engine's openings
+        false => opening_book.weighted_random_move(game, RandomState::new().hash_one(())),
+    }
+}
+
+// Presses the clock after a move, if the game is timed
+// A move made after the player's time ran out does not count, so it is taken back and the game is lost on time
+pub fn press_clock(clock: &mut Option<ChessClock>, record: &mut ChessPgnGame) {
+    if let Some(clock) = clock {
+        if !clock.press(Instant::now()) {
+            record.undo();
+        }
+    }
+}
+
+// Formats a clock time as minutes and seconds, like "4:05", with hours once there is at least one
+// and tenths of a second once there are fewer than ten seconds left
+pub fn format_clock_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+
+    match seconds {
+        3600.. => format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        ),
+        10.. => format!("{}:{:02}", seconds / 60, seconds % 60),
+        _ => format!("0:{:02}.{}", seconds, time.subsec_millis() / 100),
+    }
+}
+
+// Describes how a game ended, or gives None if it is still going on
+pub fn game_over_message(status: ChessGameStatus, game: &ChessGamestate) -> Option<String> {
+    Some(match status {
+        ChessGameStatus::Ongoing => return None,
+        ChessGameStatus::Checkmate(winner) => format!("Checkmate! {} wins.", color_name(winner)),
+        ChessGameStatus::Stalemate => "Stalemate! The game is a draw.".to_string(),
+        ChessGameStatus::InsufficientMaterial => {
+            "Neither player has enough material to checkmate. The game is a draw.".to_string()
+        }
+        ChessGameStatus::FivefoldRepetition => {
+            "The same position has occurred five times. The game is a draw.".to_string()
+        }
+        ChessGameStatus::SeventyFiveMoveRule => {
+            "75 moves have passed without a capture or Pawn move. The game is a draw.".to_string()
+        }
+        ChessGameStatus::DrawClaimed(draw_claim) => format!(
+            "{} claimed a draw by {}. The game is a draw.",
+            color_name(game.turn_color),
+            draw_claim_name(draw_claim)
+        ),
+        ChessGameStatus::Timeout(winner) => format!(
+            "{} ran out of time! {} wins.",
+            color_name(game.turn_color),
+            color_name(winner)
+        ),
+        ChessGameStatus::TimeoutVsInsufficientMaterial => format!(
+            "{} ran out of time, but {} cannot checkmate. The game is a draw.",
+            color_name(game.turn_color),
+            color_name(opponent_color(game.turn_color))
+        ),
+    })
+}
+
+pub fn color_name(color: ChessPieceColor) -> &'static str {
+    match color {
+        ChessPieceColor::White => "White",
+        ChessPieceColor::Black => "Black",
+    }
+}
+
+pub fn opponent_color(color: ChessPieceColor) -> ChessPieceColor {
+    match color {
+        ChessPieceColor::White => ChessPieceColor::Black,
+        ChessPieceColor::Black => ChessPieceColor::White,
+    }
+}
+
+pub fn draw_claim_name(draw_claim: ChessDrawClaim) -> &'static str {
+    match draw_claim {
+        ChessDrawClaim::ThreefoldRepetition => "threefold repetition",
+        ChessDrawClaim::FiftyMoveRule => "the fifty-move rule",
+    }
+}
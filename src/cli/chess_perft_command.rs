@@ -0,0 +1,40 @@
+use ltchess::ChessGamestate;
+
+use std::process;
+
+// Prints the perft count for each legal move and their total, for the given depth and FEN
+// The FEN may be given as a single argument or as separate fields, and defaults to the starting position
+pub fn run_perft(args: &[String]) {
+    let depth = match args.first().map(|depth| depth.parse::<u32>()) {
+        Some(Ok(depth)) => depth,
+        _ => {
+            eprintln!("Usage: ltchess perft <depth> [FEN]");
+            process::exit(1);
+        }
+    };
+
+    let game = match args.len() {
+        1 => ChessGamestate::new(),
+        _ => match ChessGamestate::from_fen(&args[1..].join(" ")) {
+            Ok(game) => game,
+            Err(err) => {
+                eprintln!("The FEN could not be read: {}", err);
+                process::exit(1);
+            }
+        },
+    };
+
+    let mut total_leaf_positions = 0;
+
+    for (legal_move, leaf_positions) in game.divide(depth) {
+        println!("{}: {}", legal_move, leaf_positions);
+        total_leaf_positions += leaf_positions;
+    }
+
+    // Depth 0 has no moves to divide by, but still counts the current position
+    if depth == 0 {
+        total_leaf_positions = game.perft(0);
+    }
+
+    println!("\nNodes searched: {}", total_leaf_positions);
+}
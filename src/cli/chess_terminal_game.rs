@@ -0,0 +1,321 @@
+use crate::cli::chess_game_session::choose_engine_move;
+use crate::cli::chess_game_session::color_name;
+use crate::cli::chess_game_session::draw_claim_name;
+use crate::cli::chess_game_session::format_clock_time;
+use crate::cli::chess_game_session::game_over_message;
+use crate::cli::chess_game_session::new_game_record;
+use crate::cli::chess_game_session::opponent_color;
+use crate::cli::chess_game_session::press_clock;
+use crate::cli::chess_game_session::start_clock;
+use crate::cli::GameOptions;
+
+use ltchess::ChessClock;
+use ltchess::ChessEngine;
+use ltchess::ChessError;
+use ltchess::ChessGamestate;
+use ltchess::ChessMove;
+use ltchess::ChessPgnGame;
+use ltchess::ChessPgnResult;
+use ltchess::ChessPieceColor;
+use ltchess::ChessSanError;
+use ltchess::ChessWdl;
+
+use std::fs;
+use std::io::stdin;
+use std::io::stdout;
+use std::io::Write;
+use std::time::Instant;
+
+// Plays a game in the terminal, with the board printed after every move and moves entered as text
+pub fn run(options: &GameOptions) {
+    let mut engine = ChessEngine::with_hash_size(options.hash_size_mb);
+
+    // The game record keeps every played move, so that the game can be saved as PGN
+    let mut record = new_game_record(options);
+    let mut clock = start_clock(options, record.current_gamestate().turn_color);
+
+    // This uses two loops to avoid printing the turn message on every failed move
+    loop {
+        let game = record.current_gamestate().clone();
+
+        clear_terminal();
+
+        print_board(&game, clock.as_ref());
+
+        // Once the game has ended, announce the result and stop asking for moves
+        let status = match &mut clock {
+            Some(clock) => clock.status(&game, Instant::now()),
+            None => game.status(),
+        };
+
+        if let Some(message) = game_over_message(status, &game) {
+            println!("\n{}", message);
+        }
+
+        if status.is_over() {
+            record.result = ChessPgnResult::from_status(status);
+            offer_save(&record);
+            return;
+        }
+
+        if let Some(last_move) = record.san_moves().last() {
+            println!("\nThe last move was {}.", last_move);
+        }
+
+        if game.is_in_check() {
+            println!("\n{} is in check.", color_name(game.turn_color));
+        }
+
+        if options.engine_color == Some(game.turn_color) {
+            println!("\nThe engine is thinking...");
+            flush();
+
+            let engine_move = choose_engine_move(options, &mut engine, clock.as_ref(), &game);
+
+            match record.push_move(&engine_move) {
+                Ok(_) => {
+                    press_clock(&mut clock, &mut record);
+                    continue;
+                }
+                Err(_) => unreachable!("[INTERNAL ERROR] Engine chose an illegal move"),
+            }
+        }
+
+        println!(
+            "\nIt is {}'s turn.",
+            color_name(game.turn_color).to_lowercase()
+        );
+
+        if let Some(draw_claim) = game.claimable_draw() {
+            println!(
+                "You may claim a draw by {}; enter \"claim draw\" to do so.",
+                draw_claim_name(draw_claim)
+            );
+        }
+
+        loop {
+            print!("Enter a move: ");
+            flush();
+
+            let mut user_inputted_move = String::new();
+
+            // The game ends if the input is closed, as no more moves can be entered
+            if stdin().read_line(&mut user_inputted_move).unwrap() == 0 {
+                return;
+            }
+
+            // The clock only runs forwards, so a timed game cannot be taken back or replaced
+            if clock.is_some()
+                && (matches!(user_inputted_move.trim(), "undo" | "redo")
+                    || user_inputted_move.trim().starts_with("load "))
+            {
+                println!("Moves cannot be taken back or loaded in a timed game.\n");
+                continue;
+            }
+
+            // Commands are checked for before the input is treated as a move
+            match user_inputted_move.trim() {
+                "undo" => match record.undo() {
+                    Some(_) => {
+                        // Against the engine, the player's own move is taken back along with the engine's reply
+                        if options.engine_color == Some(record.current_gamestate().turn_color) {
+                            record.undo();
+                        }
+
+                        break;
+                    }
+                    None => {
+                        println!("There are no moves to undo.\n");
+                        continue;
+                    }
+                },
+                "redo" => match record.redo() {
+                    Some(_) => {
+                        if options.engine_color == Some(record.current_gamestate().turn_color) {
+                            record.redo();
+                        }
+
+                        break;
+                    }
+                    None => {
+                        println!("There are no moves to redo.\n");
+                        continue;
+                    }
+                },
+                "tb" => {
+                    show_tablebase_result(options, &game);
+                    continue;
+                }
+                "claim draw" => match record.claim_draw() {
+                    Ok(_) => break,
+                    Err(err) => {
+                        println!("{}\n", err);
+                        continue;
+                    }
+                },
+                _ => {}
+            }
+
+            if let Some(path) = user_inputted_move.trim().strip_prefix("save ") {
+                save_game(&record, path.trim());
+                continue;
+            }
+
+            if let Some(path) = user_inputted_move.trim().strip_prefix("load ") {
+                match load_game(path.trim()) {
+                    Ok(loaded_record) => {
+                        record = loaded_record;
+                        break;
+                    }
+                    Err(message) => {
+                        println!("{}\n", message);
+                        continue;
+                    }
+                }
+            }
+
+            // Moves may be entered either as a pair of tiles or in Standard Algebraic Notation
+            let move_result = parse_move(&user_inputted_move, &game)
+                .and_then(|move_to_make| record.push_move(&move_to_make));
+
+            match move_result {
+                // If the move was valid, break out of the move repeat loop
+                Ok(_) => {
+                    press_clock(&mut clock, &mut record);
+                    break;
+                }
+                Err(err) => println!("{}\n", err),
+            }
+        }
+    }
+}
+
+// Parses a move entered either as a pair of tile positions, like "e2 e4", or in SAN, like "Nf3"
+// If neither notation matches, the problem is described in whichever notation is more informative
+fn parse_move(user_input: &str, game: &ChessGamestate) -> Result<ChessMove, ChessError> {
+    let coordinate_error = match user_input.parse::<ChessMove>() {
+        Ok(parsed_move) => return Ok(parsed_move),
+        Err(err) => err,
+    };
+
+    match ChessMove::from_san(user_input, game) {
+        Ok(parsed_move) => Ok(parsed_move),
+        Err(ChessSanError::Malformed) => Err(coordinate_error.into()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+// Asks the players whether they want to save a finished game, and saves it if they enter a path
+pub fn offer_save(record: &ChessPgnGame) {
+    print!("\nEnter a file path to save the game as PGN, or leave blank to exit: ");
+    flush();
+
+    let mut path = String::new();
+    stdin().read_line(&mut path).unwrap();
+
+    if !path.trim().is_empty() {
+        save_game(record, path.trim());
+    }
+}
+
+fn save_game(record: &ChessPgnGame, path: &str) {
+    match fs::write(path, record.to_pgn()) {
+        Ok(_) => println!("The game was saved to {}.\n", path),
+        Err(err) => println!("The game could not be saved: {}\n", err),
+    }
+}
+
+// Reads the first game from a PGN file, describing the problem if it cannot be loaded
+fn load_game(path: &str) -> Result<ChessPgnGame, String> {
+    let pgn =
+        fs::read_to_string(path).map_err(|err| format!("The file could not be read: {}", err))?;
+
+    ChessPgnGame::parse(&pgn).map_err(|err| err.to_string())
+}
+
+// Prints the board, with each player's time beside their side of it if the game is timed
+fn print_board(game: &ChessGamestate, clock: Option<&ChessClock>) {
+    for (y, row) in game.board.pieces().iter().enumerate().rev() {
+        for piece in row.iter() {
+            match piece {
+                Some(piece) => print!("[{}]", piece.get_piece_char()),
+                None => print!("[\u{2001}]"),
+            }
+        }
+
+        let clock_color = match y {
+            7 => Some(ChessPieceColor::Black),
+            0 => Some(ChessPieceColor::White),
+            _ => None,
+        };
+
+        if let (Some(clock), Some(color)) = (clock, clock_color) {
+            print!(
+                "   {}  {}",
+                color_name(color),
+                format_clock_time(clock.time_left(color, Instant::now()))
+            );
+        }
+
+        println!();
+    }
+}
+
+// Prints the tablebase result of the current position and the move which keeps it, for the "tb" command
+fn show_tablebase_result(options: &GameOptions, game: &ChessGamestate) {
+    let tablebase = match &options.tablebase {
+        Some(tablebase) => tablebase,
+        None => {
+            println!(
+                "No tablebase was given; start the game with --tb-path <directory> to use one.\n"
+            );
+            return;
+        }
+    };
+
+    let lookup = tablebase.probe_wdl(game).and_then(|wdl| {
+        let dtz = tablebase.probe_dtz(game)?;
+        let best_move = tablebase.best_move(game)?;
+
+        Ok((wdl, dtz, best_move))
+    });
+
+    let (wdl, dtz, best_move) = match lookup {
+        Ok(lookup) => lookup,
+        Err(err) => {
+            println!("{}\n", err);
+            return;
+        }
+    };
+
+    let player = color_name(game.turn_color);
+    let opponent = color_name(opponent_color(game.turn_color));
+
+    let verdict = match wdl {
+        ChessWdl::Win => format!("{} wins", player),
+        ChessWdl::CursedWin => format!("{} wins, but not within the fifty-move rule", player),
+        ChessWdl::Draw => "The position is a draw".to_string(),
+        ChessWdl::BlessedLoss => format!("{} wins, but not within the fifty-move rule", opponent),
+        ChessWdl::Loss => format!("{} wins", opponent),
+    };
+
+    match best_move {
+        Some((best_move, _)) => println!(
+            "{} (DTZ {}). The best move is {}.\n",
+            verdict,
+            dtz,
+            best_move
+                .to_san(game)
+                .unwrap_or_else(|_| best_move.to_string())
+        ),
+        None => println!("{} (DTZ {}).\n", verdict, dtz),
+    }
+}
+
+fn flush() {
+    stdout().flush().unwrap();
+}
+
+fn clear_terminal() {
+    print!("\x1B[2J\x1B[1;1H");
+}
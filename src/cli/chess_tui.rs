@@ -0,0 +1,702 @@
+use crate::cli::chess_game_session::choose_engine_move;
+use crate::cli::chess_game_session::color_name;
+use crate::cli::chess_game_session::format_clock_time;
+use crate::cli::chess_game_session::game_over_message;
+use crate::cli::chess_game_session::new_game_record;
+use crate::cli::chess_game_session::opponent_color;
+use crate::cli::chess_game_session::press_clock;
+use crate::cli::chess_game_session::start_clock;
+use crate::cli::chess_terminal_game::offer_save;
+use crate::cli::GameOptions;
+
+use ltchess::ChessClock;
+use ltchess::ChessEngine;
+use ltchess::ChessGameStatus;
+use ltchess::ChessMove;
+use ltchess::ChessPgnGame;
+use ltchess::ChessPgnResult;
+use ltchess::ChessPiece;
+use ltchess::ChessPieceColor;
+use ltchess::ChessPieceKind;
+use ltchess::ChessPoint;
+
+use crossterm::cursor;
+use crossterm::event;
+use crossterm::event::Event;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::MouseButton;
+use crossterm::event::MouseEvent;
+use crossterm::event::MouseEventKind;
+use crossterm::queue;
+use crossterm::style::Color;
+use crossterm::style::Print;
+use crossterm::style::ResetColor;
+use crossterm::style::SetBackgroundColor;
+use crossterm::style::SetForegroundColor;
+use crossterm::terminal;
+
+use std::io;
+use std::io::stdout;
+use std::io::Stdout;
+use std::io::Write;
+use std::time::Duration;
+use std::time::Instant;
+
+// The screen row of the top rank, and the screen column of the left file, with each tile three columns wide
+const BOARD_TOP: u16 = 2;
+const BOARD_LEFT: u16 = 3;
+const TILE_WIDTH: u16 = 3;
+// The screen column at which the side panel starts, to the right of the board
+const PANEL_LEFT: u16 = BOARD_LEFT + 8 * TILE_WIDTH + 4;
+// The most move list rows shown, after which only the latest moves are shown
+const MOVE_LIST_ROWS: usize = 10;
+// How often the screen is redrawn while waiting for input, so that running clocks stay up to date
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+const LIGHT_TILE: Color = Color::Rgb {
+    r: 240,
+    g: 217,
+    b: 181,
+};
+const DARK_TILE: Color = Color::Rgb {
+    r: 181,
+    g: 136,
+    b: 99,
+};
+const LAST_MOVE_TILE: Color = Color::Rgb {
+    r: 205,
+    g: 194,
+    b: 96,
+};
+const SELECTED_TILE: Color = Color::Rgb {
+    r: 106,
+    g: 162,
+    b: 205,
+};
+const DESTINATION_TILE: Color = Color::Rgb {
+    r: 130,
+    g: 190,
+    b: 110,
+};
+const CHECK_TILE: Color = Color::Rgb {
+    r: 220,
+    g: 70,
+    b: 60,
+};
+
+// A full-screen terminal UI for playing a game, in which pieces are picked with the arrow keys or the mouse
+struct ChessTui<'a> {
+    options: &'a GameOptions,
+    engine: ChessEngine,
+    record: ChessPgnGame,
+    clock: Option<ChessClock>,
+    // The tile the keyboard cursor is on
+    cursor: ChessPoint,
+    // The tile of the piece picked to move, if one has been picked
+    selected: Option<ChessPoint>,
+    // The moves to choose between when a Pawn is moved onto the last rank, one for each promotion piece
+    promotion_moves: Vec<ChessMove>,
+    // A message shown in the side panel until the next action, such as why a move could not be made
+    message: Option<String>,
+    // Whether the board is shown from Black's side, which it is when playing Black against the engine
+    flipped: bool,
+    stdout: Stdout,
+}
+
+// Puts the terminal back to how it was when the UI is closed, including when the program panics
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = crossterm::execute!(
+            stdout(),
+            event::DisableMouseCapture,
+            cursor::Show,
+            terminal::LeaveAlternateScreen
+        );
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+// Plays a game in the full-screen terminal UI, offering to save it once the UI is closed
+pub fn run(options: &GameOptions) -> io::Result<()> {
+    let record = {
+        terminal::enable_raw_mode()?;
+        let _guard = TerminalGuard;
+
+        crossterm::execute!(
+            stdout(),
+            terminal::EnterAlternateScreen,
+            terminal::Clear(terminal::ClearType::All),
+            event::EnableMouseCapture,
+            cursor::Hide
+        )?;
+
+        let mut tui = ChessTui::new(options);
+        tui.run()?;
+        tui.record
+    };
+
+    if !record.moves().is_empty() {
+        offer_save(&record);
+    }
+
+    Ok(())
+}
+
+impl<'a> ChessTui<'a> {
+    fn new(options: &'a GameOptions) -> Self {
+        let record = new_game_record(options);
+        let clock = start_clock(options, record.current_gamestate().turn_color);
+
+        // The board starts with the cursor on the King's Pawn of the player at the bottom of the screen
+        let flipped = options.engine_color == Some(ChessPieceColor::White);
+        let cursor = match flipped {
+            true => ChessPoint::new(4, 6),
+            false => ChessPoint::new(4, 1),
+        };
+
+        ChessTui {
+            options,
+            engine: ChessEngine::with_hash_size(options.hash_size_mb),
+            record,
+            clock,
+            cursor,
+            selected: None,
+            promotion_moves: Vec::new(),
+            message: None,
+            flipped,
+            stdout: stdout(),
+        }
+    }
+
+    // Runs the UI until the player quits
+    fn run(&mut self) -> io::Result<()> {
+        loop {
+            let status = self.status();
+
+            if status.is_over() {
+                self.record.result = ChessPgnResult::from_status(status);
+                self.selected = None;
+                self.promotion_moves.clear();
+            }
+
+            self.draw(status)?;
+
+            // The engine moves as soon as its turn is shown, and any input made while it thinks is handled afterwards
+            if !status.is_over()
+                && self.options.engine_color == Some(self.record.current_gamestate().turn_color)
+            {
+                let game = self.record.current_gamestate().clone();
+                let engine_move =
+                    choose_engine_move(self.options, &mut self.engine, self.clock.as_ref(), &game);
+
+                match self.record.push_move(&engine_move) {
+                    Ok(_) => press_clock(&mut self.clock, &mut self.record),
+                    Err(_) => unreachable!("[INTERNAL ERROR] Engine chose an illegal move"),
+                }
+
+                continue;
+            }
+
+            if !event::poll(REDRAW_INTERVAL)? {
+                continue;
+            }
+
+            let keep_running = match event::read()? {
+                Event::Key(key_event) if key_event.kind != KeyEventKind::Release => {
+                    self.handle_key(key_event, status)
+                }
+                Event::Mouse(mouse_event) => {
+                    self.handle_mouse(mouse_event, status);
+                    true
+                }
+                _ => true,
+            };
+
+            if !keep_running {
+                return Ok(());
+            }
+        }
+    }
+
+    // Gets the status of the game, which can also end by a player running out of time
+    fn status(&mut self) -> ChessGameStatus {
+        let game = self.record.current_gamestate();
+
+        match &mut self.clock {
+            Some(clock) => clock.status(game, Instant::now()),
+            None => game.status(),
+        }
+    }
+
+    // Handles a key press, returning false once the player quits
+    fn handle_key(&mut self, key_event: KeyEvent, status: ChessGameStatus) -> bool {
+        // While a promotion piece is being picked, only the promotion keys and Esc do anything
+        if !self.promotion_moves.is_empty() {
+            let kind = match key_event.code {
+                KeyCode::Char('q') => Some(ChessPieceKind::Queen),
+                KeyCode::Char('r') => Some(ChessPieceKind::Rook),
+                KeyCode::Char('b') => Some(ChessPieceKind::Bishop),
+                KeyCode::Char('n') => Some(ChessPieceKind::Knight),
+                _ => None,
+            };
+
+            match (kind, key_event.code) {
+                (Some(kind), _) => self.promote(kind),
+                (None, KeyCode::Esc) => self.cancel_selection(),
+                _ => {}
+            }
+
+            return true;
+        }
+
+        self.message = None;
+
+        match key_event.code {
+            KeyCode::Char('q') => return false,
+            KeyCode::Char('c') if key_event.modifiers.contains(event::KeyModifiers::CONTROL) => {
+                return false
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(0, 1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(0, -1),
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(-1, 0),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(1, 0),
+            KeyCode::Enter | KeyCode::Char(' ') if !status.is_over() => {
+                self.choose_tile(self.cursor)
+            }
+            KeyCode::Esc => self.cancel_selection(),
+            KeyCode::Char('f') => self.flipped = !self.flipped,
+            KeyCode::Char('u') => self.take_back(true),
+            KeyCode::Char('r') => self.take_back(false),
+            KeyCode::Char('d') if !status.is_over() => {
+                if let Err(err) = self.record.claim_draw() {
+                    self.message = Some(err.to_string());
+                }
+            }
+            _ => {}
+        }
+
+        true
+    }
+
+    // Handles a mouse click, which picks the tile clicked on as if the cursor had been moved there
+    fn handle_mouse(&mut self, mouse_event: MouseEvent, status: ChessGameStatus) {
+        if mouse_event.kind != MouseEventKind::Down(MouseButton::Left)
+            || !self.promotion_moves.is_empty()
+            || status.is_over()
+        {
+            return;
+        }
+
+        if let Some(point) = self.screen_to_point(mouse_event.column, mouse_event.row) {
+            self.message = None;
+            self.cursor = point;
+            self.choose_tile(point);
+        }
+    }
+
+    // Moves the cursor by the given number of tiles as seen on the screen, stopping at the edges of the board
+    fn move_cursor(&mut self, screen_dx: isize, screen_dy: isize) {
+        let (dx, dy) = match self.flipped {
+            true => (-screen_dx, -screen_dy),
+            false => (screen_dx, screen_dy),
+        };
+
+        let x = self.cursor.x().saturating_add_signed(dx).min(7);
+        let y = self.cursor.y().saturating_add_signed(dy).min(7);
+
+        self.cursor = ChessPoint::new(x, y);
+    }
+
+    // Picks a tile, either selecting the piece on it or moving the selected piece there
+    fn choose_tile(&mut self, point: ChessPoint) {
+        let game = self.record.current_gamestate();
+
+        // It is not the player's turn while the engine is thinking
+        if self.options.engine_color == Some(game.turn_color) {
+            return;
+        }
+
+        let own_piece = game
+            .board
+            .piece_at(&point)
+            .is_some_and(|piece| piece.color == game.turn_color);
+
+        let source = match self.selected {
+            Some(source) if source == point => {
+                self.selected = None;
+                return;
+            }
+            // Picking another of the player's pieces selects it instead, unless the move onto it is castling
+            Some(source) if !own_piece || game.is_castling(&ChessMove::new(source, point)) => {
+                source
+            }
+            _ => {
+                self.selected = match own_piece {
+                    true => Some(point),
+                    false => None,
+                };
+                return;
+            }
+        };
+
+        let promotion_moves: Vec<ChessMove> = game
+            .legal_moves_from(&source)
+            .into_iter()
+            .filter(|legal_move| *legal_move.destination() == point)
+            .collect();
+
+        if promotion_moves.len() > 1 {
+            self.promotion_moves = promotion_moves;
+            return;
+        }
+
+        // Illegal moves are still tried, so that the reason they cannot be made is shown
+        self.make_move(ChessMove::new(source, point));
+    }
+
+    fn promote(&mut self, kind: ChessPieceKind) {
+        let promotion_move = self
+            .promotion_moves
+            .iter()
+            .find(|promotion_move| promotion_move.promotion() == Some(kind))
+            .copied();
+
+        if let Some(promotion_move) = promotion_move {
+            self.make_move(promotion_move);
+        }
+    }
+
+    fn make_move(&mut self, move_to_make: ChessMove) {
+        self.selected = None;
+        self.promotion_moves.clear();
+
+        match self.record.push_move(&move_to_make) {
+            Ok(_) => press_clock(&mut self.clock, &mut self.record),
+            Err(err) => self.message = Some(err.to_string()),
+        }
+    }
+
+    fn cancel_selection(&mut self) {
+        self.selected = None;
+        self.promotion_moves.clear();
+    }
+
+    // Takes back the last move if undoing, or replays the next one if redoing
+    // Against the engine, the player's own move is taken back along with the engine's reply
+    fn take_back(&mut self, undoing: bool) {
+        // The clock only runs forwards, so a timed game cannot be taken back
+        if self.clock.is_some() {
+            self.message = Some("Moves cannot be taken back in a timed game.".to_string());
+            return;
+        }
+
+        let step = |record: &mut ChessPgnGame| match undoing {
+            true => record.undo(),
+            false => record.redo(),
+        };
+
+        if step(&mut self.record).is_none() {
+            self.message = Some(match undoing {
+                true => "There are no moves to undo.".to_string(),
+                false => "There are no moves to redo.".to_string(),
+            });
+            return;
+        }
+
+        if self.options.engine_color == Some(self.record.current_gamestate().turn_color) {
+            step(&mut self.record);
+        }
+
+        self.record.result = ChessPgnResult::Unknown;
+        self.cancel_selection();
+    }
+
+    // Gets the board tile shown at the given screen position, if the position is on the board
+    fn screen_to_point(&self, column: u16, row: u16) -> Option<ChessPoint> {
+        if column < BOARD_LEFT || row < BOARD_TOP {
+            return None;
+        }
+
+        let screen_x = ((column - BOARD_LEFT) / TILE_WIDTH) as usize;
+        let screen_y = (row - BOARD_TOP) as usize;
+
+        if screen_x > 7 || screen_y > 7 {
+            return None;
+        }
+
+        Some(match self.flipped {
+            true => ChessPoint::new(7 - screen_x, screen_y),
+            false => ChessPoint::new(screen_x, 7 - screen_y),
+        })
+    }
+
+    // Draws the screen over what was drawn before, rather than clearing it first, so that redrawing does not flicker
+    fn draw(&mut self, status: ChessGameStatus) -> io::Result<()> {
+        queue!(
+            self.stdout,
+            ResetColor,
+            cursor::MoveTo(BOARD_LEFT, 0),
+            Print("LTChess")
+        )?;
+
+        self.draw_board()?;
+        self.draw_panel(status)?;
+
+        queue!(self.stdout, ResetColor)?;
+        self.stdout.flush()
+    }
+
+    fn draw_board(&mut self) -> io::Result<()> {
+        let game = self.record.current_gamestate();
+
+        let last_move = game.history().last().map(|record| record.performed_move);
+
+        let checked_king = match game.is_in_check() {
+            true => Some(match game.turn_color {
+                ChessPieceColor::White => game.white_king_position,
+                ChessPieceColor::Black => game.black_king_position,
+            }),
+            false => None,
+        };
+
+        let destinations: Vec<ChessPoint> = match self.selected {
+            Some(source) => game
+                .legal_moves_from(&source)
+                .iter()
+                .map(|legal_move| *legal_move.destination())
+                .collect(),
+            None => Vec::new(),
+        };
+
+        for screen_y in 0..8 {
+            let y = match self.flipped {
+                true => screen_y,
+                false => 7 - screen_y,
+            };
+
+            queue!(
+                self.stdout,
+                ResetColor,
+                cursor::MoveTo(BOARD_LEFT - 2, BOARD_TOP + screen_y as u16),
+                Print(y + 1)
+            )?;
+
+            for screen_x in 0..8 {
+                let x = match self.flipped {
+                    true => 7 - screen_x,
+                    false => screen_x,
+                };
+
+                let point = ChessPoint::new(x, y);
+                let piece = game.board.piece_at(&point);
+
+                // Highlights are layered, with the most important one shown when a tile has several
+                let background = if checked_king == Some(point) {
+                    CHECK_TILE
+                } else if self.selected == Some(point) {
+                    SELECTED_TILE
+                } else if destinations.contains(&point) {
+                    DESTINATION_TILE
+                } else if last_move.is_some_and(|last_move| {
+                    *last_move.source() == point || *last_move.destination() == point
+                }) {
+                    LAST_MOVE_TILE
+                } else if (x + y) % 2 == 0 {
+                    DARK_TILE
+                } else {
+                    LIGHT_TILE
+                };
+
+                let (left, right) = match self.cursor == point {
+                    true => ('[', ']'),
+                    false => (' ', ' '),
+                };
+
+                // Empty tiles which the selected piece can move to are marked, so they stand out without colors
+                let middle = match piece {
+                    Some(piece) => piece.get_piece_char(),
+                    None if destinations.contains(&point) => '\u{2022}',
+                    None => ' ',
+                };
+
+                queue!(
+                    self.stdout,
+                    SetBackgroundColor(background),
+                    SetForegroundColor(Color::Black),
+                    Print(format!("{}{}{}", left, middle, right))
+                )?;
+            }
+        }
+
+        let files = match self.flipped {
+            true => "hgfedcba",
+            false => "abcdefgh",
+        };
+
+        queue!(
+            self.stdout,
+            ResetColor,
+            cursor::MoveTo(BOARD_LEFT, BOARD_TOP + 8)
+        )?;
+
+        for file in files.chars() {
+            queue!(self.stdout, Print(format!(" {} ", file)))?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_panel(&mut self, status: ChessGameStatus) -> io::Result<()> {
+        let game = self.record.current_gamestate();
+        let mut lines: Vec<(String, Color)> = Vec::new();
+
+        if let Some(clock) = &self.clock {
+            for color in [ChessPieceColor::White, ChessPieceColor::Black] {
+                lines.push((
+                    format!(
+                        "{}  {}",
+                        color_name(color),
+                        format_clock_time(clock.time_left(color, Instant::now()))
+                    ),
+                    Color::Reset,
+                ));
+            }
+
+            lines.push((String::new(), Color::Reset));
+        }
+
+        match game_over_message(status, game) {
+            Some(message) => lines.push((message, Color::Yellow)),
+            None if self.options.engine_color == Some(game.turn_color) => {
+                lines.push(("The engine is thinking...".to_string(), Color::Reset))
+            }
+            None => {
+                lines.push((
+                    format!(
+                        "It is {}'s turn.",
+                        color_name(game.turn_color).to_lowercase()
+                    ),
+                    Color::Reset,
+                ));
+
+                if game.is_in_check() {
+                    lines.push((
+                        format!("{} is in check.", color_name(game.turn_color)),
+                        Color::Red,
+                    ));
+                }
+
+                if game.claimable_draw().is_some() {
+                    lines.push((
+                        "A draw may be claimed; press d to claim it.".to_string(),
+                        Color::Reset,
+                    ));
+                }
+            }
+        }
+
+        if !self.promotion_moves.is_empty() {
+            lines.push((
+                "Promote to: (q)ueen, (r)ook, (b)ishop or k(n)ight".to_string(),
+                Color::Yellow,
+            ));
+        }
+
+        if let Some(message) = &self.message {
+            lines.push((message.clone(), Color::Red));
+        }
+
+        lines.push((String::new(), Color::Reset));
+        lines.push(("Moves".to_string(), Color::Reset));
+
+        for move_line in move_list_lines(&self.record) {
+            lines.push((move_line, Color::Reset));
+        }
+
+        lines.push((String::new(), Color::Reset));
+
+        // Each player's captures are the pieces of the other color which have left the board
+        for color in [ChessPieceColor::White, ChessPieceColor::Black] {
+            let captured: String = game
+                .history()
+                .iter()
+                .filter_map(|record| record.captured_piece)
+                .filter(|piece| piece.color == opponent_color(color))
+                .map(|piece| ChessPiece::get_piece_char(&piece))
+                .collect();
+
+            lines.push((
+                format!("{} captured: {}", color_name(color), captured),
+                Color::Reset,
+            ));
+        }
+
+        lines.push((String::new(), Color::Reset));
+        lines.push((
+            "Arrows/hjkl/click: pick  Enter: move  Esc: deselect".to_string(),
+            Color::DarkGrey,
+        ));
+        lines.push((
+            "u/r: undo/redo  d: claim draw  f: flip  q: quit".to_string(),
+            Color::DarkGrey,
+        ));
+
+        // The rest of each row is cleared, along with the rows below the panel, to remove what was drawn there before
+        let (_, screen_rows) = terminal::size()?;
+        let line_count = lines.len();
+
+        for (row, (line, color)) in lines.into_iter().enumerate() {
+            queue!(
+                self.stdout,
+                cursor::MoveTo(PANEL_LEFT, row as u16),
+                SetForegroundColor(color),
+                Print(line),
+                ResetColor,
+                terminal::Clear(terminal::ClearType::UntilNewLine)
+            )?;
+        }
+
+        for row in line_count as u16..screen_rows {
+            queue!(
+                self.stdout,
+                cursor::MoveTo(PANEL_LEFT, row),
+                terminal::Clear(terminal::ClearType::UntilNewLine)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// Lays out the moves of a game in SAN, one numbered pair of moves per line, keeping only the latest lines
+fn move_list_lines(record: &ChessPgnGame) -> Vec<String> {
+    let starting_gamestate = record.starting_gamestate();
+    let mut move_number = starting_gamestate.fullmove_clock;
+    let mut san_moves = record.san_moves().into_iter();
+    let mut lines = Vec::new();
+
+    // A game which starts with Black to move has no White move on its first line
+    if starting_gamestate.turn_color == ChessPieceColor::Black {
+        if let Some(black_move) = san_moves.next() {
+            lines.push(format!("{:>3}. ...     {}", move_number, black_move));
+            move_number += 1;
+        }
+    }
+
+    while let Some(white_move) = san_moves.next() {
+        lines.push(format!(
+            "{:>3}. {:<8}{}",
+            move_number,
+            white_move,
+            san_moves.next().unwrap_or_default()
+        ));
+        move_number += 1;
+    }
+
+    let hidden_lines = lines.len().saturating_sub(MOVE_LIST_ROWS);
+    lines.split_off(hidden_lines)
+}
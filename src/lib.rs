@@ -0,0 +1,91 @@
+//! LTChess is a basic chess environment: a rules engine which validates and performs moves,
+//! detects the end of the game, and reads and writes positions and games in FEN, SAN and PGN.
+//!
+//! The central type is [`ChessGamestate`], which holds the board and every other piece of
+//! state needed to decide whether a [`ChessMove`] is legal:
+//!
+//! ```
+//! use ltchess::{ChessGameStatus, ChessGamestate, ChessMove};
+//!
+//! let mut game = ChessGamestate::new();
+//!
+//! for encoded_move in ["f2 f3", "e7 e5", "g2 g4"] {
+//!     game.perform_move(&encoded_move.parse().unwrap()).unwrap();
+//! }
+//!
+//! let mate = ChessMove::from_san("Qh4#", &game).unwrap();
+//! game.perform_move(&mate).unwrap();
+//!
+//! assert!(matches!(game.status(), ChessGameStatus::Checkmate(_)));
+//! ```
+//!
+//! Every type in the public API is re-exported from the crate root; the modules themselves are private.
+
+mod chess_960;
+mod chess_attacks;
+mod chess_board;
+mod chess_clock;
+mod chess_engine;
+mod chess_error;
+mod chess_evaluation;
+mod chess_fen;
+mod chess_game_status;
+mod chess_gamestate;
+mod chess_move;
+mod chess_move_record;
+mod chess_opening_book;
+mod chess_parse_error;
+mod chess_perft;
+mod chess_pgn;
+mod chess_piece;
+mod chess_point;
+mod chess_san;
+mod chess_search_limits;
+mod chess_search_result;
+mod chess_syzygy_table;
+mod chess_tablebase;
+mod chess_time_control;
+mod chess_transposition_table;
+mod chess_uci;
+mod chess_xboard;
+mod chess_zobrist;
+
+pub use chess_board::ChessBoard;
+pub use chess_board::ChessPieceMut;
+pub use chess_clock::ChessClock;
+pub use chess_engine::ChessEngine;
+pub use chess_error::ChessError;
+pub use chess_fen::ChessFenError;
+pub use chess_fen::STARTING_FEN;
+pub use chess_game_status::ChessDrawClaim;
+pub use chess_game_status::ChessGameStatus;
+pub use chess_gamestate::ChessGamestate;
+pub use chess_move::ChessMove;
+pub use chess_move::MovePatternValidity;
+pub use chess_move_record::ChessMoveRecord;
+pub use chess_opening_book::ChessBookError;
+pub use chess_opening_book::ChessBookMove;
+pub use chess_opening_book::ChessOpeningBook;
+pub use chess_parse_error::ChessParseError;
+pub use chess_pgn::ChessPgnError;
+pub use chess_pgn::ChessPgnGame;
+pub use chess_pgn::ChessPgnResult;
+pub use chess_piece::ChessPiece;
+pub use chess_piece::ChessPieceColor;
+pub use chess_piece::ChessPieceKind;
+pub use chess_point::ChessPoint;
+pub use chess_san::ChessSanError;
+pub use chess_search_limits::ChessSearchLimits;
+pub use chess_search_result::ChessSearchResult;
+pub use chess_tablebase::ChessTablebase;
+pub use chess_tablebase::ChessTablebaseError;
+pub use chess_tablebase::ChessWdl;
+pub use chess_time_control::ChessClockBonus;
+pub use chess_time_control::ChessTimeControl;
+pub use chess_time_control::ChessTimeControlError;
+pub use chess_time_control::ChessTimeControlStage;
+pub use chess_transposition_table::ChessBound;
+pub use chess_transposition_table::ChessTranspositionEntry;
+pub use chess_transposition_table::ChessTranspositionTable;
+pub use chess_uci::ChessUci;
+pub use chess_xboard::ChessXboard;
@@ -0,0 +1,48 @@
+use ltchess::ChessBoard;
+use ltchess::ChessPieceColor;
+use ltchess::ChessPieceKind;
+use ltchess::ChessPoint;
+
+#[test]
+fn reads_pieces_by_tile() {
+    let board = ChessBoard::new();
+
+    let king = board.pieces()[0][4].unwrap();
+    assert_eq!(king.kind, ChessPieceKind::King);
+    assert_eq!(king.color, ChessPieceColor::White);
+
+    assert!(board.pieces()[4].iter().all(Option::is_none));
+    assert_eq!(
+        board.piece_at(&ChessPoint::new(3, 7)).unwrap().kind,
+        ChessPieceKind::Queen
+    );
+}
+
+#[test]
+fn changes_pieces_in_place() {
+    let mut board = ChessBoard::new();
+    let e2 = ChessPoint::new(4, 1);
+
+    assert!(board.piece_at_mut(&ChessPoint::new(4, 3)).is_none());
+
+    if let Some(mut piece) = board.piece_at_mut(&e2) {
+        piece.increment_move_count();
+        piece.kind = ChessPieceKind::Queen;
+    }
+
+    let piece = board.piece_at(&e2).unwrap();
+    assert_eq!(piece.kind, ChessPieceKind::Queen);
+    assert_eq!(piece.move_count, 1);
+
+    // The bitboards follow the changed piece, as they do for set_piece()
+    let e2_bit = 1 << e2.index();
+    assert_eq!(
+        board.pieces_bitboard(ChessPieceKind::Queen, ChessPieceColor::White),
+        e2_bit | 1 << ChessPoint::new(3, 0).index()
+    );
+    assert_eq!(board.kind_bitboard(ChessPieceKind::Pawn) & e2_bit, 0);
+    assert_eq!(
+        board.color_bitboard(ChessPieceColor::White).count_ones(),
+        16
+    );
+}
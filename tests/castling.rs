@@ -0,0 +1,37 @@
+use ltchess::ChessError;
+use ltchess::ChessGamestate;
+use ltchess::ChessMove;
+
+#[test]
+fn castles_on_both_sides() {
+    let mut gamestate = ChessGamestate::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+    gamestate.perform_move(&"e1 g1".parse().unwrap()).unwrap();
+    gamestate.perform_move(&"e8 c8".parse().unwrap()).unwrap();
+
+    assert_eq!(gamestate.to_fen(), "2kr3r/8/8/8/8/8/8/R4RK1 w - - 2 2");
+}
+
+#[test]
+fn rejects_castling_with_a_promotion_piece() {
+    let mut gamestate = ChessGamestate::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+    for encoded_move in ["e1g1q", "e1c1n"] {
+        let castling_move: ChessMove = encoded_move.parse().unwrap();
+
+        assert_eq!(
+            gamestate.perform_move(&castling_move),
+            Err(ChessError::UnexpectedPromotionPiece(castling_move))
+        );
+    }
+
+    // Chess960 castling moves the King onto its Rook, which must not carry a promotion piece either
+    gamestate.chess960 = true;
+
+    let castling_move: ChessMove = "e1h1r".parse().unwrap();
+
+    assert_eq!(
+        gamestate.perform_move(&castling_move),
+        Err(ChessError::UnexpectedPromotionPiece(castling_move))
+    );
+}
@@ -0,0 +1,176 @@
+// Perft counts for Chess960 positions, from https://www.chessprogramming.org/Chess960_Perft_Results
+
+use ltchess::ChessError;
+use ltchess::ChessGamestate;
+use ltchess::ChessMove;
+use ltchess::ChessPgnGame;
+use ltchess::STARTING_FEN;
+
+use std::collections::HashSet;
+
+fn perform(gamestate: &mut ChessGamestate, encoded_move: &str) {
+    gamestate
+        .perform_move(&encoded_move.parse().unwrap())
+        .unwrap();
+}
+
+#[test]
+fn numbers_starting_positions() {
+    assert_eq!(
+        ChessGamestate::new_chess960(518).unwrap().to_fen(),
+        STARTING_FEN
+    );
+    assert_eq!(
+        ChessGamestate::new_chess960(0).unwrap().to_fen(),
+        "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1"
+    );
+    assert_eq!(
+        ChessGamestate::new_chess960(959).unwrap().to_fen(),
+        "rkrnnqbb/pppppppp/8/8/8/8/PPPPPPPP/RKRNNQBB w KQkq - 0 1"
+    );
+    assert!(ChessGamestate::new_chess960(960).is_none());
+
+    let positions: HashSet<String> = (0..960)
+        .map(|index| ChessGamestate::new_chess960(index).unwrap().to_fen())
+        .collect();
+
+    assert_eq!(positions.len(), 960);
+}
+
+#[test]
+fn perft_chess960_positions() {
+    let positions: [(&str, [u64; 3]); 4] = [
+        (
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+            [21, 528, 12189],
+        ),
+        (
+            "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9",
+            [21, 807, 18002],
+        ),
+        (
+            "b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9",
+            [20, 479, 10471],
+        ),
+        (
+            "qnbnr1kr/ppp1b1pp/4p3/3p1p2/8/2NPP3/PPP1BPPP/QNB1R1KR w HEhe - 1 9",
+            [29, 899, 26578],
+        ),
+    ];
+
+    for (fen, expected_counts) in positions {
+        let gamestate = ChessGamestate::from_fen(fen).unwrap();
+
+        assert!(gamestate.chess960);
+
+        for (depth, expected_count) in (1..).zip(expected_counts) {
+            assert_eq!(
+                gamestate.perft(depth),
+                expected_count,
+                "perft({}) of {}",
+                depth,
+                fen
+            );
+        }
+    }
+}
+
+#[test]
+fn castles_by_moving_king_onto_rook() {
+    let fen = "r3k2r/8/8/8/8/8/8/1R3KR1 w GBha - 0 1";
+    let mut gamestate = ChessGamestate::from_fen(fen).unwrap();
+
+    perform(&mut gamestate, "f1 g1");
+    assert_eq!(
+        gamestate.to_shredder_fen(),
+        "r3k2r/8/8/8/8/8/8/1R3RK1 b ha - 1 1"
+    );
+
+    perform(&mut gamestate, "e8 a8");
+    assert_eq!(
+        gamestate.to_shredder_fen(),
+        "2kr3r/8/8/8/8/8/8/1R3RK1 w - - 2 2"
+    );
+
+    gamestate.unmake_move();
+    gamestate.unmake_move();
+    assert_eq!(gamestate.to_shredder_fen(), fen);
+}
+
+#[test]
+fn rejects_blocked_castling() {
+    let mut gamestate = ChessGamestate::from_fen("4k3/8/8/8/8/8/8/RN2K2R w KQ - 0 1").unwrap();
+
+    // The position could also be a standard chess position, so the Chess960 rules must be chosen explicitly
+    assert!(!gamestate.chess960);
+    gamestate.chess960 = true;
+
+    assert!(matches!(
+        gamestate.perform_move(&"e1 a1".parse().unwrap()),
+        Err(ChessError::MoveCollisionOccurs(..))
+    ));
+
+    // The usual two-tile King move is not castling under the Chess960 rules
+    assert!(gamestate.perform_move(&"e1 g1".parse().unwrap()).is_err());
+    assert!(gamestate.perform_move(&"e1 h1".parse().unwrap()).is_ok());
+}
+
+#[test]
+fn reads_and_writes_castling_rights() {
+    let fen = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9";
+    let gamestate = ChessGamestate::from_fen(fen).unwrap();
+
+    assert_eq!(gamestate.to_shredder_fen(), fen);
+    assert_eq!(
+        gamestate.to_fen(),
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9"
+    );
+    assert_eq!(
+        ChessGamestate::from_fen(&gamestate.to_fen())
+            .unwrap()
+            .to_shredder_fen(),
+        fen
+    );
+
+    // X-FEN names the Rook's file when a Rook further out would otherwise be meant
+    let inner_rook_fen = "4k3/8/8/8/8/8/8/R1R1K3 w C - 0 1";
+    let gamestate = ChessGamestate::from_fen(inner_rook_fen).unwrap();
+
+    assert_eq!(gamestate.white_queenside_rook_file, 2);
+    assert_eq!(gamestate.to_fen(), inner_rook_fen);
+
+    // Standard positions are still written the usual way
+    assert_eq!(
+        ChessGamestate::new().to_shredder_fen(),
+        STARTING_FEN.replace("KQkq", "HAha")
+    );
+    assert!(!ChessGamestate::from_fen(STARTING_FEN).unwrap().chess960);
+}
+
+#[test]
+fn castles_in_san() {
+    let mut gamestate = ChessGamestate::from_fen("r3k2r/8/8/8/8/8/8/1R3KR1 w GBha - 0 1").unwrap();
+
+    let kingside_castle = ChessMove::from_san("O-O", &gamestate).unwrap();
+    assert_eq!(kingside_castle, "f1 g1".parse().unwrap());
+    assert_eq!(kingside_castle.to_san(&gamestate).unwrap(), "O-O");
+
+    gamestate.perform_move(&kingside_castle).unwrap();
+
+    let queenside_castle = ChessMove::from_san("O-O-O", &gamestate).unwrap();
+    assert_eq!(queenside_castle, "e8 a8".parse().unwrap());
+}
+
+#[test]
+fn keeps_variant_in_pgn() {
+    let mut game = ChessPgnGame::from_gamestate(ChessGamestate::new_chess960(518).unwrap());
+    game.push_move(&"e2 e4".parse().unwrap()).unwrap();
+
+    let pgn = game.to_pgn();
+    assert!(pgn.contains("[Variant \"Chess960\"]"));
+    assert!(pgn.contains(&format!("[FEN \"{}\"]", STARTING_FEN)));
+
+    let parsed_game = ChessPgnGame::parse(&pgn).unwrap();
+    assert!(parsed_game.starting_gamestate().chess960);
+    assert!(parsed_game.current_gamestate().chess960);
+}
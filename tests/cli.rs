@@ -0,0 +1,26 @@
+use std::process::Command;
+
+// Runs the binary with the given arguments, returning whether it succeeded and the first line it printed as an error
+fn run_binary(args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_ltchess"))
+        .args(args)
+        .output()
+        .unwrap();
+
+    let error_text = String::from_utf8(output.stderr).unwrap();
+
+    (
+        output.status.success(),
+        error_text.lines().next().unwrap_or_default().to_string(),
+    )
+}
+
+#[test]
+fn rejects_invalid_move_times() {
+    for time in ["1e30", "-1", "0", "NaN", "inf"] {
+        assert_eq!(
+            run_binary(&["--time", time]),
+            (false, format!("\"{}\" is not a valid time per move.", time))
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use ltchess::ChessClock;
+use ltchess::ChessClockBonus;
+use ltchess::ChessGameStatus;
+use ltchess::ChessGamestate;
+use ltchess::ChessPieceColor;
+use ltchess::ChessTimeControl;
+use ltchess::ChessTimeControlError;
+use ltchess::ChessTimeControlStage;
+
+use std::time::Duration;
+use std::time::Instant;
+
+fn seconds(seconds: u64) -> Duration {
+    Duration::from_secs(seconds)
+}
+
+// Creates a running clock for the given time control, on which White moves first, along with the time it was started
+fn started_clock(time_control: &str) -> (ChessClock, Instant) {
+    let start = Instant::now();
+    let mut clock = ChessClock::new(time_control.parse().unwrap(), ChessPieceColor::White);
+    clock.start(start);
+
+    (clock, start)
+}
+
+#[test]
+fn parses_time_controls() {
+    let time_control: ChessTimeControl = "40/90+30,30+30".parse().unwrap();
+
+    assert_eq!(
+        time_control.stages(),
+        [
+            ChessTimeControlStage {
+                moves: Some(40),
+                base_time: seconds(90 * 60),
+                bonus: ChessClockBonus::Increment(seconds(30)),
+            },
+            ChessTimeControlStage {
+                moves: None,
+                base_time: seconds(30 * 60),
+                bonus: ChessClockBonus::Increment(seconds(30)),
+            },
+        ]
+    );
+
+    assert_eq!(
+        "5".parse::<ChessTimeControl>().unwrap(),
+        ChessTimeControl::sudden_death(seconds(300))
+    );
+    assert_eq!(
+        "0.5+1".parse::<ChessTimeControl>().unwrap(),
+        ChessTimeControl::fischer(seconds(30), seconds(1))
+    );
+    assert_eq!(
+        "5d3".parse::<ChessTimeControl>().unwrap().stages()[0].bonus,
+        ChessClockBonus::Delay(seconds(3))
+    );
+    assert_eq!(
+        "5b3".parse::<ChessTimeControl>().unwrap().stages()[0].bonus,
+        ChessClockBonus::Bronstein(seconds(3))
+    );
+
+    assert_eq!(
+        "".parse::<ChessTimeControl>(),
+        Err(ChessTimeControlError::Empty)
+    );
+    assert_eq!(
+        "40/".parse::<ChessTimeControl>(),
+        Err(ChessTimeControlError::InvalidStage("40/".to_string()))
+    );
+    assert_eq!(
+        "5+x".parse::<ChessTimeControl>(),
+        Err(ChessTimeControlError::InvalidStage("5+x".to_string()))
+    );
+    assert_eq!(
+        "90,30".parse::<ChessTimeControl>(),
+        Err(ChessTimeControlError::UnreachableStage(1))
+    );
+}
+
+#[test]
+fn rejects_stages_without_moves() {
+    let stage = |moves| ChessTimeControlStage {
+        moves,
+        base_time: seconds(60),
+        bonus: ChessClockBonus::None,
+    };
+
+    assert_eq!(
+        ChessTimeControl::new(vec![stage(Some(40)), stage(Some(0))]),
+        Err(ChessTimeControlError::ZeroMoveStage(2))
+    );
+    assert!(ChessTimeControl::new(vec![stage(Some(40)), stage(None)]).is_ok());
+}
+
+#[test]
+fn adds_fischer_increment() {
+    let (mut clock, start) = started_clock("1+2");
+
+    assert_eq!(
+        clock.time_left(ChessPieceColor::White, start + seconds(10)),
+        seconds(50)
+    );
+    assert!(clock.press(start + seconds(10)));
+
+    assert_eq!(clock.turn_color(), ChessPieceColor::Black);
+    assert_eq!(
+        clock.time_left(ChessPieceColor::White, start + seconds(15)),
+        seconds(52)
+    );
+    assert_eq!(
+        clock.time_left(ChessPieceColor::Black, start + seconds(15)),
+        seconds(55)
+    );
+}
+
+#[test]
+fn applies_delays() {
+    // Bronstein delay gives back the time used, up to the delay
+    let (mut clock, start) = started_clock("1b5");
+
+    clock.press(start + seconds(3));
+    clock.press(start + seconds(4));
+    clock.press(start + seconds(12));
+
+    assert_eq!(
+        clock.time_left(ChessPieceColor::White, start + seconds(12)),
+        seconds(57)
+    );
+
+    // A simple delay passes before the clock starts counting down
+    let (mut clock, start) = started_clock("1d5");
+
+    assert_eq!(
+        clock.time_left(ChessPieceColor::White, start + seconds(3)),
+        seconds(60)
+    );
+    assert_eq!(
+        clock.time_left(ChessPieceColor::White, start + seconds(8)),
+        seconds(57)
+    );
+
+    clock.press(start + seconds(8));
+
+    assert_eq!(
+        clock.time_left(ChessPieceColor::White, start + seconds(8)),
+        seconds(57)
+    );
+}
+
+#[test]
+fn moves_through_stages() {
+    let (mut clock, start) = started_clock("2/1,1");
+
+    assert_eq!(clock.moves_to_go(ChessPieceColor::White), Some(2));
+
+    clock.press(start + seconds(10));
+    clock.press(start + seconds(10));
+
+    assert_eq!(clock.moves_to_go(ChessPieceColor::White), Some(1));
+
+    clock.press(start + seconds(20));
+
+    // The second stage's time is added once the first stage's moves are made
+    assert_eq!(clock.moves_to_go(ChessPieceColor::White), None);
+    assert_eq!(
+        clock.time_left(ChessPieceColor::White, start + seconds(20)),
+        seconds(100)
+    );
+}
+
+#[test]
+fn loses_on_time() {
+    let gamestate = ChessGamestate::new();
+    let (mut clock, start) = started_clock("1");
+
+    assert_eq!(
+        clock.status(&gamestate, start + seconds(59)),
+        ChessGameStatus::Ongoing
+    );
+    assert_eq!(
+        clock.status(&gamestate, start + seconds(61)),
+        ChessGameStatus::Timeout(ChessPieceColor::Black)
+    );
+
+    // The clock stops once a flag falls
+    assert!(!clock.press(start + seconds(62)));
+    assert!(!clock.is_running());
+    assert_eq!(
+        clock.time_left(ChessPieceColor::White, start + seconds(62)),
+        Duration::ZERO
+    );
+}
+
+#[test]
+fn draws_on_time_without_mating_material() {
+    // Black only has a King, so White running out of time is a draw
+    let gamestate = ChessGamestate::from_fen("8/8/8/8/8/k7/8/KQ6 w - - 0 1").unwrap();
+    let (mut clock, start) = started_clock("1");
+
+    assert_eq!(
+        clock.status(&gamestate, start + seconds(61)),
+        ChessGameStatus::TimeoutVsInsufficientMaterial
+    );
+}
@@ -0,0 +1,30 @@
+use std::io::Cursor;
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+// An output which can still be read after it has been given to a protocol front end
+#[derive(Clone, Default)]
+pub struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+// Runs the given commands through a protocol front end, returning every line it sent back
+pub fn run_commands(
+    commands: &str,
+    run_front_end: impl FnOnce(SharedOutput, Cursor<&str>),
+) -> Vec<String> {
+    let output = SharedOutput::default();
+    run_front_end(output.clone(), Cursor::new(commands));
+
+    let sent_text = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+    sent_text.lines().map(str::to_string).collect()
+}
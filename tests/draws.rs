@@ -0,0 +1,113 @@
+use ltchess::ChessDrawClaim;
+use ltchess::ChessError;
+use ltchess::ChessGameStatus;
+use ltchess::ChessGamestate;
+use ltchess::ChessPieceColor;
+
+fn perform(gamestate: &mut ChessGamestate, encoded_move: &str) {
+    gamestate
+        .perform_move(&encoded_move.parse().unwrap())
+        .unwrap();
+}
+
+// Moves both Knights out and back, which repeats the position once
+fn shuffle_knights(gamestate: &mut ChessGamestate) {
+    for encoded_move in ["g1 f3", "g8 f6", "f3 g1", "f6 g8"] {
+        perform(gamestate, encoded_move);
+    }
+}
+
+#[test]
+fn claims_threefold_repetition() {
+    let mut gamestate = ChessGamestate::new();
+
+    shuffle_knights(&mut gamestate);
+    assert_eq!(gamestate.repetition_count(), 2);
+    assert_eq!(gamestate.claimable_draw(), None);
+    assert!(matches!(
+        gamestate.claim_draw(),
+        Err(ChessError::NoDrawToClaim)
+    ));
+
+    shuffle_knights(&mut gamestate);
+    assert_eq!(gamestate.repetition_count(), 3);
+    assert_eq!(
+        gamestate.claimable_draw(),
+        Some(ChessDrawClaim::ThreefoldRepetition)
+    );
+
+    // The game goes on until the draw is claimed
+    assert_eq!(gamestate.status(), ChessGameStatus::Ongoing);
+    assert_eq!(
+        gamestate.claim_draw().unwrap(),
+        ChessDrawClaim::ThreefoldRepetition
+    );
+    assert_eq!(
+        gamestate.status(),
+        ChessGameStatus::DrawClaimed(ChessDrawClaim::ThreefoldRepetition)
+    );
+    assert!(gamestate.perform_move(&"e2 e4".parse().unwrap()).is_err());
+}
+
+#[test]
+fn draws_on_fivefold_repetition() {
+    let mut gamestate = ChessGamestate::new();
+
+    for _ in 0..3 {
+        shuffle_knights(&mut gamestate);
+    }
+
+    assert_eq!(gamestate.repetition_count(), 4);
+    assert_eq!(gamestate.status(), ChessGameStatus::Ongoing);
+
+    shuffle_knights(&mut gamestate);
+    assert_eq!(gamestate.repetition_count(), 5);
+    assert_eq!(gamestate.status(), ChessGameStatus::FivefoldRepetition);
+
+    // No claim is needed, and no more moves can be made
+    assert!(matches!(
+        gamestate.perform_move(&"e2 e4".parse().unwrap()),
+        Err(ChessError::GameOver(ChessGameStatus::FivefoldRepetition))
+    ));
+
+    // Taking a move back undoes the repetition
+    gamestate.undo();
+    assert_eq!(gamestate.status(), ChessGameStatus::Ongoing);
+}
+
+#[test]
+fn does_not_count_positions_before_a_pawn_move() {
+    let mut gamestate = ChessGamestate::new();
+
+    shuffle_knights(&mut gamestate);
+    shuffle_knights(&mut gamestate);
+    perform(&mut gamestate, "e2 e4");
+    perform(&mut gamestate, "e7 e5");
+
+    assert_eq!(gamestate.repetition_count(), 1);
+}
+
+#[test]
+fn applies_the_fifty_and_seventy_five_move_rules() {
+    let mut gamestate = ChessGamestate::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 99 80").unwrap();
+    assert_eq!(gamestate.claimable_draw(), None);
+
+    perform(&mut gamestate, "a1 a2");
+    assert_eq!(
+        gamestate.claimable_draw(),
+        Some(ChessDrawClaim::FiftyMoveRule)
+    );
+    assert_eq!(gamestate.status(), ChessGameStatus::Ongoing);
+
+    let mut gamestate = ChessGamestate::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 149 80").unwrap();
+    perform(&mut gamestate, "a1 a2");
+    assert_eq!(gamestate.status(), ChessGameStatus::SeventyFiveMoveRule);
+
+    // A checkmate on the last move still counts, as the game ends as soon as it is made
+    let mut gamestate = ChessGamestate::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 149 80").unwrap();
+    perform(&mut gamestate, "a1 a8");
+    assert_eq!(
+        gamestate.status(),
+        ChessGameStatus::Checkmate(ChessPieceColor::White)
+    );
+}
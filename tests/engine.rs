@@ -0,0 +1,58 @@
+use ltchess::ChessEngine;
+use ltchess::ChessGamestate;
+use ltchess::ChessSearchLimits;
+
+use std::time::Duration;
+
+fn best_move(fen: &str, limits: &ChessSearchLimits) -> String {
+    let gamestate = ChessGamestate::from_fen(fen).unwrap();
+    let result = ChessEngine::new().search(&gamestate, limits);
+
+    result.best_move.unwrap().to_string()
+}
+
+#[test]
+fn finds_mate_in_one() {
+    let fen = "r1bqkbnr/pppp1ppp/2n5/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 0 1";
+    let gamestate = ChessGamestate::from_fen(fen).unwrap();
+    let result = ChessEngine::new().search(&gamestate, &ChessSearchLimits::depth(3));
+
+    assert_eq!(result.best_move.unwrap().to_string(), "h5f7");
+    assert_eq!(result.mate_in(), Some(1));
+}
+
+#[test]
+fn finds_mate_in_two() {
+    // 1. Rd8+ Rxd8 2. Rxd8#
+    let fen = "r5k1/5ppp/8/8/8/8/3R1PPP/3R2K1 w - - 0 1";
+    let gamestate = ChessGamestate::from_fen(fen).unwrap();
+    let result = ChessEngine::new().search(&gamestate, &ChessSearchLimits::depth(4));
+
+    assert_eq!(result.mate_in(), Some(2));
+    assert_eq!(result.best_move.unwrap().to_string(), "d2d8");
+}
+
+#[test]
+fn captures_a_hanging_queen() {
+    let fen = "rnb1kbnr/pppp1ppp/8/4p1q1/3P4/2N5/PPP1PPPP/R1BQKBNR w KQkq - 0 1";
+
+    assert_eq!(best_move(fen, &ChessSearchLimits::depth(3)), "c1g5");
+}
+
+#[test]
+fn returns_a_move_when_the_time_runs_out() {
+    let limits = ChessSearchLimits::move_time(Duration::from_millis(50));
+    let gamestate = ChessGamestate::new();
+    let result = ChessEngine::new().search(&gamestate, &limits);
+
+    assert!(gamestate.legal_moves().contains(&result.best_move.unwrap()));
+}
+
+#[test]
+fn has_no_move_when_checkmated() {
+    let fen = "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3";
+    let gamestate = ChessGamestate::from_fen(fen).unwrap();
+    let result = ChessEngine::new().search(&gamestate, &ChessSearchLimits::depth(2));
+
+    assert_eq!(result.best_move, None);
+}
@@ -0,0 +1,136 @@
+use ltchess::ChessFenError;
+use ltchess::ChessGamestate;
+use ltchess::STARTING_FEN;
+
+fn fen_error(fen: &str) -> Option<ChessFenError> {
+    ChessGamestate::from_fen(fen).err()
+}
+
+#[test]
+fn round_trips_positions() {
+    let fens = [
+        STARTING_FEN,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    ];
+
+    for fen in fens {
+        assert_eq!(ChessGamestate::from_fen(fen).unwrap().to_fen(), fen);
+    }
+
+    // The move clocks may be left out
+    assert_eq!(
+        ChessGamestate::from_fen("4k3/8/8/8/8/8/8/4K3 b - -")
+            .unwrap()
+            .to_fen(),
+        "4k3/8/8/8/8/8/8/4K3 b - - 0 1"
+    );
+}
+
+#[test]
+fn rejects_malformed_fields() {
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 w -"),
+        Some(ChessFenError::WrongFieldCount)
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/4K3 w - - 0 1"),
+        Some(ChessFenError::WrongRankCount)
+    );
+    assert_eq!(
+        fen_error("4k4/8/8/8/8/8/8/4K3 w - - 0 1"),
+        Some(ChessFenError::WrongFileCount)
+    );
+    assert_eq!(
+        fen_error("4x3/8/8/8/8/8/8/4K3 w - - 0 1"),
+        Some(ChessFenError::InvalidPieceChar)
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 x - - 0 1"),
+        Some(ChessFenError::InvalidTurnColor)
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 w - - x 1"),
+        Some(ChessFenError::InvalidHalfmoveClock)
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 w - - 0 0"),
+        Some(ChessFenError::InvalidFullmoveClock)
+    );
+}
+
+#[test]
+fn rejects_impossible_positions() {
+    // Each color needs exactly one King
+    assert_eq!(
+        fen_error("8/8/8/8/8/8/8/4K3 w - - 0 1"),
+        Some(ChessFenError::InvalidKingCount)
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/3KK3 w - - 0 1"),
+        Some(ChessFenError::InvalidKingCount)
+    );
+
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/P3K3 w - - 0 1"),
+        Some(ChessFenError::InvalidPawnRank)
+    );
+    assert_eq!(
+        fen_error("p3k3/8/8/8/8/8/8/4K3 w - - 0 1"),
+        Some(ChessFenError::InvalidPawnRank)
+    );
+
+    // Black cannot have left their King in check on White's turn
+    assert_eq!(
+        fen_error("4k3/4Q3/8/8/8/8/8/4K3 w - - 0 1"),
+        Some(ChessFenError::OpponentInCheck)
+    );
+    assert!(ChessGamestate::from_fen("4k3/4Q3/8/8/8/8/8/4K3 b - - 0 1").is_ok());
+}
+
+#[test]
+fn rejects_invalid_en_passant_tiles() {
+    // There is no Black Pawn in front of e6, so the tile was not left by a Pawn moving two tiles
+    assert_eq!(
+        fen_error("4k3/8/8/3PN3/8/8/8/4K3 w - e6 0 1"),
+        Some(ChessFenError::InvalidEnPassantTile)
+    );
+    // The tile the Pawn moved from must be empty
+    assert_eq!(
+        fen_error("4k3/4p3/8/3Pp3/8/8/8/4K3 w - e6 0 1"),
+        Some(ChessFenError::InvalidEnPassantTile)
+    );
+    // The en passant tile must be on the rank behind the Pawn
+    assert_eq!(
+        fen_error("4k3/8/8/3Pp3/8/8/8/4K3 w - e3 0 1"),
+        Some(ChessFenError::InvalidEnPassantTile)
+    );
+    assert!(ChessGamestate::from_fen("4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 1").is_ok());
+}
+
+#[test]
+fn rejects_castling_rights_without_pieces() {
+    // There are no Rooks to castle with
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 w KQ - 0 1"),
+        Some(ChessFenError::InvalidCastlingRights)
+    );
+    // The King is off its back rank
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/4K3/R6R w KQ - 0 1"),
+        Some(ChessFenError::InvalidCastlingRights)
+    );
+    // The named file has no Rook on it
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/R3K3 w G - 0 1"),
+        Some(ChessFenError::InvalidCastlingRights)
+    );
+    // Each right may only be given once
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/R3K2R w KK - 0 1"),
+        Some(ChessFenError::InvalidCastlingRights)
+    );
+
+    assert!(ChessGamestate::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").is_ok());
+}
@@ -0,0 +1,79 @@
+use ltchess::ChessGameStatus;
+use ltchess::ChessGamestate;
+use ltchess::ChessPieceColor;
+
+fn gamestate(fen: &str) -> ChessGamestate {
+    ChessGamestate::from_fen(fen).unwrap()
+}
+
+#[test]
+fn detects_insufficient_material() {
+    let dead_positions = [
+        // King against King
+        "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        // King and minor piece against King
+        "4k3/8/8/8/8/8/8/2B1K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/1N2K3 w - - 0 1",
+        // Bishops which are all on the same tile color
+        "1b2k3/8/8/8/8/8/8/2B1K3 w - - 0 1",
+        "4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1",
+    ];
+
+    for fen in dead_positions {
+        let gamestate = gamestate(fen);
+
+        assert!(gamestate.has_insufficient_material(), "{}", fen);
+        assert_eq!(
+            gamestate.status(),
+            ChessGameStatus::InsufficientMaterial,
+            "{}",
+            fen
+        );
+    }
+
+    let live_positions = [
+        "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/R3K3 w - - 0 1",
+        // Bishops on opposite tile colors
+        "2b1k3/8/8/8/8/8/8/2B1K3 w - - 0 1",
+        // Two Knights cannot force mate, but mate is still possible
+        "4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1",
+    ];
+
+    for fen in live_positions {
+        assert!(!gamestate(fen).has_insufficient_material(), "{}", fen);
+    }
+}
+
+#[test]
+fn checks_each_color_for_mating_material() {
+    // A lone Knight can only mate if the enemy has a piece to block their own King in with
+    let gamestate = gamestate("4k3/4p3/8/8/8/8/8/1N2K3 w - - 0 1");
+    assert!(gamestate.has_mating_material(ChessPieceColor::White));
+    assert!(gamestate.has_mating_material(ChessPieceColor::Black));
+
+    // An enemy Queen can always escape the blocking tile, so she does not help
+    let gamestate = self::gamestate("3qk3/8/8/8/8/8/8/1N2K3 w - - 0 1");
+    assert!(!gamestate.has_mating_material(ChessPieceColor::White));
+    assert!(gamestate.has_mating_material(ChessPieceColor::Black));
+
+    // A lone King can never give check
+    let gamestate = self::gamestate("4k3/8/8/8/8/8/8/Q3K3 w - - 0 1");
+    assert!(!gamestate.has_mating_material(ChessPieceColor::Black));
+}
+
+#[test]
+fn ends_the_game_when_material_runs_out() {
+    let mut gamestate = gamestate("4k3/8/8/8/8/8/8/1n2K2R b - - 0 1");
+
+    assert_eq!(gamestate.status(), ChessGameStatus::Ongoing);
+
+    // The Knight forks the King and Rook, and capturing the Rook leaves only a minor piece on the board
+    gamestate.perform_move(&"b1 d2".parse().unwrap()).unwrap();
+    gamestate.perform_move(&"h1 h2".parse().unwrap()).unwrap();
+    gamestate.perform_move(&"d2 f3".parse().unwrap()).unwrap();
+    gamestate.perform_move(&"e1 f2".parse().unwrap()).unwrap();
+    gamestate.perform_move(&"f3 h2".parse().unwrap()).unwrap();
+
+    assert_eq!(gamestate.status(), ChessGameStatus::InsufficientMaterial);
+}
@@ -0,0 +1,163 @@
+use ltchess::ChessBookError;
+use ltchess::ChessBookMove;
+use ltchess::ChessGamestate;
+use ltchess::ChessMove;
+use ltchess::ChessOpeningBook;
+
+// The Polyglot key of the starting position, from the Polyglot book format specification
+const STARTING_POSITION_KEY: u64 = 0x463b96181691fc9c;
+
+// Encodes a move like "e2e4" the way Polyglot books store it
+fn encode_move(coordinates: &str, promotion: u16) -> u16 {
+    let bytes = coordinates.as_bytes();
+    let file = |index: usize| (bytes[index] - b'a') as u16;
+    let rank = |index: usize| (bytes[index] - b'1') as u16;
+
+    file(2) | rank(3) << 3 | file(0) << 6 | rank(1) << 9 | promotion << 12
+}
+
+// Builds the contents of a book file from (key, move, weight) entries
+fn book_bytes(entries: &[(u64, u16, u16)]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+
+    for (key, encoded_move, weight) in entries {
+        bytes.extend_from_slice(&key.to_be_bytes());
+        bytes.extend_from_slice(&encoded_move.to_be_bytes());
+        bytes.extend_from_slice(&weight.to_be_bytes());
+        bytes.extend_from_slice(&0u32.to_be_bytes());
+    }
+
+    bytes
+}
+
+fn parse(coordinates: &str) -> ChessMove {
+    coordinates.parse().unwrap()
+}
+
+#[test]
+fn finds_the_moves_of_the_starting_position() {
+    let book = ChessOpeningBook::from_bytes(&book_bytes(&[
+        (1, encode_move("a2a3", 0), 5),
+        (STARTING_POSITION_KEY, encode_move("e2e4", 0), 10),
+        (STARTING_POSITION_KEY, encode_move("d2d4", 0), 30),
+        // Not legal in the starting position, so it must come from a different position with the same key
+        (STARTING_POSITION_KEY, encode_move("e2e5", 0), 50),
+    ]))
+    .unwrap();
+
+    let gamestate = ChessGamestate::new();
+
+    assert_eq!(book.len(), 4);
+    assert_eq!(
+        book.moves(&gamestate),
+        vec![
+            ChessBookMove {
+                book_move: parse("e2e4"),
+                weight: 10
+            },
+            ChessBookMove {
+                book_move: parse("d2d4"),
+                weight: 30
+            },
+        ]
+    );
+    assert_eq!(book.best_move(&gamestate), Some(parse("d2d4")));
+}
+
+#[test]
+fn picks_moves_in_proportion_to_their_weights() {
+    let book = ChessOpeningBook::from_bytes(&book_bytes(&[
+        (STARTING_POSITION_KEY, encode_move("e2e4", 0), 10),
+        (STARTING_POSITION_KEY, encode_move("c2c4", 0), 0),
+        (STARTING_POSITION_KEY, encode_move("d2d4", 0), 30),
+    ]))
+    .unwrap();
+
+    let gamestate = ChessGamestate::new();
+
+    assert_eq!(
+        book.weighted_random_move(&gamestate, 0),
+        Some(parse("e2e4"))
+    );
+    assert_eq!(
+        book.weighted_random_move(&gamestate, 9),
+        Some(parse("e2e4"))
+    );
+    assert_eq!(
+        book.weighted_random_move(&gamestate, 10),
+        Some(parse("d2d4"))
+    );
+    assert_eq!(
+        book.weighted_random_move(&gamestate, 39),
+        Some(parse("d2d4"))
+    );
+    assert_eq!(
+        book.weighted_random_move(&gamestate, 40),
+        Some(parse("e2e4"))
+    );
+}
+
+#[test]
+fn has_no_moves_for_positions_it_does_not_cover() {
+    let book = ChessOpeningBook::from_bytes(&book_bytes(&[(
+        STARTING_POSITION_KEY,
+        encode_move("e2e4", 0),
+        1,
+    )]))
+    .unwrap();
+
+    let mut gamestate = ChessGamestate::new();
+    gamestate.perform_move(&parse("g1f3")).unwrap();
+
+    assert!(book.moves(&gamestate).is_empty());
+    assert_eq!(book.best_move(&gamestate), None);
+    assert_eq!(book.weighted_random_move(&gamestate, 7), None);
+}
+
+#[test]
+fn decodes_castling_as_the_kings_move() {
+    let gamestate =
+        ChessGamestate::from_fen("r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1").unwrap();
+    let book = ChessOpeningBook::from_bytes(&book_bytes(&[
+        (gamestate.hash(), encode_move("e1h1", 0), 2),
+        (gamestate.hash(), encode_move("e1a1", 0), 1),
+    ]))
+    .unwrap();
+
+    let moves: Vec<ChessMove> = book
+        .moves(&gamestate)
+        .iter()
+        .map(|book_move| book_move.book_move)
+        .collect();
+
+    assert_eq!(moves, vec![parse("e1g1"), parse("e1c1")]);
+}
+
+#[test]
+fn decodes_promotions() {
+    let gamestate = ChessGamestate::from_fen("7k/P7/8/8/8/8/8/K7 w - - 0 1").unwrap();
+    let book = ChessOpeningBook::from_bytes(&book_bytes(&[
+        (gamestate.hash(), encode_move("a7a8", 4), 1),
+        (gamestate.hash(), encode_move("a7a8", 1), 1),
+    ]))
+    .unwrap();
+
+    let moves: Vec<ChessMove> = book
+        .moves(&gamestate)
+        .iter()
+        .map(|book_move| book_move.book_move)
+        .collect();
+
+    assert_eq!(moves, vec![parse("a7a8q"), parse("a7a8n")]);
+}
+
+#[test]
+fn rejects_truncated_books() {
+    let mut bytes = book_bytes(&[(STARTING_POSITION_KEY, encode_move("e2e4", 0), 1)]);
+    bytes.pop();
+
+    assert_eq!(
+        ChessOpeningBook::from_bytes(&bytes).err(),
+        Some(ChessBookError::InvalidLength(15))
+    );
+}
@@ -0,0 +1,56 @@
+use ltchess::ChessMove;
+use ltchess::ChessParseError;
+use ltchess::ChessPieceKind;
+use ltchess::ChessPoint;
+
+fn move_error(encoded_move: &str) -> Option<ChessParseError> {
+    encoded_move.parse::<ChessMove>().err()
+}
+
+#[test]
+fn parses_moves() {
+    let promotion = ChessMove::new_promotion(
+        ChessPoint::new(4, 6),
+        ChessPoint::new(4, 7),
+        ChessPieceKind::Queen,
+    );
+
+    assert_eq!("e7e8q".parse::<ChessMove>(), Ok(promotion));
+    assert_eq!("e7 e8q".parse::<ChessMove>(), Ok(promotion));
+    assert_eq!("e7 e8 q".parse::<ChessMove>(), Ok(promotion));
+    assert_eq!(
+        "e2 e4".parse::<ChessMove>(),
+        Ok(ChessMove::new(ChessPoint::new(4, 1), ChessPoint::new(4, 3)))
+    );
+}
+
+#[test]
+fn reports_parse_errors() {
+    assert_eq!(move_error(""), Some(ChessParseError::Empty));
+    assert_eq!(move_error("e2"), Some(ChessParseError::Incomplete));
+    assert_eq!(move_error("z2 e4"), Some(ChessParseError::InvalidFile('z')));
+    assert_eq!(move_error("e2 e9"), Some(ChessParseError::InvalidRank('9')));
+    assert_eq!(
+        move_error("e7e8x"),
+        Some(ChessParseError::InvalidPromotionPiece('x'))
+    );
+    assert_eq!(
+        move_error("e2 e4 q r"),
+        Some(ChessParseError::WrongTokenCount(4))
+    );
+
+    // Only a single character after the tiles can be a promotion piece
+    assert_eq!(
+        move_error("e2 e4extra"),
+        Some(ChessParseError::TrailingCharacters("extra".to_string()))
+    );
+    assert_eq!(
+        move_error("e7e8qq"),
+        Some(ChessParseError::TrailingCharacters("qq".to_string()))
+    );
+
+    assert_eq!(
+        "e44".parse::<ChessPoint>(),
+        Err(ChessParseError::TrailingCharacters("4".to_string()))
+    );
+}
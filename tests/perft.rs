@@ -0,0 +1,84 @@
+// Perft counts for the standard test positions, from https://www.chessprogramming.org/Perft_Results
+// The depths are kept low enough for the suite to run quickly in debug builds
+
+use ltchess::ChessGamestate;
+use ltchess::STARTING_FEN;
+
+const KIWIPETE_FEN: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+const POSITION_3_FEN: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+const POSITION_4_FEN: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+const POSITION_4_MIRRORED_FEN: &str =
+    "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1";
+const POSITION_5_FEN: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+const POSITION_6_FEN: &str =
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+// Checks the perft counts from depth 1 upwards
+fn assert_perft(fen: &str, expected_counts: &[u64]) {
+    let gamestate = ChessGamestate::from_fen(fen).unwrap();
+
+    for (depth, expected_count) in (1..).zip(expected_counts) {
+        assert_eq!(
+            gamestate.perft(depth),
+            *expected_count,
+            "perft({}) of {}",
+            depth,
+            fen
+        );
+    }
+}
+
+#[test]
+fn perft_starting_position() {
+    assert_perft(STARTING_FEN, &[20, 400, 8902, 197281]);
+}
+
+// Castling through and out of check, and castling rights lost to captures
+#[test]
+fn perft_kiwipete() {
+    assert_perft(KIWIPETE_FEN, &[48, 2039, 97862]);
+}
+
+// En passant captures which expose the King along a rank
+#[test]
+fn perft_position_3() {
+    assert_perft(POSITION_3_FEN, &[14, 191, 2812, 43238]);
+}
+
+// Promotions, including promotions with capture, and pinned pieces
+#[test]
+fn perft_position_4() {
+    assert_perft(POSITION_4_FEN, &[6, 264, 9467]);
+}
+
+#[test]
+fn perft_position_4_mirrored() {
+    assert_perft(POSITION_4_MIRRORED_FEN, &[6, 264, 9467]);
+}
+
+#[test]
+fn perft_position_5() {
+    assert_perft(POSITION_5_FEN, &[44, 1486, 62379]);
+}
+
+#[test]
+fn perft_position_6() {
+    assert_perft(POSITION_6_FEN, &[46, 2079, 89890]);
+}
+
+#[test]
+fn perft_of_depth_zero_counts_the_current_position() {
+    assert_eq!(ChessGamestate::new().perft(0), 1);
+}
+
+#[test]
+fn divide_sums_to_perft() {
+    let gamestate = ChessGamestate::from_fen(KIWIPETE_FEN).unwrap();
+    let divided_counts = gamestate.divide(2);
+
+    assert_eq!(divided_counts.len(), 48);
+    assert_eq!(
+        divided_counts.iter().map(|(_, count)| count).sum::<u64>(),
+        gamestate.perft(2)
+    );
+}
@@ -0,0 +1,167 @@
+use ltchess::ChessGamestate;
+use ltchess::ChessPgnError;
+use ltchess::ChessPgnGame;
+use ltchess::ChessPgnResult;
+
+const SCHOLARS_MATE_PGN: &str = r#"[Event "Casual Game"]
+[Site "?"]
+[Date "2024.01.01"]
+[Round "?"]
+[White "Alice"]
+[Black "Bob"]
+[Result "1-0"]
+[Annotator "Carol"]
+
+1. e4 e5 2. Bc4 Nc6 3. Qh5 Nf6 4. Qxf7# 1-0
+"#;
+
+#[test]
+fn reads_movetext_with_annotations() {
+    let pgn = r#"[Event "Casual Game"]
+[White "Alice \"The Rook\""]
+
+% This escaped line is ignored
+1.e4 {The King's Pawn} e5 $1 2. Bc4 (2. Nf3 Nc6 (2... d6) 3. Bb5) 2... Nc6 ; a comment to the end of the line
+3. Qh5!? Nf6?? 4. Qxf7# 1-0
+"#;
+
+    let game = ChessPgnGame::parse(pgn).unwrap();
+
+    assert_eq!(game.tag("Event"), Some("Casual Game"));
+    assert_eq!(game.tag("White"), Some("Alice \"The Rook\""));
+    assert_eq!(game.result, ChessPgnResult::WhiteWins);
+    assert_eq!(
+        game.san_moves(),
+        ["e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6", "Qxf7#"]
+    );
+    assert!(game.current_gamestate().status().is_over());
+}
+
+#[test]
+fn round_trips_games() {
+    let game = ChessPgnGame::parse(SCHOLARS_MATE_PGN).unwrap();
+
+    assert_eq!(game.to_pgn(), SCHOLARS_MATE_PGN);
+}
+
+#[test]
+fn reads_brackets_and_escapes_in_tag_values() {
+    let pgn = r#"[Event "Rapid [blitz]"]
+[Site "C:\\Games\\"]
+[White "Alice \"[The Rook]\""]
+
+1. e4 *
+"#;
+
+    let game = ChessPgnGame::parse(pgn).unwrap();
+
+    assert_eq!(game.tag("Event"), Some("Rapid [blitz]"));
+    assert_eq!(game.tag("Site"), Some("C:\\Games\\"));
+    assert_eq!(game.tag("White"), Some("Alice \"[The Rook]\""));
+    assert_eq!(game.san_moves(), ["e4"]);
+
+    // Escaped values are written back the way they were read
+    let reparsed_game = ChessPgnGame::parse(&game.to_pgn()).unwrap();
+    assert_eq!(reparsed_game.tag("Event"), Some("Rapid [blitz]"));
+    assert_eq!(reparsed_game.tag("Site"), Some("C:\\Games\\"));
+    assert_eq!(reparsed_game.tag("White"), Some("Alice \"[The Rook]\""));
+}
+
+#[test]
+fn formats_each_move_in_its_own_position() {
+    let game = ChessPgnGame::parse(SCHOLARS_MATE_PGN).unwrap();
+    let mut gamestate = game.starting_gamestate().clone();
+
+    for (played_move, san) in game.moves().iter().zip(game.san_moves()) {
+        assert_eq!(played_move.to_san(&gamestate).unwrap(), san);
+        gamestate.perform_move(played_move).unwrap();
+    }
+}
+
+#[test]
+fn writes_the_seven_tag_roster() {
+    let mut game = ChessPgnGame::new();
+    game.set_tag("Annotator", "Carol");
+    game.set_tag("White", "Alice");
+    game.push_move(&"e2 e4".parse().unwrap()).unwrap();
+
+    assert_eq!(
+        game.to_pgn(),
+        r#"[Event "?"]
+[Site "?"]
+[Date "????.??.??"]
+[Round "?"]
+[White "Alice"]
+[Black "?"]
+[Result "*"]
+[Annotator "Carol"]
+
+1. e4 *
+"#
+    );
+}
+
+#[test]
+fn keeps_the_starting_position() {
+    let fen = "4k3/8/8/8/8/8/4P3/4K3 b - - 0 12";
+    let mut game = ChessPgnGame::from_gamestate(ChessGamestate::from_fen(fen).unwrap());
+    game.push_move(&"e8 d7".parse().unwrap()).unwrap();
+    game.push_move(&"e2 e4".parse().unwrap()).unwrap();
+
+    let pgn = game.to_pgn();
+    assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 12\"]"));
+    assert!(pgn.ends_with("\n12... Kd7 13. e4 *\n"));
+
+    let parsed_game = ChessPgnGame::parse(&pgn).unwrap();
+    assert_eq!(parsed_game.starting_gamestate().to_fen(), fen);
+    assert_eq!(
+        parsed_game.current_gamestate().to_fen(),
+        game.current_gamestate().to_fen()
+    );
+}
+
+#[test]
+fn reads_every_game_in_a_database() {
+    let database = format!(
+        "{}\n{}",
+        SCHOLARS_MATE_PGN, "[Event \"Second\"]\n\n1. d4 d5 *\n"
+    );
+    let games = ChessPgnGame::parse_all(&database).unwrap();
+
+    assert_eq!(games.len(), 2);
+    assert_eq!(games[1].tag("Event"), Some("Second"));
+    assert_eq!(games[1].san_moves(), ["d4", "d5"]);
+    assert_eq!(games[1].result, ChessPgnResult::Unknown);
+}
+
+#[test]
+fn reports_invalid_pgn() {
+    assert_eq!(
+        ChessPgnGame::parse("1. e4 e5 2. Ke3 *").err(),
+        Some(ChessPgnError::IllegalMove {
+            ply: 3,
+            san: "Ke3".to_string(),
+        })
+    );
+    assert_eq!(
+        ChessPgnGame::parse("1. e4 {unfinished").err(),
+        Some(ChessPgnError::UnterminatedComment)
+    );
+    assert_eq!(
+        ChessPgnGame::parse("1. e4 (1. d4").err(),
+        Some(ChessPgnError::UnterminatedVariation)
+    );
+    assert_eq!(
+        ChessPgnGame::parse("[Event]\n1. e4 *").err(),
+        Some(ChessPgnError::InvalidTagPair)
+    );
+    assert_eq!(
+        ChessPgnGame::parse("[Event \"Rapid]\n1. e4 *").err(),
+        Some(ChessPgnError::InvalidTagPair)
+    );
+    assert_eq!(
+        ChessPgnGame::parse("[Event \"Rapid\" \"Blitz\"]\n1. e4 *").err(),
+        Some(ChessPgnError::InvalidTagPair)
+    );
+    assert_eq!(ChessPgnGame::parse("").err(), Some(ChessPgnError::NoGame));
+}
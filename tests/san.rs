@@ -0,0 +1,128 @@
+use ltchess::ChessGamestate;
+use ltchess::ChessMove;
+use ltchess::ChessSanError;
+
+fn san_of(fen: &str, encoded_move: &str) -> String {
+    let gamestate = ChessGamestate::from_fen(fen).unwrap();
+
+    encoded_move
+        .parse::<ChessMove>()
+        .unwrap()
+        .to_san(&gamestate)
+        .unwrap()
+}
+
+fn parse_san(fen: &str, san: &str) -> Result<String, ChessSanError> {
+    let gamestate = ChessGamestate::from_fen(fen).unwrap();
+
+    ChessMove::from_san(san, &gamestate).map(|parsed_move| parsed_move.to_string())
+}
+
+#[test]
+fn parses_san() {
+    let mut gamestate = ChessGamestate::new();
+
+    assert_eq!(
+        ChessMove::from_san("Nf3", &gamestate).unwrap().to_string(),
+        "g1f3"
+    );
+
+    gamestate.perform_move(&"e2 e4".parse().unwrap()).unwrap();
+    gamestate.perform_move(&"d7 d5".parse().unwrap()).unwrap();
+
+    assert_eq!(
+        ChessMove::from_san("exd5", &gamestate).unwrap().to_string(),
+        "e4d5"
+    );
+
+    let castling_fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    assert_eq!(parse_san(castling_fen, "O-O"), Ok("e1g1".to_string()));
+    assert_eq!(parse_san(castling_fen, "0-0-0"), Ok("e1c1".to_string()));
+
+    assert_eq!(
+        parse_san("2k5/4P3/8/8/8/8/8/4K3 w - - 0 1", "e8=Q+"),
+        Ok("e7e8q".to_string())
+    );
+    assert_eq!(
+        parse_san("4k3/8/8/8/8/8/8/R2n1RK1 w - - 0 1", "Raxd1#"),
+        Ok("a1d1".to_string())
+    );
+}
+
+#[test]
+fn reports_san_errors() {
+    let gamestate = ChessGamestate::new();
+
+    assert_eq!(
+        ChessMove::from_san("Zz9", &gamestate),
+        Err(ChessSanError::Malformed)
+    );
+    assert_eq!(
+        ChessMove::from_san("e", &gamestate),
+        Err(ChessSanError::Malformed)
+    );
+    assert_eq!(
+        ChessMove::from_san("Nf6", &gamestate),
+        Err(ChessSanError::NoMatchingMove)
+    );
+
+    // Both Rooks can capture on d1
+    assert_eq!(
+        parse_san("4k3/8/8/8/8/8/8/R2n1RK1 w - - 0 1", "Rxd1"),
+        Err(ChessSanError::AmbiguousMove)
+    );
+}
+
+#[test]
+fn disambiguates_pieces() {
+    // The Rooks are on different files, so the file is enough
+    let fen = "4k3/8/8/8/8/8/8/R2n1RK1 w - - 0 1";
+    assert_eq!(san_of(fen, "a1d1"), "Raxd1");
+    assert_eq!(san_of(fen, "f1d1"), "Rfxd1");
+
+    // The Rooks are on the same file, so the rank is needed
+    let fen = "4k3/8/8/R7/8/8/8/R5K1 w - - 0 1";
+    assert_eq!(san_of(fen, "a1a3"), "R1a3");
+    assert_eq!(parse_san(fen, "R5a3"), Ok("a5a3".to_string()));
+
+    // Other Queens share both the file and the rank, so the whole tile is needed
+    let fen = "4k3/8/8/8/8/Q7/8/Q1Q3K1 w - - 0 1";
+    assert_eq!(san_of(fen, "a1b2"), "Qa1b2");
+    assert_eq!(parse_san(fen, "Qa1b2"), Ok("a1b2".to_string()));
+
+    // The Rook on d5 is pinned, so it cannot legally reach d3 and does not need to be told apart
+    let fen = "4k3/8/8/r2R3K/8/8/8/3R4 w - - 0 1";
+    assert_eq!(san_of(fen, "d1d3"), "Rd3");
+}
+
+#[test]
+fn adds_suffixes() {
+    assert_eq!(san_of("2k5/4P3/8/8/8/8/8/4K3 w - - 0 1", "e7e8q"), "e8=Q+");
+    assert_eq!(
+        san_of(
+            "r1bqkbnr/pppp1ppp/2n5/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 0 1",
+            "h5f7"
+        ),
+        "Qxf7#"
+    );
+    assert_eq!(
+        san_of("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "e1c1"),
+        "O-O-O"
+    );
+}
+
+#[test]
+fn refuses_to_format_illegal_moves() {
+    let gamestate = ChessGamestate::new();
+
+    // The source tile is empty, the Knight cannot reach the destination, and it is not Black's turn
+    for encoded_move in ["e4 e5", "g1 g3", "e7 e5"] {
+        assert_eq!(
+            encoded_move
+                .parse::<ChessMove>()
+                .unwrap()
+                .to_san(&gamestate),
+            Err(ChessSanError::NoMatchingMove)
+        );
+    }
+}
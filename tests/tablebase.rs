@@ -0,0 +1,631 @@
+use ltchess::ChessGamestate;
+use ltchess::ChessMove;
+use ltchess::ChessTablebase;
+use ltchess::ChessTablebaseError;
+use ltchess::ChessWdl;
+
+use std::fs;
+use std::ops::Deref;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+const WDL_MAGIC: [u8; 4] = [0x71, 0xE8, 0x23, 0x5D];
+const DTZ_MAGIC: [u8; 4] = [0xD7, 0x66, 0x0C, 0xA5];
+
+// The values WDL tables store for each result
+const LOSS: u8 = 0;
+const DRAW: u8 = 2;
+const WIN: u8 = 4;
+
+// The number of placements of the Kings and one other piece in a table without Pawns, and of the Kings and one Pawn
+// in each file's part of a table with Pawns
+const KQVK_SIZE: usize = 31332;
+const KPVK_SIZE: usize = 23436;
+
+// The piece codes of the tables, in the order their pieces are indexed
+const KQVK_PIECES: [u8; 3] = [6, 14, 5];
+const KPVK_PIECES: [u8; 3] = [1, 6, 14];
+
+// The index of White King b1, Black King h8, White Queen c4 in the KQvK table
+const KQVK_INDEX: usize = 3869;
+// The index of White Pawn a4, White King e1, Black King e8 in the a-file part of the KPvK table
+const KPVK_INDEX: usize = 21950;
+
+// The values of one side to move and one file of a test table
+enum TestValues {
+    // Every position has the same value
+    Single(u8),
+    // Every position has its own value, which is compressed
+    Compressed(Vec<u8>),
+}
+
+// An empty directory for a test's table files, which is deleted along with the files when the test ends
+// Its name is unique to the test run and to the directory, so tests running at the same time never share one
+struct TableDirectory(PathBuf);
+
+impl TableDirectory {
+    fn new() -> Self {
+        static DIRECTORY_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let directory = std::env::temp_dir().join(format!(
+            "ltchess-tablebase-{}-{}",
+            process::id(),
+            DIRECTORY_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        fs::create_dir_all(&directory).unwrap();
+
+        TableDirectory(directory)
+    }
+}
+
+impl Deref for TableDirectory {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TableDirectory {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TableDirectory {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+// Writes a table in the Syzygy format, with the same pieces for every side and file, the leading group indexed first,
+// the given flags and values for each file and side to move, and for a DTZ table with one file, its maps if it has any
+// Values are compressed with a fixed seven-bit code, in which the symbols 0 to 4 stand for themselves and each further
+// symbol stands for a run of 2 to 16384 copies of one of them, and are all put in a single block
+fn write_table(
+    path: &Path,
+    magic: [u8; 4],
+    pieces: &[u8],
+    parts: &[Vec<(u8, TestValues)>],
+    dtz_maps: &[&[u8]],
+) {
+    let has_pawns = parts.len() == 4;
+    let mut bytes = magic.to_vec();
+
+    bytes.push(2 * has_pawns as u8 + (parts[0].len() == 2) as u8);
+
+    for _ in parts {
+        bytes.push(0x00);
+        bytes.extend(pieces.iter().map(|&piece| piece | (piece << 4)));
+    }
+
+    bytes.resize(bytes.len() + bytes.len() % 2, 0);
+
+    let mut blocks = Vec::new();
+
+    for (flags, values) in parts.iter().flatten() {
+        match values {
+            TestValues::Single(value) => bytes.extend([flags | 0x80, *value]),
+            TestValues::Compressed(values) => {
+                let block = compress(values);
+                let block_size_log = block.len().trailing_zeros() as u8;
+
+                bytes.extend([*flags, block_size_log, 10, 0]);
+                bytes.extend(1u32.to_le_bytes());
+                bytes.extend([7, 7]);
+                bytes.extend(0u16.to_le_bytes());
+                bytes.extend(75u16.to_le_bytes());
+
+                for symbol in 0..75 {
+                    // Each run symbol is a pair of the symbols for half as many copies
+                    let (left, right) = match symbol {
+                        0..=4 => (symbol, 0xFFF),
+                        _ => {
+                            let half_run = run_symbol((symbol - 5) / 14, (symbol - 5) % 14);
+                            (half_run, half_run)
+                        }
+                    };
+
+                    bytes.extend([
+                        left as u8,
+                        (left >> 8) as u8 | ((right & 0xF) << 4) as u8,
+                        (right >> 4) as u8,
+                    ]);
+                }
+
+                bytes.push(0);
+                blocks.push((values.len(), block));
+            }
+        }
+    }
+
+    for map in dtz_maps {
+        bytes.push(map.len() as u8);
+        bytes.extend(*map);
+    }
+
+    if magic == DTZ_MAGIC {
+        bytes.resize(bytes.len() + bytes.len() % 2, 0);
+    }
+
+    // Each sparse index entry points at the middle of its span, which is always in the only block
+    for (value_count, _) in &blocks {
+        for span in 0..value_count.div_ceil(1024) {
+            bytes.extend(0u32.to_le_bytes());
+            bytes.extend((span as u16 * 1024 + 512).to_le_bytes());
+        }
+    }
+
+    for (value_count, _) in &blocks {
+        bytes.extend((*value_count as u16 - 1).to_le_bytes());
+    }
+
+    for (_, block) in &blocks {
+        bytes.resize(bytes.len().next_multiple_of(64), 0);
+        bytes.extend(block);
+    }
+
+    fs::write(path, bytes).unwrap();
+}
+
+// Gets the symbol standing for 2 to the power of the given number of copies of a value
+fn run_symbol(value: u16, power: u16) -> u16 {
+    match power {
+        0 => value,
+        _ => 5 + value * 14 + power - 1,
+    }
+}
+
+// Compresses values into a block, padded to a power of two so that the decoder can read ahead past the last symbol
+fn compress(values: &[u8]) -> Vec<u8> {
+    let mut bits = Vec::new();
+    let mut start = 0;
+
+    while start < values.len() {
+        let value = values[start];
+        let run = values[start..]
+            .iter()
+            .take_while(|&&other| other == value)
+            .count();
+        let mut left = run;
+
+        while left > 0 {
+            let power = (left.ilog2() as u16).min(14);
+
+            let symbol = run_symbol(value as u16, power);
+            bits.extend((0..7).rev().map(|bit| (symbol >> bit) & 1 == 1));
+            left -= 1 << power;
+        }
+
+        start += run;
+    }
+
+    let mut block: Vec<u8> = bits
+        .chunks(8)
+        .map(|byte_bits| {
+            (0..8).fold(0, |byte, bit| {
+                (byte << 1) | *byte_bits.get(bit).unwrap_or(&false) as u8
+            })
+        })
+        .collect();
+
+    block.resize((block.len() + 8).next_power_of_two(), 0);
+    block
+}
+
+// Gets the values of a table part in which every position has one value, apart from one which has another
+fn values_with(size: usize, value: u8, index: usize, index_value: u8) -> TestValues {
+    let mut values = vec![value; size];
+    values[index] = index_value;
+
+    TestValues::Compressed(values)
+}
+
+// Writes a KQvK WDL table in which White wins with King b1, Queen c4 against King h8 (or its mirror images) and draws
+// otherwise, and Black to move always loses
+fn write_kqvk_wdl_table(directory: &Path) {
+    write_table(
+        &directory.join("KQvK.rtbw"),
+        WDL_MAGIC,
+        &KQVK_PIECES,
+        &[vec![
+            (0, values_with(KQVK_SIZE, DRAW, KQVK_INDEX, WIN)),
+            (0, TestValues::Single(LOSS)),
+        ]],
+        &[],
+    );
+}
+
+// Opens a tablebase with KQvK and KRvK tables
+// In the KQvK DTZ table, White wins in 7 plies with King b1, Queen c4 against King h8; in the KRvK tables White always
+// wins, in 11 plies, and Black always loses
+// Tables are only read once they are probed, so the directory must be kept until the tablebase is no longer used
+fn open_tablebase() -> (TableDirectory, ChessTablebase) {
+    let directory = TableDirectory::new();
+
+    write_kqvk_wdl_table(&directory);
+    write_table(
+        &directory.join("KQvK.rtbz"),
+        DTZ_MAGIC,
+        &KQVK_PIECES,
+        &[vec![(0, values_with(KQVK_SIZE, 0, KQVK_INDEX, 3))]],
+        &[],
+    );
+    write_table(
+        &directory.join("KRvK.rtbw"),
+        WDL_MAGIC,
+        &[6, 14, 4],
+        &[vec![
+            (0, TestValues::Single(WIN)),
+            (0, TestValues::Single(LOSS)),
+        ]],
+        &[],
+    );
+    write_table(
+        &directory.join("KRvK.rtbz"),
+        DTZ_MAGIC,
+        &[6, 14, 4],
+        &[vec![(0, TestValues::Single(5))]],
+        &[],
+    );
+
+    fs::write(directory.join("README.txt"), "Not a table").unwrap();
+
+    let tablebase = ChessTablebase::open(&directory).unwrap();
+
+    (directory, tablebase)
+}
+
+fn gamestate(fen: &str) -> ChessGamestate {
+    ChessGamestate::from_fen(fen).unwrap()
+}
+
+#[test]
+fn finds_the_tables_in_a_directory() {
+    let (_directory, tablebase) = open_tablebase();
+
+    assert_eq!(tablebase.table_count(), 2);
+    assert_eq!(tablebase.max_pieces(), 3);
+}
+
+#[test]
+fn rejects_files_which_are_not_tables() {
+    let directory = TableDirectory::new();
+    fs::write(directory.join("KPvK.rtbw"), DTZ_MAGIC).unwrap();
+
+    assert_eq!(
+        ChessTablebase::open(&directory).err(),
+        Some(ChessTablebaseError::InvalidTable("KPvK.rtbw".to_string()))
+    );
+}
+
+#[test]
+fn decides_positions_by_the_rules() {
+    let (_directory, tablebase) = open_tablebase();
+
+    let checkmated = gamestate("k7/1Q6/1K6/8/8/8/8/8 b - - 0 1");
+    let stalemated = gamestate("k7/8/1Q6/8/8/8/8/7K b - - 0 1");
+    let bare_kings = gamestate("k7/8/8/8/8/8/8/7K w - - 0 1");
+
+    assert_eq!(tablebase.probe_wdl(&checkmated), Ok(ChessWdl::Loss));
+    assert_eq!(tablebase.probe_wdl(&stalemated), Ok(ChessWdl::Draw));
+    assert_eq!(tablebase.probe_wdl(&bare_kings), Ok(ChessWdl::Draw));
+    assert_eq!(tablebase.probe_dtz(&bare_kings), Ok(0));
+}
+
+#[test]
+fn reads_compressed_wdl_values() {
+    let (_directory, tablebase) = open_tablebase();
+
+    // The same position mirrored left to right, and with the colors swapped, has the same index
+    let winning = gamestate("7k/8/8/8/2Q5/8/8/1K6 w - - 0 1");
+    let mirrored = gamestate("k7/8/8/8/5Q2/8/8/6K1 w - - 0 1");
+    let colors_swapped = gamestate("1k6/8/8/2q5/8/8/8/7K b - - 0 1");
+    let drawn = gamestate("7k/8/8/8/8/3Q4/8/1K6 w - - 0 1");
+    let black_to_move = gamestate("7k/8/8/8/2Q5/8/8/1K6 b - - 0 1");
+
+    assert_eq!(tablebase.probe_wdl(&winning), Ok(ChessWdl::Win));
+    assert_eq!(tablebase.probe_wdl(&mirrored), Ok(ChessWdl::Win));
+    assert_eq!(tablebase.probe_wdl(&colors_swapped), Ok(ChessWdl::Win));
+    assert_eq!(tablebase.probe_wdl(&drawn), Ok(ChessWdl::Draw));
+    assert_eq!(tablebase.probe_wdl(&black_to_move), Ok(ChessWdl::Loss));
+}
+
+#[test]
+fn reads_tables_with_pawns_by_the_leading_pawn_file() {
+    let directory = TableDirectory::new();
+
+    let mut parts = vec![vec![
+        (0, values_with(KPVK_SIZE, DRAW, KPVK_INDEX, WIN)),
+        (0, TestValues::Single(LOSS)),
+    ]];
+
+    for _ in 1..4 {
+        parts.push(vec![
+            (0, TestValues::Single(DRAW)),
+            (0, TestValues::Single(LOSS)),
+        ]);
+    }
+
+    write_table(
+        &directory.join("KPvK.rtbw"),
+        WDL_MAGIC,
+        &KPVK_PIECES,
+        &parts,
+        &[],
+    );
+    let tablebase = ChessTablebase::open(&directory).unwrap();
+
+    // The h-file is mirrored onto the a-file
+    let winning = gamestate("4k3/8/8/8/P7/8/8/4K3 w - - 0 1");
+    let mirrored = gamestate("3k4/8/8/8/7P/8/8/3K4 w - - 0 1");
+    let other_rank = gamestate("4k3/8/8/P7/8/8/8/4K3 w - - 0 1");
+    let other_file = gamestate("4k3/8/8/8/1P6/8/8/4K3 w - - 0 1");
+
+    assert_eq!(tablebase.probe_wdl(&winning), Ok(ChessWdl::Win));
+    assert_eq!(tablebase.probe_wdl(&mirrored), Ok(ChessWdl::Win));
+    assert_eq!(tablebase.probe_wdl(&other_rank), Ok(ChessWdl::Draw));
+    assert_eq!(tablebase.probe_wdl(&other_file), Ok(ChessWdl::Draw));
+}
+
+#[test]
+fn reads_dtz_values_for_either_side_to_move() {
+    let (_directory, tablebase) = open_tablebase();
+
+    // Both tables store distances in moves, which are doubled and given one more ply
+    assert_eq!(
+        tablebase.probe_dtz(&gamestate("7k/8/8/8/2Q5/8/8/1K6 w - - 0 1")),
+        Ok(7)
+    );
+    assert_eq!(
+        tablebase.probe_dtz(&gamestate("k7/8/8/8/8/8/8/1R5K w - - 0 1")),
+        Ok(11)
+    );
+
+    // The KRvK table only stores White to move, so Black's distance comes from the positions after its moves
+    assert_eq!(
+        tablebase.probe_dtz(&gamestate("k7/8/8/8/8/8/8/1R5K b - - 0 1")),
+        Ok(-12)
+    );
+}
+
+#[test]
+fn reads_dtz_values_through_the_maps() {
+    let directory = TableDirectory::new();
+    write_kqvk_wdl_table(&directory);
+
+    // The table's wins are stored in plies, as the second entry of the map of wins
+    write_table(
+        &directory.join("KQvK.rtbz"),
+        DTZ_MAGIC,
+        &KQVK_PIECES,
+        &[vec![(2 | 4, values_with(KQVK_SIZE, 0, KQVK_INDEX, 1))]],
+        &[&[9, 17, 25], &[5], &[1], &[1]],
+    );
+    let tablebase = ChessTablebase::open(&directory).unwrap();
+
+    assert_eq!(
+        tablebase.probe_dtz(&gamestate("7k/8/8/8/2Q5/8/8/1K6 w - - 0 1")),
+        Ok(18)
+    );
+}
+
+#[test]
+fn reports_why_a_position_cannot_be_probed() {
+    let (_directory, tablebase) = open_tablebase();
+
+    assert_eq!(
+        tablebase.probe_wdl(&gamestate("k7/8/8/8/8/8/P7/7K b - - 0 1")),
+        Err(ChessTablebaseError::MissingTable("KPvK".to_string()))
+    );
+    assert_eq!(
+        tablebase.probe_wdl(&gamestate("k7/8/8/8/8/8/PP6/7K w - - 0 1")),
+        Err(ChessTablebaseError::MissingTable("KPPvK".to_string()))
+    );
+    assert_eq!(
+        tablebase.probe_wdl(&gamestate("k7/8/8/8/8/8/P7/R3K3 w Q - 0 1")),
+        Err(ChessTablebaseError::CastlingRights)
+    );
+    assert_eq!(
+        tablebase.probe_wdl(&gamestate("k7/8/8/8/8/8/PPPPPP2/7K w - - 0 1")),
+        Err(ChessTablebaseError::TooManyPieces(8))
+    );
+}
+
+#[test]
+fn reports_tables_which_cannot_be_read() {
+    let directory = TableDirectory::new();
+    fs::write(directory.join("KQvK.rtbw"), WDL_MAGIC).unwrap();
+    let tablebase = ChessTablebase::open(&directory).unwrap();
+
+    assert_eq!(
+        tablebase.probe_wdl(&gamestate("7k/8/8/8/2Q5/8/8/1K6 w - - 0 1")),
+        Err(ChessTablebaseError::InvalidTable("KQvK.rtbw".to_string()))
+    );
+
+    // Without any tables, positions with few pieces are still missing a table rather than having too many pieces
+    let empty_directory = TableDirectory::new();
+    let empty_tablebase = ChessTablebase::open(&empty_directory).unwrap();
+
+    assert_eq!(
+        empty_tablebase.probe_wdl(&gamestate("7k/8/8/8/2Q5/8/8/1K6 w - - 0 1")),
+        Err(ChessTablebaseError::MissingTable("KQvK".to_string()))
+    );
+}
+
+#[test]
+fn finds_the_best_move_when_every_reply_is_decided() {
+    let (_directory, tablebase) = open_tablebase();
+
+    // The only move captures the Queen, leaving a draw with the bare Kings
+    let gamestate = gamestate("k7/1Q6/8/8/8/8/8/7K b - - 0 1");
+    let capture: ChessMove = "a8b7".parse().unwrap();
+
+    assert_eq!(
+        tablebase.best_move(&gamestate),
+        Ok(Some((capture, ChessWdl::Draw)))
+    );
+}
+
+#[test]
+fn finds_the_best_move_among_the_replies_which_can_be_probed() {
+    let (_directory, tablebase) = open_tablebase();
+
+    // Only capturing the Pawn reaches a table in the tablebase, as every other move leaves KRvKP
+    let gamestate_with_pawn = gamestate("k7/8/8/8/8/8/p7/R6K w - - 0 1");
+    let capture: ChessMove = "a1a2".parse().unwrap();
+
+    assert_eq!(
+        tablebase.best_move(&gamestate_with_pawn),
+        Ok(Some((capture, ChessWdl::Win)))
+    );
+
+    // When no reply can be probed, the first reply's problem is given
+    assert_eq!(
+        tablebase.best_move(&gamestate("k7/8/8/8/8/p7/8/7K w - - 0 1")),
+        Err(ChessTablebaseError::MissingTable("KvKP".to_string()))
+    );
+}
+
+// The results of real KQvK and KRvK tables, as published by the Syzygy generator, are checked against chess itself
+// rather than against values copied from another prober: the fixed positions below have results that follow from
+// the rules, and every position of a sweep must have the best result of its replies
+// No table files are checked in, so this only runs with LTCHESS_SYZYGY_PATH set to a directory holding KQvK.rtbw,
+// KQvK.rtbz, KRvK.rtbw and KRvK.rtbz; the other tests only read tables written by write_table()
+#[test]
+fn agrees_with_real_syzygy_tables() {
+    let Some(path) = std::env::var_os("LTCHESS_SYZYGY_PATH") else {
+        eprintln!("LTCHESS_SYZYGY_PATH is not set, so the real Syzygy tables are not checked");
+        return;
+    };
+
+    let tablebase = ChessTablebase::open(path).unwrap();
+
+    // Mates in one, a Queen left hanging, and a King with nowhere to go but into a mate
+    let fixed_positions = [
+        ("7k/5Q2/6K1/8/8/8/8/8 w - - 0 1", ChessWdl::Win, Some(1)),
+        ("8/8/8/8/8/6k1/5q2/7K b - - 0 1", ChessWdl::Win, Some(1)),
+        ("7k/8/6K1/8/8/8/8/R7 w - - 0 1", ChessWdl::Win, Some(1)),
+        ("7k/6Q1/8/8/8/8/8/K7 b - - 0 1", ChessWdl::Draw, Some(0)),
+        ("k7/1R6/8/8/8/8/8/7K b - - 0 1", ChessWdl::Draw, Some(0)),
+        ("k7/8/1K6/8/8/8/8/7R b - - 0 1", ChessWdl::Loss, None),
+    ];
+
+    for (fen, wdl, dtz) in fixed_positions {
+        let gamestate = gamestate(fen);
+
+        assert_eq!(tablebase.probe_wdl(&gamestate), Ok(wdl), "{}", fen);
+        if let Some(dtz) = dtz {
+            assert_eq!(tablebase.probe_dtz(&gamestate), Ok(dtz), "{}", fen);
+        }
+    }
+
+    // The longest wins are mates in 10 moves with a Queen and 16 moves with a Rook, which no capture or Pawn move cuts
+    // short, so no distance can be more than those plies and the one ply a table storing moves may add
+    for (piece, longest_win) in [('Q', 20), ('R', 32)] {
+        for white_king in [0, 18, 27] {
+            for black_king in 0..64 {
+                for piece_tile in 0..64 {
+                    for turn in ["w", "b"] {
+                        let pieces = [('K', white_king), ('k', black_king), (piece, piece_tile)];
+                        let Some(mut gamestate) = sweep_gamestate(&pieces, turn) else {
+                            continue;
+                        };
+
+                        check_real_table_position(&tablebase, &mut gamestate, longest_win);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Places the given pieces, by their FEN letter and tile index, returning None if they overlap or cannot be reached
+fn sweep_gamestate(pieces: &[(char, usize)], turn: &str) -> Option<ChessGamestate> {
+    let mut tiles = [None; 64];
+
+    for &(letter, tile) in pieces {
+        if tiles[tile].replace(letter).is_some() {
+            return None;
+        }
+    }
+
+    let ranks: Vec<String> = tiles
+        .chunks(8)
+        .rev()
+        .map(|rank| {
+            let mut rank_text = String::new();
+            let mut empty_count = 0;
+
+            for tile in rank {
+                match tile {
+                    Some(letter) => {
+                        if empty_count > 0 {
+                            rank_text.push_str(&empty_count.to_string());
+                            empty_count = 0;
+                        }
+                        rank_text.push(*letter);
+                    }
+                    None => empty_count += 1,
+                }
+            }
+
+            if empty_count > 0 {
+                rank_text.push_str(&empty_count.to_string());
+            }
+
+            rank_text
+        })
+        .collect();
+
+    ChessGamestate::from_fen(&format!("{} {} - - 0 1", ranks.join("/"), turn)).ok()
+}
+
+// Checks that a position has the best result of its replies, and a distance which agrees with its result
+fn check_real_table_position(
+    tablebase: &ChessTablebase,
+    gamestate: &mut ChessGamestate,
+    longest_win: i32,
+) {
+    let fen = gamestate.to_fen();
+    let wdl = tablebase.probe_wdl(gamestate).unwrap();
+    let dtz = tablebase.probe_dtz(gamestate).unwrap();
+
+    match wdl {
+        ChessWdl::Win => assert!((1..=longest_win).contains(&dtz), "{} {}", fen, dtz),
+        ChessWdl::Draw => assert_eq!(dtz, 0, "{}", fen),
+        ChessWdl::Loss => assert!((-longest_win..=-1).contains(&dtz), "{} {}", fen, dtz),
+        // The fifty-move rule never decides a position with a Queen or Rook against a bare King
+        _ => panic!("{} {:?}", fen, wdl),
+    }
+
+    // Checkmates and stalemates are decided by the rules rather than the tables
+    let legal_moves = gamestate.legal_moves();
+    if legal_moves.is_empty() {
+        return;
+    }
+
+    let best_reply_wdl = legal_moves
+        .iter()
+        .map(|reply| {
+            gamestate.perform_move(reply).unwrap();
+            let reply_wdl = tablebase.probe_wdl(gamestate).unwrap();
+            gamestate.unmake_move();
+
+            match reply_wdl {
+                ChessWdl::Loss => ChessWdl::Win,
+                ChessWdl::BlessedLoss => ChessWdl::CursedWin,
+                ChessWdl::Draw => ChessWdl::Draw,
+                ChessWdl::CursedWin => ChessWdl::BlessedLoss,
+                ChessWdl::Win => ChessWdl::Loss,
+            }
+        })
+        .max()
+        .unwrap();
+
+    assert_eq!(wdl, best_reply_wdl, "{}", fen);
+}
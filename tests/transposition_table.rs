@@ -0,0 +1,114 @@
+use ltchess::ChessBound;
+use ltchess::ChessEngine;
+use ltchess::ChessGamestate;
+use ltchess::ChessMove;
+use ltchess::ChessSearchLimits;
+use ltchess::ChessTranspositionTable;
+
+#[test]
+fn stores_and_probes_entries() {
+    let mut table = ChessTranspositionTable::new(1);
+    let hash = ChessGamestate::new().hash();
+    let best_move: ChessMove = "e2e4".parse().unwrap();
+
+    assert!(table.probe(hash).is_none());
+
+    table.store(hash, 5, ChessBound::Exact, 35, Some(best_move), 0);
+    let entry = table.probe(hash).unwrap();
+
+    assert_eq!(entry.depth(), 5);
+    assert_eq!(entry.bound(), ChessBound::Exact);
+    assert_eq!(entry.score(0), 35);
+    assert_eq!(entry.best_move(), Some(best_move));
+
+    // A different position in the same slot must not be mistaken for the stored one
+    assert!(table.probe(hash ^ (table.capacity() as u64)).is_none());
+}
+
+#[test]
+fn adjusts_mate_scores_to_the_ply_they_are_reached_at() {
+    let mut table = ChessTranspositionTable::new(1);
+
+    // Mate in 3 plies, found 2 plies from the root, is mate in 1 ply from the stored position
+    table.store(
+        1,
+        3,
+        ChessBound::Exact,
+        ChessEngine::MATE_SCORE - 3,
+        None,
+        2,
+    );
+    table.store(
+        2,
+        3,
+        ChessBound::Exact,
+        -ChessEngine::MATE_SCORE + 3,
+        None,
+        2,
+    );
+
+    assert_eq!(
+        table.probe(1).unwrap().score(4),
+        ChessEngine::MATE_SCORE - 5
+    );
+    assert_eq!(
+        table.probe(2).unwrap().score(0),
+        -ChessEngine::MATE_SCORE + 1
+    );
+}
+
+#[test]
+fn keeps_deeper_entries_of_the_current_search() {
+    let mut table = ChessTranspositionTable::new(1);
+    let other_hash = 1 + table.capacity() as u64;
+
+    table.store(1, 6, ChessBound::Exact, 10, None, 0);
+    table.store(other_hash, 2, ChessBound::Exact, 20, None, 0);
+
+    assert!(table.probe(1).is_some());
+    assert!(table.probe(other_hash).is_none());
+
+    // Entries from an earlier search are always replaced
+    table.new_search();
+    table.store(other_hash, 2, ChessBound::Exact, 20, None, 0);
+
+    assert!(table.probe(1).is_none());
+    assert!(table.probe(other_hash).is_some());
+}
+
+#[test]
+fn keeps_the_best_move_when_a_search_fails_low() {
+    let mut table = ChessTranspositionTable::new(1);
+    let best_move: ChessMove = "g1f3".parse().unwrap();
+
+    table.store(1, 2, ChessBound::Lower, 50, Some(best_move), 0);
+    table.store(1, 3, ChessBound::Upper, -10, None, 0);
+
+    assert_eq!(table.probe(1).unwrap().best_move(), Some(best_move));
+}
+
+#[test]
+fn reports_how_full_it_is() {
+    let mut table = ChessTranspositionTable::new(1);
+
+    assert_eq!(table.hashfull(), 0);
+
+    for hash in 0..500 {
+        table.store(hash, 1, ChessBound::Exact, 0, None, 0);
+    }
+
+    assert_eq!(table.hashfull(), 500);
+
+    table.clear();
+
+    assert_eq!(table.hashfull(), 0);
+}
+
+#[test]
+fn engine_fills_its_table_while_searching() {
+    let gamestate = ChessGamestate::new();
+    let result = ChessEngine::with_hash_size(1).search(&gamestate, &ChessSearchLimits::depth(4));
+
+    assert!(result.best_move.is_some());
+    assert!(result.hashfull > 0);
+}
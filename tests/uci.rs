@@ -0,0 +1,136 @@
+use ltchess::ChessUci;
+
+mod common;
+
+// Runs the given commands through the UCI front end, returning every line it sent back
+fn run_commands(commands: &str) -> Vec<String> {
+    common::run_commands(commands, |output, input| ChessUci::new(output).run(input))
+}
+
+#[test]
+fn identifies_itself() {
+    let lines = run_commands("uci\nisready\n");
+
+    assert!(lines[0].starts_with("id name "));
+    assert!(lines.contains(&"uciok".to_string()));
+    assert_eq!(lines.last().unwrap(), "readyok");
+}
+
+#[test]
+fn searches_from_a_fen_position() {
+    let lines = run_commands(
+        "position fen r1bqkbnr/pppp1ppp/2n5/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 0 1\ngo depth 3\n",
+    );
+
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("info depth 1 score mate 1")));
+    assert_eq!(lines.last().unwrap(), "bestmove h5f7");
+}
+
+#[test]
+fn plays_moves_given_in_long_algebraic_notation() {
+    // After 1. f3 e5 2. g4, Black mates with Qh4
+    let lines = run_commands("position startpos moves f2f3 e7e5 g2g4\ngo depth 2\n");
+
+    assert_eq!(lines.last().unwrap(), "bestmove d8h4");
+}
+
+#[test]
+fn promotes_with_a_promotion_letter() {
+    let lines = run_commands("position fen 8/P7/8/8/8/8/8/k6K w - - 0 1\ngo depth 2\n");
+
+    assert_eq!(lines.last().unwrap(), "bestmove a7a8q");
+}
+
+#[test]
+fn stops_an_infinite_search() {
+    let lines = run_commands("position startpos\ngo infinite\nstop\n");
+
+    assert!(lines.last().unwrap().starts_with("bestmove "));
+}
+
+#[test]
+fn searches_with_a_clock() {
+    let lines =
+        run_commands("position startpos moves e2e4\ngo wtime 1000 btime 1000 winc 10 binc 10\n");
+
+    assert!(lines.last().unwrap().starts_with("bestmove "));
+}
+
+#[test]
+fn searches_with_a_negative_clock() {
+    let lines = run_commands("position startpos\ngo wtime -500 btime 1000\n");
+
+    assert!(!lines.iter().any(|line| line.starts_with("info string")));
+    assert!(lines.last().unwrap().starts_with("bestmove "));
+    assert_ne!(lines.last().unwrap(), "bestmove 0000");
+}
+
+#[test]
+fn searches_to_a_default_depth_without_limits() {
+    let lines = run_commands("position startpos\ngo\n");
+
+    assert!(lines.iter().any(|line| line.starts_with("info depth 6 ")));
+    assert!(!lines.iter().any(|line| line.starts_with("info depth 7 ")));
+    assert!(lines.last().unwrap().starts_with("bestmove "));
+}
+
+#[test]
+fn reports_no_move_when_checkmated() {
+    let lines = run_commands("position startpos moves f2f3 e7e5 g2g4 d8h4\ngo depth 1\n");
+
+    assert_eq!(lines.last().unwrap(), "bestmove 0000");
+}
+
+#[test]
+fn sets_the_hash_size() {
+    let lines = run_commands("uci\nsetoption name Hash value 1\nisready\ngo depth 3\n");
+
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("option name Hash type spin")));
+    assert!(!lines.iter().any(|line| line.starts_with("info string")));
+    assert!(lines.iter().any(|line| line.contains(" hashfull ")));
+    assert!(lines.last().unwrap().starts_with("bestmove "));
+}
+
+#[test]
+fn castles_onto_the_rook_in_chess960() {
+    let moves = "position startpos moves e2e4 e7e5 g1f3 b8c6 f1c4 g8f6 e1h1\ngo depth 1\n";
+
+    let lines = run_commands(&format!(
+        "setoption name UCI_Chess960 value true\n{}",
+        moves
+    ));
+    assert!(!lines.iter().any(|line| line.starts_with("info string")));
+
+    // Without the option, the King cannot move onto its own Rook
+    let lines = run_commands(moves);
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("info string Illegal move e1h1")));
+}
+
+#[test]
+fn replays_moves_after_a_draw_by_rule() {
+    // The bare Kings are already a draw, which does not stop the GUI from sending moves
+    let lines = run_commands("position fen 4k3/8/8/8/8/8/8/4K3 w - - 0 1 moves e1e2\ngo depth 1\n");
+
+    assert!(!lines.iter().any(|line| line.starts_with("info string")));
+    assert!(lines.last().unwrap().starts_with("bestmove e8"));
+}
+
+#[test]
+fn resets_the_position_after_an_invalid_one() {
+    let lines =
+        run_commands("position startpos moves e2e4\nposition startpos moves e2e5\ngo depth 1\n");
+
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("info string Illegal move e2e5")));
+
+    // The search is from the starting position rather than the position after e2e4, so White moves
+    let best_move = lines.last().unwrap().strip_prefix("bestmove ").unwrap();
+    assert!(matches!(&best_move[1..2], "1" | "2"));
+}
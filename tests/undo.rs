@@ -0,0 +1,84 @@
+use ltchess::ChessGamestate;
+use ltchess::ChessMove;
+
+fn perform(gamestate: &mut ChessGamestate, encoded_move: &str) {
+    gamestate
+        .perform_move(&encoded_move.parse().unwrap())
+        .unwrap();
+}
+
+// Gets the parts of the gamestate which an undone or redone move must restore exactly
+fn snapshot(gamestate: &ChessGamestate) -> (String, u64) {
+    (gamestate.to_fen(), gamestate.hash())
+}
+
+#[test]
+fn undoes_and_redoes_every_kind_of_move() {
+    // En passant, castling on both sides, and a promotion which captures
+    let mut gamestate =
+        ChessGamestate::from_fen("r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1").unwrap();
+    let moves = ["e5d6", "e8g8", "b7a8q", "f8a8", "e1c1"];
+
+    let mut snapshots = vec![snapshot(&gamestate)];
+
+    for encoded_move in moves {
+        perform(&mut gamestate, encoded_move);
+        snapshots.push(snapshot(&gamestate));
+    }
+
+    assert_eq!(gamestate.to_fen(), "r5k1/8/3P4/8/8/8/8/2KR3R b - - 1 3");
+
+    for (index, encoded_move) in moves.iter().enumerate().rev() {
+        assert_eq!(gamestate.undo().unwrap().to_string(), *encoded_move);
+        assert_eq!(snapshot(&gamestate), snapshots[index]);
+        assert_eq!(gamestate.hash(), gamestate.compute_hash());
+    }
+
+    assert_eq!(gamestate.undo(), None);
+
+    for (index, encoded_move) in moves.iter().enumerate() {
+        assert_eq!(gamestate.redo().unwrap().to_string(), *encoded_move);
+        assert_eq!(snapshot(&gamestate), snapshots[index + 1]);
+    }
+
+    assert_eq!(gamestate.redo(), None);
+}
+
+#[test]
+fn restores_pawn_double_moves() {
+    let mut gamestate = ChessGamestate::new();
+
+    perform(&mut gamestate, "e2 e4");
+    gamestate.undo();
+
+    // The Pawn is back on its starting tile unmoved, so it can still move two tiles
+    perform(&mut gamestate, "e2 e4");
+    assert_eq!(
+        gamestate.to_fen(),
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+    );
+}
+
+#[test]
+fn forgets_undone_moves_after_a_new_move() {
+    let mut gamestate = ChessGamestate::new();
+
+    perform(&mut gamestate, "e2 e4");
+    gamestate.undo();
+    perform(&mut gamestate, "d2 d4");
+
+    assert_eq!(gamestate.redo(), None);
+    assert_eq!(
+        gamestate
+            .history()
+            .iter()
+            .map(|record| record.performed_move)
+            .collect::<Vec<ChessMove>>(),
+        ["d2 d4".parse().unwrap()]
+    );
+
+    // Unmaking a move does not allow it to be redone
+    assert!(gamestate.unmake_move().is_some());
+    assert_eq!(gamestate.redo(), None);
+    assert_eq!(gamestate.to_fen(), ChessGamestate::new().to_fen());
+}
@@ -0,0 +1,113 @@
+use ltchess::ChessXboard;
+
+mod common;
+
+// Runs the given commands through the XBoard front end, returning every line it sent back
+fn run_commands(commands: &str) -> Vec<String> {
+    common::run_commands(commands, |output, input| {
+        ChessXboard::new(output).run(input)
+    })
+}
+
+#[test]
+fn negotiates_features() {
+    let lines = run_commands("xboard\nprotover 2\nping 7\n");
+
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("feature "));
+    assert!(lines[0].contains("setboard=1"));
+    assert!(lines[0].contains("usermove=1"));
+    assert!(lines[0].contains("san=0"));
+    assert!(lines[0].contains("memory=1"));
+    assert!(lines[0].ends_with("done=1"));
+    assert_eq!(lines[1], "pong 7");
+}
+
+#[test]
+fn plays_and_reports_checkmate() {
+    let lines = run_commands(
+        "xboard\nprotover 2\nforce\nsetboard r1bqkbnr/pppp1ppp/2n5/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 0 1\nsd 3\ngo\n",
+    );
+
+    assert_eq!(lines[1], "move h5f7");
+    assert_eq!(lines[2], "1-0 {White mates}");
+}
+
+#[test]
+fn replies_to_the_opponents_move() {
+    let lines = run_commands("xboard\nprotover 2\nnew\nsd 2\nusermove e2e4\n");
+
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].starts_with("move "));
+}
+
+#[test]
+fn accepts_san_moves() {
+    let lines = run_commands("xboard\nprotover 2\nnew\nforce\nusermove Nf3\nusermove d5\n");
+
+    assert_eq!(lines.len(), 1);
+}
+
+#[test]
+fn records_moves_without_replying_in_force_mode() {
+    let lines = run_commands(
+        "xboard\nprotover 2\nnew\nforce\nusermove e2e4\nusermove e7e5\nremove\nusermove e2e4\nusermove e2e5\n",
+    );
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1], "Illegal move: e2e5");
+}
+
+#[test]
+fn takes_back_moves() {
+    let lines = run_commands(
+        "xboard\nprotover 2\nnew\nforce\nusermove e2e4\nundo\nusermove e7e5\nusermove e2e4\nusermove e7e5\n",
+    );
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1], "Illegal move: e7e5");
+}
+
+#[test]
+fn reports_stalemate() {
+    let lines = run_commands(
+        "xboard\nprotover 2\nforce\nsetboard k7/8/8/2Q5/8/8/8/7K w - - 0 1\nusermove c5b6\n",
+    );
+
+    assert_eq!(lines.last().unwrap(), "1/2-1/2 {Stalemate}");
+}
+
+#[test]
+fn records_moves_after_a_draw_by_rule() {
+    // The bare Kings are already a draw, but the game can still be replayed in force mode
+    let lines = run_commands(
+        "xboard\nprotover 2\nforce\nsetboard 4k3/8/8/8/8/8/8/4K3 w - - 0 1\nusermove e1e2\nusermove e8e7\n",
+    );
+
+    assert_eq!(lines.len(), 1);
+}
+
+#[test]
+fn searches_within_the_time_control() {
+    let lines = run_commands("xboard\nprotover 2\nnew\nlevel 40 0:02 0\ntime 100\nusermove d2d4\n");
+
+    assert!(lines.last().unwrap().starts_with("move "));
+}
+
+#[test]
+fn rejects_unknown_commands() {
+    let lines = run_commands("xboard\nprotover 2\nfrobnicate\n");
+
+    assert_eq!(lines[1], "Error (unknown command): frobnicate");
+}
+
+#[test]
+fn plays_fischer_random() {
+    let lines = run_commands(
+        "xboard\nprotover 2\nnew\nvariant fischerandom\nsetboard r3k2r/8/8/8/8/8/8/1R3KR1 w GBha - 0 1\nforce\nusermove O-O\nusermove e8a8\nvariant crazyhouse\n",
+    );
+
+    assert!(lines[0].contains("variants=\"normal,fischerandom\""));
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1], "Error (unsupported variant): variant crazyhouse");
+}
@@ -0,0 +1,93 @@
+// Reference keys from the Polyglot opening book format specification, http://hgm.nubati.net/book_format.html
+
+use ltchess::ChessGamestate;
+
+const REFERENCE_KEYS: [(&str, u64); 9] = [
+    ("", 0x463b96181691fc9c),
+    ("e2e4", 0x823c9b50fd114196),
+    ("e2e4 d7d5", 0x0756b94461c50fb0),
+    ("e2e4 d7d5 e4e5", 0x662fafb965db29d4),
+    ("e2e4 d7d5 e4e5 f7f5", 0x22a48b5a8e47ff78),
+    ("e2e4 d7d5 e4e5 f7f5 e1e2", 0x652a607ca3f242c1),
+    ("e2e4 d7d5 e4e5 f7f5 e1e2 e8f7", 0x00fdd303c946bdd9),
+    ("a2a4 b7b5 h2h4 b5b4 c2c4", 0x3c8123ea7b067637),
+    ("a2a4 b7b5 h2h4 b5b4 c2c4 b4c3 a1a3", 0x5c3f9b829b279560),
+];
+
+fn play(moves: &str) -> ChessGamestate {
+    let mut gamestate = ChessGamestate::new();
+
+    for encoded_move in moves.split_whitespace() {
+        gamestate
+            .perform_move(&encoded_move.parse().unwrap())
+            .unwrap();
+    }
+
+    gamestate
+}
+
+#[test]
+fn matches_polyglot_keys() {
+    for (moves, expected_key) in REFERENCE_KEYS {
+        let gamestate = play(moves);
+
+        assert_eq!(gamestate.hash(), expected_key, "after \"{}\"", moves);
+        assert_eq!(
+            gamestate.compute_hash(),
+            expected_key,
+            "after \"{}\"",
+            moves
+        );
+
+        // A position loaded from FEN has the same key as the one reached by playing the moves
+        assert_eq!(
+            ChessGamestate::from_fen(&gamestate.to_fen())
+                .unwrap()
+                .hash(),
+            expected_key,
+            "after \"{}\"",
+            moves
+        );
+    }
+}
+
+#[test]
+fn restores_keys_when_moves_are_undone() {
+    let mut gamestate = play("a2a4 b7b5 h2h4 b5b4 c2c4 b4c3 a1a3");
+
+    for (moves, expected_key) in REFERENCE_KEYS[7..].iter().rev() {
+        assert_eq!(gamestate.hash(), *expected_key, "after \"{}\"", moves);
+        gamestate.undo();
+        gamestate.undo();
+    }
+}
+
+#[test]
+fn tells_positions_apart() {
+    // The same pieces with different castling rights, turn colors or en passant tiles are different positions
+    let fens = [
+        "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1",
+        "r3k2r/8/8/8/8/8/8/R3K2R w Kkq - 0 1",
+        "r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1",
+        "r3k2r/8/8/8/8/8/8/R3K2R w - - 0 1",
+    ];
+
+    let mut keys: Vec<u64> = fens
+        .iter()
+        .map(|fen| ChessGamestate::from_fen(fen).unwrap().hash())
+        .collect();
+
+    keys.sort();
+    keys.dedup();
+    assert_eq!(keys.len(), fens.len());
+
+    // An en passant tile only changes the key when a Pawn could capture on it
+    let without_capture = ChessGamestate::from_fen("4k3/8/8/3p4/8/8/8/4K3 w - d6 0 1").unwrap();
+    let without_tile = ChessGamestate::from_fen("4k3/8/8/3p4/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(without_capture.hash(), without_tile.hash());
+
+    let with_capture = ChessGamestate::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+    let with_capture_without_tile =
+        ChessGamestate::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_ne!(with_capture.hash(), with_capture_without_tile.hash());
+}